@@ -26,6 +26,9 @@ contract_addr() {
 # base58_to_hex INPUT
 # Decodes a base58 string to hex. Uses bc for big number arithmetic.
 # Example: base58_to_hex "QmXyz..." -> "1220abcd..."
+# The Rust test crate decodes CIDs natively (tests/src/ids.rs, pinned to the
+# same CHEATSHEET.md vector); this copy stays for scripts/publish-subgraph.sh,
+# which runs on the host and in containers without the crate.
 base58_to_hex() {
   # Disable trace to avoid noisy output
   { _xtrace_was_set=1; set +x; } 2>/dev/null || _xtrace_was_set=0
@@ -0,0 +1,51 @@
+;; Mapping of the fixture subgraph (src/subgraph_deploy.rs).
+;;
+;; graph-node only needs the AssemblyScript runtime exports it calls to pass
+;; trigger data in (`memory`, `allocate`, `id_of_type`) and the handler named
+;; in the manifest. `allocate` is a bump allocator: a fresh instance is
+;; created per trigger, so nothing is ever freed. The handler ignores its
+;; event, and no entities are written.
+;;
+;; Rebuild with: wat2wasm mapping.wat -o mapping.wasm
+(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 8))
+
+  (func (export "allocate") (param $size i32) (result i32)
+    (local $ptr i32)
+    global.get $heap
+    local.set $ptr
+    ;; heap = (heap + size + 15) & ~15
+    global.get $heap
+    local.get $size
+    i32.add
+    i32.const 15
+    i32.add
+    i32.const -16
+    i32.and
+    global.set $heap
+    ;; grow memory to cover the new heap end
+    global.get $heap
+    memory.size
+    i32.const 16
+    i32.shl
+    i32.gt_u
+    if
+      global.get $heap
+      memory.size
+      i32.const 16
+      i32.shl
+      i32.sub
+      i32.const 65535
+      i32.add
+      i32.const 16
+      i32.shr_u
+      memory.grow
+      drop
+    end
+    local.get $ptr)
+
+  (func (export "id_of_type") (param i32) (result i32)
+    i32.const 0)
+
+  (func (export "handleFixture") (param i32)))
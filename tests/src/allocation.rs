@@ -0,0 +1,407 @@
+//! Direct SubgraphService allocation operations, bypassing indexer-agent.
+//!
+//! The agent normally generates allocation keys, signs the allocation-ID
+//! proof and calls `startService`/`stopService`. Doing it here gives tests
+//! full control over allocation IDs, amounts and timing, and keeps
+//! contract-level tests independent of agent bugs:
+//!
+//!   - `AllocationKey`: a fresh allocation key; its address is the allocation ID
+//!   - proof: EIP-712 `AllocationIdProof(indexer, allocationId)` signed by the
+//!     allocation key in the SubgraphService domain
+//!   - `start_service` / `stop_service`: open/close as the indexer or an
+//!     authorized operator (`HorizonStaking.setOperator`)
+//!
+//! The management API has no resize mutation either, so `resize_allocation`
+//! calls `SubgraphService.resizeAllocation` and reads the on-chain allocation
+//! state around it. The state carries the rewards snapshot
+//! (`accRewardsPerAllocatedToken`, `accRewardsPending`) that RewardsConditions
+//! Cycle 5 checks.
+
+use alloy_primitives::{Bytes, U256, hex};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{Eip712Domain, SolStruct, SolType, SolValue, eip712_domain, sol};
+use anyhow::{Context, Result};
+
+use crate::attestation::Attestation;
+use crate::{Address, B256, DeploymentId, Grt, TestNetwork};
+
+sol! {
+    /// Proof that the indexer holds the allocation key.
+    struct AllocationIdProof {
+        address indexer;
+        address allocationId;
+    }
+
+    /// `Allocation.State` as returned by `SubgraphService.getAllocation`.
+    struct AllocationState {
+        address indexer;
+        bytes32 subgraphDeploymentId;
+        uint256 tokens;
+        uint256 createdAt;
+        uint256 closedAt;
+        uint256 lastPOIPresentedAt;
+        uint256 accRewardsPerAllocatedToken;
+        uint256 accRewardsPending;
+        uint256 createdAtEpoch;
+    }
+}
+
+/// The SubgraphService EIP-712 domain used for allocation-ID proofs.
+pub fn allocation_proof_domain(chain_id: u64, subgraph_service: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: "SubgraphService",
+        version: "1.0",
+        chain_id: chain_id,
+        verifying_contract: subgraph_service,
+    }
+}
+
+/// A freshly generated allocation key. Its address is the allocation ID.
+#[derive(Debug, Clone)]
+pub struct AllocationKey {
+    signer: PrivateKeySigner,
+}
+
+impl AllocationKey {
+    pub fn random() -> Self {
+        Self {
+            signer: PrivateKeySigner::random(),
+        }
+    }
+
+    /// The allocation ID.
+    pub fn id(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// EIP-712 digest of the allocation-ID proof for `indexer`.
+    pub fn proof_hash(&self, domain: &Eip712Domain, indexer: Address) -> B256 {
+        AllocationIdProof {
+            indexer,
+            allocationId: self.id(),
+        }
+        .eip712_signing_hash(domain)
+    }
+
+    /// Sign a query attestation with the allocation key, as indexer-service
+    /// does for responses served from this allocation.
+    pub fn sign_attestation(
+        &self,
+        domain: &Eip712Domain,
+        request: &[u8],
+        response: &[u8],
+        deployment: DeploymentId,
+    ) -> Result<Attestation> {
+        Attestation::sign(&self.signer, domain, request, response, deployment)
+    }
+
+    /// The 65-byte proof `startService` expects.
+    pub fn proof(&self, domain: &Eip712Domain, indexer: Address) -> Result<Bytes> {
+        let signature = self
+            .signer
+            .sign_hash_sync(&self.proof_hash(domain, indexer))
+            .context("signing allocation-ID proof")?;
+        Ok(Bytes::from(signature.as_bytes().to_vec()))
+    }
+}
+
+/// `startService` data: `abi.encode(deploymentId, tokens, allocationId, proof)`.
+pub fn start_service_data(
+    deployment: DeploymentId,
+    tokens: Grt,
+    allocation_id: Address,
+    proof: &Bytes,
+) -> Bytes {
+    (
+        deployment.bytes32(),
+        U256::from(tokens.wei()),
+        allocation_id,
+        proof.clone(),
+    )
+        .abi_encode_params()
+        .into()
+}
+
+/// An allocation as stored by SubgraphService.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnChainAllocation {
+    pub id: Address,
+    pub indexer: Address,
+    pub deployment: DeploymentId,
+    pub tokens: Grt,
+    /// Timestamps (seconds); `closed_at` is 0 while open.
+    pub created_at: u64,
+    pub closed_at: u64,
+    pub last_poi_presented_at: u64,
+    /// Rewards-per-token snapshot, a 1e18 fixed-point ratio (see
+    /// `rewards_acc_per_allocated_token`).
+    pub acc_rewards_per_allocated_token: u128,
+    /// Rewards accrued before the last resize, claimable at the next POI.
+    pub acc_rewards_pending: Grt,
+    pub created_at_epoch: u64,
+}
+
+impl OnChainAllocation {
+    fn from_state(id: Address, s: AllocationState) -> Self {
+        Self {
+            id,
+            indexer: s.indexer,
+            deployment: DeploymentId::new(s.subgraphDeploymentId),
+            tokens: Grt::from_wei(s.tokens.saturating_to()),
+            created_at: s.createdAt.saturating_to(),
+            closed_at: s.closedAt.saturating_to(),
+            last_poi_presented_at: s.lastPOIPresentedAt.saturating_to(),
+            acc_rewards_per_allocated_token: s.accRewardsPerAllocatedToken.saturating_to(),
+            acc_rewards_pending: Grt::from_wei(s.accRewardsPending.saturating_to()),
+            created_at_epoch: s.createdAtEpoch.saturating_to(),
+        }
+    }
+
+    /// Decode the raw `getAllocation` return data.
+    pub fn decode(id: Address, data: &[u8]) -> Result<Self> {
+        let state =
+            <AllocationState as SolType>::abi_decode(data).context("decoding Allocation.State")?;
+        Ok(Self::from_state(id, state))
+    }
+
+    pub fn exists(&self) -> bool {
+        self.created_at != 0
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.exists() && self.closed_at == 0
+    }
+}
+
+/// Outcome of `resize_allocation`: the allocation read before and after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocationResize {
+    pub before: OnChainAllocation,
+    pub after: OnChainAllocation,
+}
+
+impl AllocationResize {
+    pub fn old_tokens(&self) -> Grt {
+        self.before.tokens
+    }
+
+    pub fn new_tokens(&self) -> Grt {
+        self.after.tokens
+    }
+
+    /// Rewards moved into `accRewardsPending` by the resize. Zero when the
+    /// allocation was stale and its rewards were reclaimed instead.
+    pub fn rewards_snapshotted(&self) -> Grt {
+        self.after
+            .acc_rewards_pending
+            .saturating_sub(self.before.acc_rewards_pending)
+    }
+}
+
+impl TestNetwork {
+    /// The EIP-712 domain for allocation-ID proofs on the local SubgraphService.
+    pub fn allocation_proof_domain(&self) -> Eip712Domain {
+        allocation_proof_domain(self.chain_id, self.contracts.subgraph_service)
+    }
+
+    /// Open an allocation of `tokens` on `deployment` with a crate-generated
+    /// key, as the indexer (RECEIVER_SECRET).
+    /// Emulates the agent's `createAllocation` without the agent.
+    pub fn start_service(
+        &self,
+        deployment: DeploymentId,
+        tokens: Grt,
+        key: &AllocationKey,
+    ) -> Result<OnChainAllocation> {
+        self.start_service_as(&self.receiver_secret, deployment, tokens, key)
+    }
+
+    /// Like `start_service`, sent by `sender` (the indexer or an operator
+    /// authorized via `HorizonStaking.setOperator`). The proof is always for
+    /// the indexer.
+    pub fn start_service_as(
+        &self,
+        sender: &str,
+        deployment: DeploymentId,
+        tokens: Grt,
+        key: &AllocationKey,
+    ) -> Result<OnChainAllocation> {
+        let proof = key.proof(&self.allocation_proof_domain(), self.indexer_address)?;
+        let data = start_service_data(deployment, tokens, key.id(), &proof);
+        self.cast_send_as(
+            sender,
+            self.contracts.subgraph_service,
+            "startService(address,bytes)",
+            &[&self.indexer_address.to_string(), &data.to_string()],
+        )
+        .with_context(|| format!("starting allocation {} on {deployment}", key.id()))?;
+        self.subgraph_service_allocation(key.id())
+    }
+
+    /// `startService` with an arbitrary allocation ID and proof, for negative
+    /// tests. Returns `Ok(false)` if the call reverts.
+    pub fn start_service_with_proof(
+        &self,
+        sender: &str,
+        deployment: DeploymentId,
+        tokens: Grt,
+        allocation_id: Address,
+        proof: &Bytes,
+    ) -> Result<bool> {
+        let data = start_service_data(deployment, tokens, allocation_id, proof);
+        self.cast_send_may_revert(
+            sender,
+            self.contracts.subgraph_service,
+            "startService(address,bytes)",
+            &[&self.indexer_address.to_string(), &data.to_string()],
+        )
+    }
+
+    /// Close an allocation via `SubgraphService.stopService` as the indexer.
+    ///
+    /// Unlike the agent's close, no POI is presented and nothing is collected:
+    /// pending rewards are reclaimed (CLOSE_ALLOCATION). Use
+    /// `collect_indexing_rewards` first to claim them.
+    pub fn stop_service(&self, allocation: Address) -> Result<OnChainAllocation> {
+        self.stop_service_as(&self.receiver_secret, allocation)
+    }
+
+    /// Like `stop_service`, sent by `sender` (the indexer or an operator).
+    pub fn stop_service_as(&self, sender: &str, allocation: Address) -> Result<OnChainAllocation> {
+        let data = Bytes::from(allocation.abi_encode());
+        self.cast_send_as(
+            sender,
+            self.contracts.subgraph_service,
+            "stopService(address,bytes)",
+            &[&self.indexer_address.to_string(), &data.to_string()],
+        )
+        .with_context(|| format!("stopping allocation {allocation}"))?;
+        self.subgraph_service_allocation(allocation)
+    }
+
+    /// The allocation-ID proof digest as computed by the contract
+    /// (`SubgraphService.encodeAllocationProof`).
+    pub fn encode_allocation_proof(&self, allocation_id: Address) -> Result<B256> {
+        let output = self.cast_call(
+            self.contracts.subgraph_service,
+            "encodeAllocationProof(address,address)(bytes32)",
+            &[
+                &self.indexer_address.to_string(),
+                &allocation_id.to_string(),
+            ],
+        )?;
+        output
+            .trim()
+            .parse()
+            .context("parsing encodeAllocationProof")
+    }
+
+    /// Read an allocation from `SubgraphService.getAllocation`.
+    /// Unknown IDs decode to a zeroed allocation (`exists()` is false).
+    pub fn subgraph_service_allocation(&self, id: Address) -> Result<OnChainAllocation> {
+        let output = self.cast_call(
+            self.contracts.subgraph_service,
+            "getAllocation(address)",
+            &[&id.to_string()],
+        )?;
+        let data = hex::decode(output.trim()).context("decoding getAllocation output")?;
+        OnChainAllocation::decode(id, &data)
+    }
+
+    /// Resize an open allocation to `tokens` via
+    /// `SubgraphService.resizeAllocation(indexer, allocationId, tokens)`.
+    /// RewardsConditionsTestPlan Cycle 5.
+    ///
+    /// Called as the indexer (RECEIVER_SECRET) because of
+    /// `onlyAuthorizedForProvision`. Pending rewards are snapshotted into
+    /// `accRewardsPending`, or reclaimed as STALE_POI if the allocation is stale.
+    pub fn resize_allocation(&self, allocation: Address, tokens: Grt) -> Result<AllocationResize> {
+        let before = self.subgraph_service_allocation(allocation)?;
+        anyhow::ensure!(before.is_open(), "allocation {allocation} is not open");
+        self.cast_send_as_indexer(
+            self.contracts.subgraph_service,
+            "resizeAllocation(address,address,uint256)",
+            &[
+                &self.indexer_address.to_string(),
+                &allocation.to_string(),
+                &tokens.to_wei_string(),
+            ],
+        )
+        .with_context(|| format!("resizing {allocation} to {tokens} GRT"))?;
+        let after = self.subgraph_service_allocation(allocation)?;
+        Ok(AllocationResize { before, after })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Signature;
+
+    #[test]
+    fn decodes_allocation_state() {
+        let deployment = B256::repeat_byte(0x11);
+        let data = (
+            Address::repeat_byte(0x22),
+            deployment,
+            U256::from(5_000_000_000_000_000_000u128),
+            U256::from(1_700_000_000u64),
+            U256::ZERO,
+            U256::from(1_700_000_100u64),
+            U256::from(42u64),
+            U256::from(7u64),
+            U256::from(3u64),
+        )
+            .abi_encode_params();
+        let alloc = OnChainAllocation::decode(Address::repeat_byte(0x33), &data).unwrap();
+        assert_eq!(alloc.indexer, Address::repeat_byte(0x22));
+        assert_eq!(alloc.deployment, DeploymentId::new(deployment));
+        assert_eq!(alloc.tokens, Grt::from_grt(5));
+        assert!(alloc.is_open());
+        assert_eq!(alloc.acc_rewards_per_allocated_token, 42);
+        assert_eq!(alloc.acc_rewards_pending, Grt::from_wei(7));
+        assert_eq!(alloc.created_at_epoch, 3);
+
+        let missing = OnChainAllocation::decode(Address::ZERO, &[0u8; 9 * 32]).unwrap();
+        assert!(!missing.exists());
+    }
+
+    #[test]
+    fn proof_recovers_to_allocation_id() {
+        let key = AllocationKey::random();
+        let domain = allocation_proof_domain(1337, Address::repeat_byte(0x42));
+        let indexer = Address::repeat_byte(0x01);
+        let proof = key.proof(&domain, indexer).unwrap();
+        assert_eq!(proof.len(), 65);
+        let signature = Signature::try_from(proof.as_ref()).unwrap();
+        let recovered = signature
+            .recover_address_from_prehash(&key.proof_hash(&domain, indexer))
+            .unwrap();
+        assert_eq!(recovered, key.id());
+
+        // Bound to the indexer
+        assert_ne!(
+            key.proof_hash(&domain, indexer),
+            key.proof_hash(&domain, Address::repeat_byte(0x02))
+        );
+    }
+
+    #[test]
+    fn encodes_start_service_data() {
+        let deployment = DeploymentId::new(B256::repeat_byte(0x11));
+        let proof = Bytes::from(vec![0xab; 65]);
+        let data = start_service_data(
+            deployment,
+            Grt::from_grt(1),
+            Address::repeat_byte(0x22),
+            &proof,
+        );
+        let (id, tokens, alloc, decoded): (B256, U256, Address, Bytes) =
+            SolValue::abi_decode_params(&data).unwrap();
+        assert_eq!(id, deployment.bytes32());
+        assert_eq!(tokens, U256::from(Grt::from_grt(1).wei()));
+        assert_eq!(alloc, Address::repeat_byte(0x22));
+        assert_eq!(decoded, proof);
+    }
+}
@@ -0,0 +1,433 @@
+//! Query response attestations.
+//!
+//! Indexers sign every paid query response with their allocation key. The
+//! gateway forwards the signature in the `graph-attestation` response header:
+//!   `{"requestCID", "responseCID", "subgraphDeploymentID", "r", "s", "v"}`
+//!
+//! The signed message is an EIP-712 `Receipt` in the DisputeManager domain, so
+//! the recovered signer is the allocation ID that served the query.
+//! indexer-service returns the same attestation in its response body:
+//!   `{"graphQLResponse": "<json string>", "attestation": {...} | null}`
+//!
+//! `verify_attestation` checks the signer against the network subgraph: it
+//! must be an active allocation on the attested deployment. After allocation
+//! rotation an indexer that keeps signing with the old allocation key fails
+//! with `AttestationMismatch::InactiveAllocation` (signer drift). Gateway
+//! responses are checked against `gateway_deployment`, the queried
+//! subgraph's current version.
+//!
+//! Request/response CIDs (keccak256 of the bodies) can only be recomputed for
+//! direct indexer-service queries: the gateway rewrites the query it forwards.
+
+use alloy_primitives::{Signature, b256, keccak256};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{Eip712Domain, SolStruct, eip712_domain, sol};
+use anyhow::{Context, Result};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+use crate::gateway::GatewayResponse;
+use crate::{Address, B256, DeploymentId, TestNetwork};
+
+/// Response header carrying the attestation JSON.
+pub const ATTESTATION_HEADER: &str = "graph-attestation";
+
+/// Length of an encoded attestation (5 × bytes32 + v).
+pub const ATTESTATION_SIZE: usize = 161;
+
+/// Fixed salt of the attestation EIP-712 domain (DisputeManager).
+const DOMAIN_SALT: B256 =
+    b256!("0xa070ffb1cd7409649bf77822cce74495468e06dbfaef09556838bf188679b9c2");
+
+sol! {
+    /// EIP-712 message signed by the allocation key.
+    struct Receipt {
+        bytes32 requestCID;
+        bytes32 responseCID;
+        bytes32 subgraphDeploymentID;
+    }
+}
+
+/// A signed attestation as returned by the gateway or indexer-service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Attestation {
+    /// keccak256 of the request body.
+    #[serde(rename = "requestCID")]
+    pub request_cid: B256,
+    /// keccak256 of the response body.
+    #[serde(rename = "responseCID")]
+    pub response_cid: B256,
+    #[serde(rename = "subgraphDeploymentID")]
+    pub deployment: B256,
+    pub r: B256,
+    pub s: B256,
+    pub v: u8,
+}
+
+impl Attestation {
+    /// Extract the attestation from response headers.
+    /// Returns `None` when the header is absent or empty (free/unattested queries).
+    pub fn from_headers(headers: &HeaderMap) -> Result<Option<Self>> {
+        let Some(value) = headers.get(ATTESTATION_HEADER) else {
+            return Ok(None);
+        };
+        let value = value
+            .to_str()
+            .context("attestation header is not valid UTF-8")?;
+        if value.trim().is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_str(value)
+            .map(Some)
+            .with_context(|| format!("parsing attestation {value}"))
+    }
+
+    /// Extract the attestation from an indexer-service response body.
+    pub fn from_indexer_response(body: &Value) -> Result<Option<Self>> {
+        match &body["attestation"] {
+            Value::Null => Ok(None),
+            attestation => serde_json::from_value(attestation.clone())
+                .map(Some)
+                .context("parsing indexer-service attestation"),
+        }
+    }
+
+    pub fn deployment_id(&self) -> DeploymentId {
+        DeploymentId::new(self.deployment)
+    }
+
+    /// EIP-712 digest of the attested receipt.
+    pub fn signing_hash(&self, domain: &Eip712Domain) -> B256 {
+        Receipt {
+            requestCID: self.request_cid,
+            responseCID: self.response_cid,
+            subgraphDeploymentID: self.deployment,
+        }
+        .eip712_signing_hash(domain)
+    }
+
+    /// Recover the signer (the allocation ID) from the signature.
+    pub fn recover_signer(&self, domain: &Eip712Domain) -> Result<Address> {
+        let parity = match self.v {
+            0 | 27 => false,
+            1 | 28 => true,
+            v => anyhow::bail!("invalid attestation v value {v}"),
+        };
+        let signature = Signature::from_scalars_and_parity(self.r, self.s, parity);
+        signature
+            .recover_address_from_prehash(&self.signing_hash(domain))
+            .context("recovering attestation signer")
+    }
+
+    /// Sign `request`/`response` for `deployment` with an allocation key, as
+    /// indexer-service does. Used to build (conflicting) attestations for
+    /// query disputes.
+    pub fn sign(
+        allocation_key: &PrivateKeySigner,
+        domain: &Eip712Domain,
+        request: &[u8],
+        response: &[u8],
+        deployment: DeploymentId,
+    ) -> Result<Self> {
+        let mut attestation = Attestation {
+            request_cid: keccak256(request),
+            response_cid: keccak256(response),
+            deployment: deployment.bytes32(),
+            r: B256::ZERO,
+            s: B256::ZERO,
+            v: 0,
+        };
+        let signature = allocation_key
+            .sign_hash_sync(&attestation.signing_hash(domain))
+            .context("signing attestation")?;
+        attestation.r = signature.r().into();
+        attestation.s = signature.s().into();
+        attestation.v = 27 + signature.v() as u8;
+        Ok(attestation)
+    }
+
+    /// The 161-byte encoding DisputeManager parses:
+    /// `requestCID ‖ responseCID ‖ subgraphDeploymentID ‖ r ‖ s ‖ v`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(ATTESTATION_SIZE);
+        for word in [
+            self.request_cid,
+            self.response_cid,
+            self.deployment,
+            self.r,
+            self.s,
+        ] {
+            data.extend_from_slice(word.as_slice());
+        }
+        data.push(self.v);
+        data
+    }
+
+    /// Check the attested CIDs against the exact request and response bodies.
+    pub fn check_payload(
+        &self,
+        request: &[u8],
+        response: &[u8],
+    ) -> Result<(), AttestationMismatch> {
+        let request_cid = keccak256(request);
+        if request_cid != self.request_cid {
+            return Err(AttestationMismatch::RequestCid {
+                attested: self.request_cid,
+                computed: request_cid,
+            });
+        }
+        let response_cid = keccak256(response);
+        if response_cid != self.response_cid {
+            return Err(AttestationMismatch::ResponseCid {
+                attested: self.response_cid,
+                computed: response_cid,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Why an attestation failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttestationMismatch {
+    /// The attestation is for a different deployment than the one queried.
+    Deployment {
+        expected: DeploymentId,
+        attested: DeploymentId,
+    },
+    /// The signer is not an allocation known to the network subgraph.
+    UnknownSigner(Address),
+    /// The signer is a closed allocation (stale signer after rotation).
+    InactiveAllocation {
+        allocation: Address,
+        status: String,
+    },
+    /// The signer allocates to a different deployment.
+    AllocationDeployment {
+        allocation: Address,
+        deployment: DeploymentId,
+    },
+    RequestCid {
+        attested: B256,
+        computed: B256,
+    },
+    ResponseCid {
+        attested: B256,
+        computed: B256,
+    },
+}
+
+impl fmt::Display for AttestationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deployment { expected, attested } => {
+                write!(f, "attested deployment {attested}, expected {expected}")
+            }
+            Self::UnknownSigner(signer) => write!(f, "signer {signer} is not a known allocation"),
+            Self::InactiveAllocation { allocation, status } => {
+                write!(f, "signer allocation {allocation} is {status}, not Active")
+            }
+            Self::AllocationDeployment {
+                allocation,
+                deployment,
+            } => write!(f, "signer allocation {allocation} is on {deployment}"),
+            Self::RequestCid { attested, computed } => {
+                write!(f, "request CID {attested} != keccak256(request) {computed}")
+            }
+            Self::ResponseCid { attested, computed } => {
+                write!(
+                    f,
+                    "response CID {attested} != keccak256(response) {computed}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AttestationMismatch {}
+
+/// An attestation whose signer is an active allocation on its deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifiedAttestation {
+    pub attestation: Attestation,
+    /// The recovered signer, i.e. the allocation ID.
+    pub allocation: Address,
+    pub indexer: Address,
+}
+
+/// The attestation EIP-712 domain for a DisputeManager deployment.
+pub fn attestation_domain(chain_id: u64, dispute_manager: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: "Graph Protocol",
+        version: "0",
+        chain_id: chain_id,
+        verifying_contract: dispute_manager,
+        salt: DOMAIN_SALT,
+    }
+}
+
+impl TestNetwork {
+    /// The attestation EIP-712 domain of the local network's DisputeManager.
+    pub fn attestation_domain(&self) -> Eip712Domain {
+        attestation_domain(self.chain_id, self.contracts.dispute_manager)
+    }
+
+    /// Verify an attestation for a query against `deployment`.
+    ///
+    /// Recovers the signer and requires it to be an active allocation on that
+    /// deployment. Mismatches are returned as `AttestationMismatch` so tests
+    /// can `downcast_ref` to tell signer drift from other failures.
+    pub async fn verify_attestation(
+        &self,
+        attestation: &Attestation,
+        deployment: DeploymentId,
+    ) -> Result<VerifiedAttestation> {
+        if attestation.deployment_id() != deployment {
+            return Err(AttestationMismatch::Deployment {
+                expected: deployment,
+                attested: attestation.deployment_id(),
+            }
+            .into());
+        }
+        let signer = attestation.recover_signer(&self.attestation_domain())?;
+        let alloc = self.query_allocation(signer).await?;
+        if alloc.is_null() {
+            return Err(AttestationMismatch::UnknownSigner(signer).into());
+        }
+        let status = alloc["status"].as_str().unwrap_or_default();
+        if status != "Active" {
+            return Err(AttestationMismatch::InactiveAllocation {
+                allocation: signer,
+                status: status.to_string(),
+            }
+            .into());
+        }
+        let allocated_to: DeploymentId =
+            serde_json::from_value(alloc["subgraphDeployment"]["ipfsHash"].clone())
+                .context("parsing allocation deployment")?;
+        if allocated_to != deployment {
+            return Err(AttestationMismatch::AllocationDeployment {
+                allocation: signer,
+                deployment: allocated_to,
+            }
+            .into());
+        }
+        let indexer = serde_json::from_value(alloc["indexer"]["id"].clone())
+            .context("parsing allocation indexer")?;
+        Ok(VerifiedAttestation {
+            attestation: *attestation,
+            allocation: signer,
+            indexer,
+        })
+    }
+
+    /// Deployment the gateway serves `gateway_query` from: the current
+    /// version of the queried subgraph (`SUBGRAPH` by default), which is what
+    /// gateway attestations must attest to.
+    pub async fn gateway_deployment(&self) -> Result<DeploymentId> {
+        let subgraph = self.subgraph_id;
+        let query = format!(
+            r#"{{ subgraph(id: "{subgraph}") {{
+                active currentVersion {{ subgraphDeployment {{ ipfsHash }} }}
+            }} }}"#
+        );
+        let resp = self.subgraph_query(&query).await?;
+        let indexed = &resp["data"]["subgraph"];
+        anyhow::ensure!(
+            indexed["active"].as_bool() == Some(true),
+            "subgraph {subgraph} has no active version"
+        );
+        serde_json::from_value(indexed["currentVersion"]["subgraphDeployment"]["ipfsHash"].clone())
+            .with_context(|| format!("parsing current deployment of subgraph {subgraph}"))
+    }
+
+    /// Verify the attestation of a gateway response against `deployment`,
+    /// the deployment the gateway should serve the queried subgraph from
+    /// (see `gateway_deployment`).
+    pub async fn verify_gateway_attestation(
+        &self,
+        resp: &GatewayResponse,
+        deployment: DeploymentId,
+    ) -> Result<VerifiedAttestation> {
+        let attestation = resp
+            .attestation
+            .context("gateway response carries no attestation")?;
+        self.verify_attestation(&attestation, deployment).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn parses_gateway_header() {
+        let json = r#"{
+            "requestCID": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "responseCID": "0x2222222222222222222222222222222222222222222222222222222222222222",
+            "subgraphDeploymentID": "0x30bd35d223f4a673b34667502c2ee987df35e81e20bf4454f0d548980632c6f2",
+            "r": "0x3333333333333333333333333333333333333333333333333333333333333333",
+            "s": "0x4444444444444444444444444444444444444444444444444444444444444444",
+            "v": 27
+        }"#;
+        let mut headers = HeaderMap::new();
+        assert_eq!(Attestation::from_headers(&headers).unwrap(), None);
+        headers.insert(ATTESTATION_HEADER, HeaderValue::from_static(""));
+        assert_eq!(Attestation::from_headers(&headers).unwrap(), None);
+        headers.insert(
+            ATTESTATION_HEADER,
+            HeaderValue::from_str(&json.replace(char::is_whitespace, "")).unwrap(),
+        );
+        let attestation = Attestation::from_headers(&headers).unwrap().unwrap();
+        assert_eq!(
+            attestation.deployment_id().ipfs_hash(),
+            "QmRcucmbxAXLaAZkkCR8Bdj1X7QGPLjfRmQ5H6tFhGqiHX"
+        );
+        assert_eq!(attestation.v, 27);
+    }
+
+    /// Sign a receipt like indexer-service does and recover the allocation.
+    #[test]
+    fn recovers_allocation_signer() {
+        let allocation_key = PrivateKeySigner::random();
+        let domain = attestation_domain(1337, Address::repeat_byte(0x11));
+        let request = br#"{"query":"{ _meta { block { number } } }"}"#;
+        let response = br#"{"data":{"_meta":{"block":{"number":1}}}}"#;
+        let deployment = DeploymentId::new(b256!(
+            "0x30bd35d223f4a673b34667502c2ee987df35e81e20bf4454f0d548980632c6f2"
+        ));
+        let attestation =
+            Attestation::sign(&allocation_key, &domain, request, response, deployment).unwrap();
+
+        assert_eq!(
+            attestation.recover_signer(&domain).unwrap(),
+            allocation_key.address()
+        );
+        // A different DisputeManager yields a different signer
+        let other = attestation_domain(1337, Address::repeat_byte(0x22));
+        assert_ne!(
+            attestation.recover_signer(&other).unwrap(),
+            allocation_key.address()
+        );
+        assert_eq!(attestation.check_payload(request, response), Ok(()));
+
+        let encoded = attestation.encode();
+        assert_eq!(encoded.len(), ATTESTATION_SIZE);
+        assert_eq!(&encoded[64..96], deployment.bytes32().as_slice());
+        assert_eq!(encoded[160], attestation.v);
+        assert!(matches!(
+            attestation.check_payload(request, b"{}"),
+            Err(AttestationMismatch::ResponseCid { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_indexer_service_body() {
+        let body = serde_json::json!({ "graphQLResponse": "{}", "attestation": null });
+        assert_eq!(Attestation::from_indexer_response(&body).unwrap(), None);
+    }
+}
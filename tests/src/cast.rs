@@ -3,16 +3,37 @@
 use anyhow::{Context, Result};
 use std::process::Command;
 
-use crate::TestNetwork;
+use crate::reclaim::ReclaimCondition;
+use crate::{Address, B256, DeploymentId, Grt, TestNetwork};
 
 impl TestNetwork {
     /// Read-only contract call via `cast call`.
     /// Returns the raw stdout (decoded return value).
-    pub fn cast_call(&self, to: &str, sig: &str, args: &[&str]) -> Result<String> {
+    pub fn cast_call(&self, to: Address, sig: &str, args: &[&str]) -> Result<String> {
         let mut cmd = Command::new("cast");
         cmd.arg("call")
             .arg(format!("--rpc-url={}", self.rpc_url))
-            .arg(to)
+            .arg(to.to_string())
+            .arg(sig);
+        for arg in args {
+            cmd.arg(arg);
+        }
+        run_command(&mut cmd)
+    }
+
+    /// `cast_call` against the state at the end of `block`.
+    pub fn cast_call_at(
+        &self,
+        block: u64,
+        to: Address,
+        sig: &str,
+        args: &[&str],
+    ) -> Result<String> {
+        let mut cmd = Command::new("cast");
+        cmd.arg("call")
+            .arg(format!("--rpc-url={}", self.rpc_url))
+            .arg(format!("--block={block}"))
+            .arg(to.to_string())
             .arg(sig);
         for arg in args {
             cmd.arg(arg);
@@ -22,13 +43,13 @@ impl TestNetwork {
 
     /// State-changing transaction via `cast send`.
     /// Uses `account0_secret` as the signer. Returns stdout.
-    pub fn cast_send(&self, to: &str, sig: &str, args: &[&str]) -> Result<String> {
+    pub fn cast_send(&self, to: Address, sig: &str, args: &[&str]) -> Result<String> {
         let mut cmd = Command::new("cast");
         cmd.arg("send")
             .arg(format!("--rpc-url={}", self.rpc_url))
             .arg("--confirmations=0")
             .arg(format!("--private-key={}", self.account0_secret))
-            .arg(to)
+            .arg(to.to_string())
             .arg(sig);
         for arg in args {
             cmd.arg(arg);
@@ -37,34 +58,22 @@ impl TestNetwork {
     }
 
     /// Check if an address is eligible via the REO contract.
-    pub fn reo_is_eligible(&self, address: &str) -> Result<bool> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?;
-        let output = self.cast_call(reo, "isEligible(address)(bool)", &[address])?;
+    pub fn reo_is_eligible(&self, address: Address) -> Result<bool> {
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
+        let output = self.cast_call(reo, "isEligible(address)(bool)", &[&address.to_string()])?;
         Ok(output.trim() == "true")
     }
 
     /// Check if eligibility validation is enabled on the REO contract.
     pub fn reo_validation_enabled(&self) -> Result<bool> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?;
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         let output = self.cast_call(reo, "getEligibilityValidation()(bool)", &[])?;
         Ok(output.trim() == "true")
     }
 
     /// Get the last oracle update time from the REO contract.
     pub fn reo_last_oracle_update(&self) -> Result<u64> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?;
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         let output = self.cast_call(reo, "getLastOracleUpdateTime()(uint256)", &[])?;
         cast_parse_uint(&output)
             .parse()
@@ -74,14 +83,9 @@ impl TestNetwork {
     /// Seed the REO lastOracleUpdateTime by calling renewIndexerEligibility with
     /// an empty array. Requires ORACLE_ROLE (account0).
     pub fn reo_seed_oracle_timestamp(&self) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         self.cast_send(
-            &reo,
+            reo,
             "renewIndexerEligibility(address[],bytes)",
             &["[]", "0x"],
         )?;
@@ -89,16 +93,11 @@ impl TestNetwork {
     }
 
     /// Renew eligibility for a specific indexer. Requires ORACLE_ROLE (account0).
-    pub fn reo_renew_indexer(&self, address: &str) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
+    pub fn reo_renew_indexer(&self, address: Address) -> Result<()> {
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         let array = format!("[{address}]");
         self.cast_send(
-            &reo,
+            reo,
             "renewIndexerEligibility(address[],bytes)",
             &[&array, "0x"],
         )?;
@@ -107,11 +106,7 @@ impl TestNetwork {
 
     /// Get the eligibility period (seconds) from the REO contract.
     pub fn reo_eligibility_period(&self) -> Result<u64> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?;
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         let output = self.cast_call(reo, "getEligibilityPeriod()(uint256)", &[])?;
         cast_parse_uint(&output)
             .parse()
@@ -119,13 +114,13 @@ impl TestNetwork {
     }
 
     /// State-changing transaction via `cast send`, signed by an arbitrary private key.
-    pub fn cast_send_as(&self, key: &str, to: &str, sig: &str, args: &[&str]) -> Result<String> {
+    pub fn cast_send_as(&self, key: &str, to: Address, sig: &str, args: &[&str]) -> Result<String> {
         let mut cmd = Command::new("cast");
         cmd.arg("send")
             .arg(format!("--rpc-url={}", self.rpc_url))
             .arg("--confirmations=0")
             .arg(format!("--private-key={key}"))
-            .arg(to)
+            .arg(to.to_string())
             .arg(sig);
         for arg in args {
             cmd.arg(arg);
@@ -137,7 +132,7 @@ impl TestNetwork {
     pub fn cast_send_may_revert(
         &self,
         key: &str,
-        to: &str,
+        to: Address,
         sig: &str,
         args: &[&str],
     ) -> Result<bool> {
@@ -156,13 +151,13 @@ impl TestNetwork {
 
     /// State-changing transaction via `cast send`, signed by `receiver_secret` (the indexer).
     /// Needed for operations that require `onlyAuthorizedForProvision`.
-    pub fn cast_send_as_indexer(&self, to: &str, sig: &str, args: &[&str]) -> Result<String> {
+    pub fn cast_send_as_indexer(&self, to: Address, sig: &str, args: &[&str]) -> Result<String> {
         let mut cmd = Command::new("cast");
         cmd.arg("send")
             .arg(format!("--rpc-url={}", self.rpc_url))
             .arg("--confirmations=0")
             .arg(format!("--private-key={}", self.receiver_secret))
-            .arg(to)
+            .arg(to.to_string())
             .arg(sig);
         for arg in args {
             cmd.arg(arg);
@@ -178,8 +173,8 @@ impl TestNetwork {
     ///
     /// Must be called BEFORE closing the allocation.
     /// Requires calling as the indexer (RECEIVER_SECRET) due to `onlyAuthorizedForProvision`.
-    pub fn collect_indexing_rewards(&self, allocation_id: &str) -> Result<String> {
-        let ss = &self.contracts.subgraph_service;
+    pub fn collect_indexing_rewards(&self, allocation_id: Address) -> Result<String> {
+        let ss = self.contracts.subgraph_service;
         // PaymentTypes.IndexingRewards = 2
         // data = abi.encode(address allocationId, bytes32 poi, bytes poiMetadata)
         // Use a non-zero POI (keccak of "test") so it takes the CLAIMED path
@@ -201,15 +196,13 @@ impl TestNetwork {
     }
 
     /// Query the indexer's total staked tokens from the HorizonStaking contract.
-    pub fn staked_tokens(&self) -> Result<u128> {
+    pub fn staked_tokens(&self) -> Result<Grt> {
         let output = self.cast_call(
-            &self.contracts.horizon_staking,
+            self.contracts.horizon_staking,
             "getStake(address)(uint256)",
-            &[&self.indexer_address],
+            &[&self.indexer_address.to_string()],
         )?;
-        cast_parse_uint(&output)
-            .parse()
-            .context("parsing staked tokens")
+        Grt::parse_wei(&output).context("parsing staked tokens")
     }
 
     // --- REO Governance Operations (ReoTestPlan Cycles 3-5, 7) ---
@@ -217,115 +210,54 @@ impl TestNetwork {
     /// Set eligibility validation on/off. Requires OPERATOR_ROLE (account0).
     /// ReoTestPlan 4.1 (enable) / 7.2 (disable).
     pub fn reo_set_validation(&self, enabled: bool) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         self.cast_send(
-            &reo,
+            reo,
             "setEligibilityValidation(bool)",
             &[if enabled { "true" } else { "false" }],
         )?;
         Ok(())
     }
 
-    /// Set the eligibility period (seconds). Requires OPERATOR_ROLE (account0).
-    /// ReoTestPlan 4.4.
-    pub fn reo_set_eligibility_period(&self, seconds: u64) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
-        self.cast_send(
-            &reo,
-            "setEligibilityPeriod(uint256)",
-            &[&seconds.to_string()],
-        )?;
-        Ok(())
-    }
-
     /// Get the oracle update timeout (seconds). ReoTestPlan 1.3.
     pub fn reo_oracle_timeout(&self) -> Result<u64> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?;
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         let output = self.cast_call(reo, "getOracleUpdateTimeout()(uint256)", &[])?;
         cast_parse_uint(&output)
             .parse()
             .context("parsing oracleUpdateTimeout")
     }
 
-    /// Set the oracle update timeout (seconds). Requires OPERATOR_ROLE (account0).
-    /// ReoTestPlan 5.1.
-    pub fn reo_set_oracle_timeout(&self, seconds: u64) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
-        self.cast_send(
-            &reo,
-            "setOracleUpdateTimeout(uint256)",
-            &[&seconds.to_string()],
-        )?;
-        Ok(())
-    }
-
     /// Pause the REO contract. Requires PAUSE_ROLE (account0 on local network).
     /// ReoTestPlan 7.1.
     pub fn reo_pause(&self) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
-        self.cast_send(&reo, "pause()", &[])?;
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
+        self.cast_send(reo, "pause()", &[])?;
         Ok(())
     }
 
     /// Unpause the REO contract. Requires PAUSE_ROLE.
     /// ReoTestPlan 7.1.
     pub fn reo_unpause(&self) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
-        self.cast_send(&reo, "unpause()", &[])?;
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
+        self.cast_send(reo, "unpause()", &[])?;
         Ok(())
     }
 
     /// Check if the REO contract is paused. ReoTestPlan 1.5 / 7.1.
     pub fn reo_is_paused(&self) -> Result<bool> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?;
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         let output = self.cast_call(reo, "paused()(bool)", &[])?;
         Ok(output.trim() == "true")
     }
 
     /// Renew eligibility for multiple indexers in a batch. ReoTestPlan 3.3.
-    pub fn reo_renew_batch(&self, addresses: &[&str]) -> Result<()> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?
-            .to_string();
+    pub fn reo_renew_batch(&self, addresses: &[Address]) -> Result<()> {
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
+        let addresses: Vec<String> = addresses.iter().map(Address::to_string).collect();
         let array = format!("[{}]", addresses.join(","));
         self.cast_send(
-            &reo,
+            reo,
             "renewIndexerEligibility(address[],bytes)",
             &[&array, "0x"],
         )?;
@@ -333,16 +265,12 @@ impl TestNetwork {
     }
 
     /// Get the eligibility renewal time for an indexer. ReoTestPlan 3.2.
-    pub fn reo_renewal_time(&self, address: &str) -> Result<u64> {
-        let reo = self
-            .contracts
-            .reo
-            .as_deref()
-            .context("REO contract not deployed")?;
+    pub fn reo_renewal_time(&self, address: Address) -> Result<u64> {
+        let reo = self.contracts.reo.context("REO contract not deployed")?;
         let output = self.cast_call(
             reo,
             "getEligibilityRenewalTime(address)(uint256)",
-            &[address],
+            &[&address.to_string()],
         )?;
         cast_parse_uint(&output)
             .parse()
@@ -350,13 +278,13 @@ impl TestNetwork {
     }
 
     /// Check the RewardsManager → REO integration. ReoTestPlan 1.4.
-    pub fn rewards_manager_reo_address(&self) -> Result<String> {
+    pub fn rewards_manager_reo_address(&self) -> Result<Address> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "getRewardsEligibilityOracle()(address)",
             &[],
         )?;
-        Ok(output.trim().to_string())
+        crate::ids::parse_address(&output)
     }
 
     /// Get the latest block timestamp from the chain.
@@ -377,15 +305,30 @@ impl TestNetwork {
 
     /// Query pending rewards for an allocation via RewardsManager.getRewards().
     /// ReoTestPlan 6.5: view functions should return 0 for ineligible indexers.
-    pub fn rewards_pending(&self, allocation_id: &str) -> Result<u128> {
+    pub fn rewards_pending(&self, allocation_id: Address) -> Result<Grt> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "getRewards(address,address)(uint256)",
-            &[&self.contracts.subgraph_service, allocation_id],
+            &[
+                &self.contracts.subgraph_service.to_string(),
+                &allocation_id.to_string(),
+            ],
         )?;
-        cast_parse_uint(&output)
-            .parse()
-            .context("parsing pending rewards")
+        Grt::parse_wei(&output).context("parsing pending rewards")
+    }
+
+    /// `rewards_pending` as of the end of `block`.
+    pub fn rewards_pending_at(&self, allocation_id: Address, block: u64) -> Result<Grt> {
+        let output = self.cast_call_at(
+            block,
+            self.contracts.rewards_manager,
+            "getRewards(address,address)(uint256)",
+            &[
+                &self.contracts.subgraph_service.to_string(),
+                &allocation_id.to_string(),
+            ],
+        )?;
+        Grt::parse_wei(&output).context("parsing pending rewards")
     }
 
     // --- Governor Operations ---
@@ -393,7 +336,7 @@ impl TestNetwork {
 
     /// State-changing transaction via `cast send`, signed by the governor (account1).
     /// Needed for RewardsManager governance (setReclaimAddress, setMinimumSubgraphSignal, etc.).
-    pub fn cast_send_as_governor(&self, to: &str, sig: &str, args: &[&str]) -> Result<String> {
+    pub fn cast_send_as_governor(&self, to: Address, sig: &str, args: &[&str]) -> Result<String> {
         self.cast_send_as(&self.account1_secret, to, sig, args)
     }
 
@@ -401,93 +344,72 @@ impl TestNetwork {
 
     /// Set a per-condition reclaim address. Requires Governor.
     /// RewardsConditionsTestPlan 1.1.
-    pub fn rewards_set_reclaim_address(&self, condition_hash: &str, address: &str) -> Result<()> {
+    pub fn rewards_set_reclaim_address(
+        &self,
+        condition: ReclaimCondition,
+        address: Address,
+    ) -> Result<()> {
         self.cast_send_as_governor(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "setReclaimAddress(bytes32,address)",
-            &[condition_hash, address],
+            &[&condition.hash().to_string(), &address.to_string()],
         )?;
         Ok(())
     }
 
     /// Get the reclaim address for a condition.
     /// RewardsConditionsTestPlan 1.1.
-    pub fn rewards_get_reclaim_address(&self, condition_hash: &str) -> Result<String> {
+    pub fn rewards_get_reclaim_address(&self, condition: ReclaimCondition) -> Result<Address> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "getReclaimAddress(bytes32)(address)",
-            &[condition_hash],
+            &[&condition.hash().to_string()],
         )?;
-        Ok(output.trim().to_string())
-    }
-
-    /// Set the default reclaim address. Requires Governor.
-    /// RewardsConditionsTestPlan 1.2.
-    pub fn rewards_set_default_reclaim_address(&self, address: &str) -> Result<()> {
-        self.cast_send_as_governor(
-            &self.contracts.rewards_manager,
-            "setDefaultReclaimAddress(address)",
-            &[address],
-        )?;
-        Ok(())
+        crate::ids::parse_address(&output)
     }
 
     /// Get the default reclaim address.
     /// RewardsConditionsTestPlan 1.2.
-    pub fn rewards_get_default_reclaim_address(&self) -> Result<String> {
+    pub fn rewards_get_default_reclaim_address(&self) -> Result<Address> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "getDefaultReclaimAddress()(address)",
             &[],
         )?;
-        Ok(output.trim().to_string())
+        crate::ids::parse_address(&output)
     }
 
     /// Get the minimum subgraph signal threshold.
     /// RewardsConditionsTestPlan 2.1.
-    pub fn rewards_minimum_signal(&self) -> Result<u128> {
+    pub fn rewards_minimum_signal(&self) -> Result<Grt> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "minimumSubgraphSignal()(uint256)",
             &[],
         )?;
-        cast_parse_uint(&output)
-            .parse()
-            .context("parsing minimumSubgraphSignal")
-    }
-
-    /// Set the minimum subgraph signal threshold. Requires Governor.
-    /// RewardsConditionsTestPlan 2.2.
-    pub fn rewards_set_minimum_signal(&self, threshold: &str) -> Result<()> {
-        self.cast_send_as_governor(
-            &self.contracts.rewards_manager,
-            "setMinimumSubgraphSignal(uint256)",
-            &[threshold],
-        )?;
-        Ok(())
+        Grt::parse_wei(&output).context("parsing minimumSubgraphSignal")
     }
 
     /// Get accumulated rewards for a subgraph deployment.
     /// RewardsConditionsTestPlan 2.3, SubgraphDenialTestPlan 3.1.
-    pub fn rewards_acc_for_subgraph(&self, deployment_id: &str) -> Result<u128> {
+    pub fn rewards_acc_for_subgraph(&self, deployment: DeploymentId) -> Result<Grt> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "getAccRewardsForSubgraph(bytes32)(uint256)",
-            &[deployment_id],
+            &[&deployment.bytes32().to_string()],
         )?;
-        cast_parse_uint(&output)
-            .parse()
-            .context("parsing accRewardsForSubgraph")
+        Grt::parse_wei(&output).context("parsing accRewardsForSubgraph")
     }
 
     /// Get accumulated rewards per allocated token for a subgraph.
-    /// Returns just the first value (accumulated amount).
+    /// Returns just the first value (accumulated amount), a fixed-point ratio
+    /// scaled by 1e18 rather than a GRT amount.
     /// RewardsConditionsTestPlan 3.3, SubgraphDenialTestPlan 3.1.
-    pub fn rewards_acc_per_allocated_token(&self, deployment_id: &str) -> Result<u128> {
+    pub fn rewards_acc_per_allocated_token(&self, deployment: DeploymentId) -> Result<u128> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "getAccRewardsPerAllocatedToken(bytes32)(uint256,uint256)",
-            &[deployment_id],
+            &[&deployment.bytes32().to_string()],
         )?;
         // Returns two values on separate lines; take the first
         let first_line = output.lines().next().unwrap_or(&output);
@@ -498,22 +420,22 @@ impl TestNetwork {
 
     /// Trigger accumulator update for a subgraph's signal.
     /// RewardsConditionsTestPlan 2.2-2.4, SubgraphDenialTestPlan 3.3.
-    pub fn rewards_on_subgraph_signal_update(&self, deployment_id: &str) -> Result<()> {
+    pub fn rewards_on_subgraph_signal_update(&self, deployment: DeploymentId) -> Result<()> {
         self.cast_send(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "onSubgraphSignalUpdate(bytes32)",
-            &[deployment_id],
+            &[&deployment.bytes32().to_string()],
         )?;
         Ok(())
     }
 
     /// Trigger accumulator update for a subgraph's allocation.
     /// RewardsConditionsTestPlan 3.2.
-    pub fn rewards_on_subgraph_allocation_update(&self, deployment_id: &str) -> Result<()> {
+    pub fn rewards_on_subgraph_allocation_update(&self, deployment: DeploymentId) -> Result<()> {
         self.cast_send(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "onSubgraphAllocationUpdate(bytes32)",
-            &[deployment_id],
+            &[&deployment.bytes32().to_string()],
         )?;
         Ok(())
     }
@@ -523,25 +445,16 @@ impl TestNetwork {
     /// Ensure the oracle account has ETH for gas. The subgraph availability
     /// oracle (deployment mnemonic index 4) may not be funded on fresh chains.
     fn ensure_oracle_funded(&self) -> Result<()> {
-        let oracle_addr = "0xd03ea8624C8C5987235048901fB614fDcA89b117";
+        let oracle_addr = ORACLE_ADDRESS.to_string();
         let output = run_command(
             Command::new("cast")
                 .arg("balance")
-                .arg(oracle_addr)
+                .arg(&oracle_addr)
                 .arg(format!("--rpc-url={}", self.rpc_url)),
         )?;
         let balance: u128 = cast_parse_uint(&output).parse().unwrap_or(0);
         if balance == 0 {
-            // Fund oracle with 1 ETH from account0 (plain ETH transfer)
-            run_command(
-                Command::new("cast")
-                    .arg("send")
-                    .arg(format!("--rpc-url={}", self.rpc_url))
-                    .arg("--confirmations=0")
-                    .arg(format!("--private-key={}", self.account0_secret))
-                    .arg("--value=1ether")
-                    .arg(oracle_addr),
-            )?;
+            self.transfer_eth(ORACLE_ADDRESS, 1)?;
         }
         Ok(())
     }
@@ -549,24 +462,27 @@ impl TestNetwork {
     /// Set denied status for a subgraph deployment.
     /// Requires the subgraph availability oracle role (deployment mnemonic index 4).
     /// SubgraphDenialTestPlan 2.2.
-    pub fn rewards_set_denied(&self, deployment_id: &str, denied: bool) -> Result<()> {
+    pub fn rewards_set_denied(&self, deployment: DeploymentId, denied: bool) -> Result<()> {
         self.ensure_oracle_funded()?;
         self.cast_send_as(
             &self.oracle_secret,
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "setDenied(bytes32,bool)",
-            &[deployment_id, if denied { "true" } else { "false" }],
+            &[
+                &deployment.bytes32().to_string(),
+                if denied { "true" } else { "false" },
+            ],
         )?;
         Ok(())
     }
 
     /// Check if a subgraph deployment is denied.
     /// SubgraphDenialTestPlan 2.1.
-    pub fn rewards_is_denied(&self, deployment_id: &str) -> Result<bool> {
+    pub fn rewards_is_denied(&self, deployment: DeploymentId) -> Result<bool> {
         let output = self.cast_call(
-            &self.contracts.rewards_manager,
+            self.contracts.rewards_manager,
             "isDenied(bytes32)(bool)",
-            &[deployment_id],
+            &[&deployment.bytes32().to_string()],
         )?;
         Ok(output.trim() == "true")
     }
@@ -575,7 +491,7 @@ impl TestNetwork {
     /// RewardsConditionsTestPlan 4.2.
     pub fn max_poi_staleness(&self) -> Result<u64> {
         let output = self.cast_call(
-            &self.contracts.subgraph_service,
+            self.contracts.subgraph_service,
             "maxPOIStaleness()(uint256)",
             &[],
         )?;
@@ -588,15 +504,50 @@ impl TestNetwork {
 
     /// Get GRT balance of an address.
     /// RewardsConditionsTestPlan 1.5, SubgraphDenialTestPlan 1.2.
-    pub fn grt_balance_of(&self, address: &str) -> Result<u128> {
+    pub fn grt_balance_of(&self, address: Address) -> Result<Grt> {
         let output = self.cast_call(
-            &self.contracts.grt_token,
+            self.contracts.grt_token,
             "balanceOf(address)(uint256)",
-            &[address],
+            &[&address.to_string()],
         )?;
-        cast_parse_uint(&output)
-            .parse()
-            .context("parsing GRT balance")
+        Grt::parse_wei(&output).context("parsing GRT balance")
+    }
+
+    /// `grt_balance_of` at the end of `block`.
+    pub fn grt_balance_of_at(&self, address: Address, block: u64) -> Result<Grt> {
+        let output = self.cast_call_at(
+            block,
+            self.contracts.grt_token,
+            "balanceOf(address)(uint256)",
+            &[&address.to_string()],
+        )?;
+        Grt::parse_wei(&output).context("parsing GRT balance")
+    }
+
+    /// Transfer GRT from account0 (the deployer holds the GRT supply), e.g.
+    /// to fund a delegator or curator account.
+    pub fn transfer_grt(&self, to: Address, amount: Grt) -> Result<()> {
+        self.cast_send(
+            self.contracts.grt_token,
+            "transfer(address,uint256)",
+            &[&to.to_string(), &amount.to_wei_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Send `ether` whole ETH from account0 (plain transfer), e.g. to give a
+    /// freshly generated account gas money.
+    pub fn transfer_eth(&self, to: Address, ether: u64) -> Result<()> {
+        run_command(
+            Command::new("cast")
+                .arg("send")
+                .arg(format!("--rpc-url={}", self.rpc_url))
+                .arg("--confirmations=0")
+                .arg(format!("--private-key={}", self.account0_secret))
+                .arg(format!("--value={ether}ether"))
+                .arg(to.to_string()),
+        )?;
+        Ok(())
     }
 
     // --- Event Filtering ---
@@ -604,10 +555,10 @@ impl TestNetwork {
     /// Query event logs filtered by topic0 (event signature).
     pub fn cast_logs_with_topic(
         &self,
-        address: &str,
+        address: Address,
         from_block: u64,
         to_block: u64,
-        topic0: &str,
+        topic0: B256,
     ) -> Result<Vec<serde_json::Value>> {
         let mut cmd = std::process::Command::new("cast");
         cmd.arg("logs")
@@ -616,7 +567,7 @@ impl TestNetwork {
             .arg(format!("--to-block={to_block}"))
             .arg(format!("--address={address}"))
             .arg(format!("--rpc-url={}", self.rpc_url))
-            .arg(topic0);
+            .arg(topic0.to_string());
         let output = run_command(&mut cmd)?;
         let logs: Vec<serde_json::Value> =
             serde_json::from_str(&output).context("parsing filtered cast logs JSON")?;
@@ -641,7 +592,7 @@ impl TestNetwork {
     /// Returns parsed JSON log objects.
     pub fn cast_logs_json(
         &self,
-        address: &str,
+        address: Address,
         from_block: u64,
         to_block: u64,
     ) -> Result<Vec<serde_json::Value>> {
@@ -659,12 +610,15 @@ impl TestNetwork {
     }
 
     /// Compute keccak256 hash of a string via cast.
-    pub fn cast_keccak(&self, input: &str) -> Result<String> {
+    pub fn cast_keccak(&self, input: &str) -> Result<B256> {
         let output = run_command(Command::new("cast").arg("keccak").arg(input))?;
-        Ok(output.trim().to_string())
+        output.parse().context("parsing keccak256 output")
     }
 }
 
+/// The subgraph availability oracle (deployment mnemonic index 4).
+const ORACLE_ADDRESS: Address = crate::ids::address!("0xd03ea8624C8C5987235048901fB614fDcA89b117");
+
 /// Run a command, returning trimmed stdout on success or an error with stderr.
 fn run_command(cmd: &mut Command) -> Result<String> {
     let output = cmd.output().context("spawning command")?;
@@ -684,3 +638,14 @@ fn run_command(cmd: &mut Command) -> Result<String> {
 pub fn cast_parse_uint(raw: &str) -> &str {
     raw.split_whitespace().next().unwrap_or(raw)
 }
+
+/// Block number from the receipt `cast send` prints (`blockNumber  123`).
+pub fn cast_receipt_block(receipt: &str) -> Result<u64> {
+    receipt
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("blockNumber"))
+        .map(str::trim)
+        .context("no blockNumber in cast send receipt")?
+        .parse()
+        .context("parsing receipt blockNumber")
+}
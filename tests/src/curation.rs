@@ -0,0 +1,366 @@
+//! Curation signal on L2Curation (per deployment) and GNS (per subgraph).
+//!
+//! Signal decides how issuance is split between deployments, and
+//! RewardsManager only issues when there is signal at all: its global signal
+//! is L2Curation's GRT balance. Curators:
+//!
+//!   - `mint_signal_as` → approve + `L2Curation.mint(deployment, tokens, 0)`
+//!   - `burn_signal_as` → `L2Curation.burn(deployment, signal, 0)`
+//!   - `gns_mint_signal_as` / `gns_burn_signal_as` → the same through a
+//!     subgraph's current version (`L2GNS.mintSignal`/`burnSignal`)
+//!
+//! Minting pays `curationTaxPercentage` of the tokens as tax, so a burn
+//! returns less than was minted. Signal amounts are raw 18-decimal units.
+//! `wait_for_deployment_signal` polls until the network subgraph's
+//! `SubgraphDeployment.signalledTokens` matches the chain.
+
+use alloy_primitives::U256;
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::cast::cast_parse_uint;
+use crate::governance::GovernanceParameter;
+use crate::grt::PPM;
+use crate::polling::PollResult;
+use crate::{Address, DeploymentId, Grt, SubgraphId, TestNetwork};
+
+/// A deployment's curation pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurationPool {
+    /// GRT deposited (after tax).
+    pub tokens: Grt,
+    /// Signal outstanding.
+    pub signal: u128,
+}
+
+/// Curation fields of a `SubgraphDeployment` entity.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphDeploymentSignal {
+    pub ipfs_hash: DeploymentId,
+    pub signalled_tokens: Grt,
+    #[serde(deserialize_with = "crate::graphql::big_int")]
+    pub signal_amount: u128,
+}
+
+/// GRT that reaches the curation pool when minting with `tokens`: the tax
+/// (`tokens * tax_ppm / PPM`, rounded down) is burned.
+pub fn tokens_after_tax(tokens: Grt, tax_ppm: u32) -> Grt {
+    tokens - tokens.mul_ppm(tax_ppm)
+}
+
+/// Tokens to mint with so that at least `tokens` reach the pool after tax.
+pub fn tokens_before_tax(tokens: Grt, tax_ppm: u32) -> Grt {
+    let ppm = u128::from(PPM);
+    let net = ppm - u128::from(tax_ppm);
+    let gross = U256::from(tokens.wei()) * U256::from(ppm);
+    let gross = gross.div_ceil(U256::from(net));
+    Grt::from_wei(gross.to())
+}
+
+impl TestNetwork {
+    // --- L2Curation ---
+
+    /// Mint signal on `deployment` with account0's GRT. Returns the signal minted.
+    pub fn mint_signal(&self, deployment: DeploymentId, tokens: Grt) -> Result<u128> {
+        self.mint_signal_as(&self.account0_secret, deployment, tokens)
+    }
+
+    /// Approve and mint signal on `deployment` as `curator_secret`, which
+    /// must hold `tokens` GRT. Returns the signal minted.
+    pub fn mint_signal_as(
+        &self,
+        curator_secret: &str,
+        deployment: DeploymentId,
+        tokens: Grt,
+    ) -> Result<u128> {
+        let curator = signer_address(curator_secret)?;
+        let before = self.curator_signal(curator, deployment)?;
+        self.cast_send_as(
+            curator_secret,
+            self.contracts.grt_token,
+            "approve(address,uint256)",
+            &[
+                &self.contracts.curation.to_string(),
+                &tokens.to_wei_string(),
+            ],
+        )?;
+        self.cast_send_as(
+            curator_secret,
+            self.contracts.curation,
+            "mint(bytes32,uint256,uint256)",
+            &[
+                &deployment.bytes32().to_string(),
+                &tokens.to_wei_string(),
+                "0",
+            ],
+        )
+        .with_context(|| format!("minting signal on {deployment}"))?;
+        Ok(self.curator_signal(curator, deployment)? - before)
+    }
+
+    /// Burn `signal` of `curator_secret`'s signal on `deployment`. Returns the
+    /// GRT paid out to the curator.
+    pub fn burn_signal_as(
+        &self,
+        curator_secret: &str,
+        deployment: DeploymentId,
+        signal: u128,
+    ) -> Result<Grt> {
+        let curator = signer_address(curator_secret)?;
+        let before = self.grt_balance_of(curator)?;
+        self.cast_send_as(
+            curator_secret,
+            self.contracts.curation,
+            "burn(bytes32,uint256,uint256)",
+            &[&deployment.bytes32().to_string(), &signal.to_string(), "0"],
+        )
+        .with_context(|| format!("burning signal on {deployment}"))?;
+        Ok(self.grt_balance_of(curator)? - before)
+    }
+
+    /// Tokens and signal in `deployment`'s curation pool.
+    pub fn curation_pool(&self, deployment: DeploymentId) -> Result<CurationPool> {
+        let id = deployment.bytes32().to_string();
+        let tokens = self.cast_call(
+            self.contracts.curation,
+            "getCurationPoolTokens(bytes32)(uint256)",
+            &[&id],
+        )?;
+        let signal = self.cast_call(
+            self.contracts.curation,
+            "getCurationPoolSignal(bytes32)(uint256)",
+            &[&id],
+        )?;
+        Ok(CurationPool {
+            tokens: Grt::parse_wei(cast_parse_uint(&tokens)).context("parsing pool tokens")?,
+            signal: parse_signal(&signal)?,
+        })
+    }
+
+    /// Signal `curator` holds on `deployment`.
+    pub fn curator_signal(&self, curator: Address, deployment: DeploymentId) -> Result<u128> {
+        let output = self.cast_call(
+            self.contracts.curation,
+            "getCuratorSignal(address,bytes32)(uint256)",
+            &[&curator.to_string(), &deployment.bytes32().to_string()],
+        )?;
+        parse_signal(&output)
+    }
+
+    /// Burn all of account0's signal on `deployments`. Returns the pool tokens
+    /// removed per deployment, for `restore_signal`.
+    pub fn burn_all_signal(
+        &self,
+        deployments: &[DeploymentId],
+    ) -> Result<Vec<(DeploymentId, Grt)>> {
+        let account0 = signer_address(&self.account0_secret)?;
+        let mut burned = Vec::new();
+        for &deployment in deployments {
+            let signal = self.curator_signal(account0, deployment)?;
+            if signal == 0 {
+                continue;
+            }
+            let before = self.curation_pool(deployment)?.tokens;
+            self.burn_signal_as(&self.account0_secret, deployment, signal)?;
+            burned.push((deployment, before - self.curation_pool(deployment)?.tokens));
+        }
+        Ok(burned)
+    }
+
+    /// Re-mint signal removed by `burn_all_signal` with account0's GRT. The
+    /// curation tax is waived meanwhile, so each pool gets exactly its tokens
+    /// back and account0 pays what the burn returned.
+    pub fn restore_signal(&self, burned: &[(DeploymentId, Grt)]) -> Result<()> {
+        let mut overrides = self.governance_override();
+        overrides.set(GovernanceParameter::CurationTaxPercentage, 0u64)?;
+        for &(deployment, tokens) in burned {
+            self.mint_signal(deployment, tokens)?;
+        }
+        overrides.restore()
+    }
+
+    /// Curation tax charged on mint, in PPM.
+    pub fn curation_tax(&self) -> Result<u32> {
+        let output = self.cast_call(
+            self.contracts.curation,
+            "curationTaxPercentage()(uint32)",
+            &[],
+        )?;
+        cast_parse_uint(&output)
+            .parse()
+            .context("parsing curationTaxPercentage")
+    }
+
+    /// Total GRT curated across all deployments: the global signal
+    /// RewardsManager divides issuance by (zero means nothing is issued).
+    pub fn total_curation_tokens(&self) -> Result<Grt> {
+        self.grt_balance_of(self.contracts.curation)
+    }
+
+    // --- GNS ---
+
+    /// Approve and mint name signal on `subgraph` (deposited into its current
+    /// version's deployment) as `curator_secret`. Returns the name signal minted.
+    pub fn gns_mint_signal_as(
+        &self,
+        curator_secret: &str,
+        subgraph: SubgraphId,
+        tokens: Grt,
+    ) -> Result<u128> {
+        let curator = signer_address(curator_secret)?;
+        let before = self.gns_curator_signal(subgraph, curator)?;
+        self.cast_send_as(
+            curator_secret,
+            self.contracts.grt_token,
+            "approve(address,uint256)",
+            &[&self.contracts.gns.to_string(), &tokens.to_wei_string()],
+        )?;
+        self.cast_send_as(
+            curator_secret,
+            self.contracts.gns,
+            "mintSignal(uint256,uint256,uint256)",
+            &[
+                &subgraph.uint256().to_string(),
+                &tokens.to_wei_string(),
+                "0",
+            ],
+        )
+        .with_context(|| format!("minting name signal on subgraph {subgraph}"))?;
+        Ok(self.gns_curator_signal(subgraph, curator)? - before)
+    }
+
+    /// Burn `name_signal` on `subgraph` as `curator_secret`. Returns the GRT
+    /// paid out to the curator.
+    pub fn gns_burn_signal_as(
+        &self,
+        curator_secret: &str,
+        subgraph: SubgraphId,
+        name_signal: u128,
+    ) -> Result<Grt> {
+        let curator = signer_address(curator_secret)?;
+        let before = self.grt_balance_of(curator)?;
+        self.cast_send_as(
+            curator_secret,
+            self.contracts.gns,
+            "burnSignal(uint256,uint256,uint256)",
+            &[
+                &subgraph.uint256().to_string(),
+                &name_signal.to_string(),
+                "0",
+            ],
+        )
+        .with_context(|| format!("burning name signal on subgraph {subgraph}"))?;
+        Ok(self.grt_balance_of(curator)? - before)
+    }
+
+    /// Name signal `curator` holds on `subgraph`.
+    pub fn gns_curator_signal(&self, subgraph: SubgraphId, curator: Address) -> Result<u128> {
+        let output = self.cast_call(
+            self.contracts.gns,
+            "getCuratorSignal(uint256,address)(uint256)",
+            &[&subgraph.uint256().to_string(), &curator.to_string()],
+        )?;
+        parse_signal(&output)
+    }
+
+    /// Total name signal on `subgraph`.
+    pub fn gns_subgraph_signal(&self, subgraph: SubgraphId) -> Result<u128> {
+        let output = self.cast_call(
+            self.contracts.gns,
+            "subgraphSignal(uint256)(uint256)",
+            &[&subgraph.uint256().to_string()],
+        )?;
+        parse_signal(&output)
+    }
+
+    // --- Network subgraph ---
+
+    /// Curation state of `deployment` as indexed by the network subgraph
+    /// (`None` if the deployment isn't indexed).
+    pub async fn query_deployment_signal(
+        &self,
+        deployment: DeploymentId,
+    ) -> Result<Option<SubgraphDeploymentSignal>> {
+        let query = format!(
+            r#"{{ subgraphDeployment(id: "{}") {{
+                ipfsHash signalledTokens signalAmount
+            }} }}"#,
+            deployment.bytes32()
+        );
+        let resp = self.subgraph_query(&query).await?;
+        serde_json::from_value(resp["data"]["subgraphDeployment"].clone())
+            .context("parsing subgraphDeployment signal")
+    }
+
+    /// Wait until the network subgraph reports `deployment`'s on-chain
+    /// curation pool tokens as `signalledTokens`.
+    pub async fn wait_for_deployment_signal(
+        &self,
+        deployment: DeploymentId,
+        timeout: Duration,
+    ) -> Result<SubgraphDeploymentSignal> {
+        let expected = self.curation_pool(deployment)?.tokens;
+        let result = self
+            .poll_until(timeout, Duration::from_secs(2), || async {
+                let indexed = self.query_deployment_signal(deployment).await?;
+                Ok(indexed.filter(|d| d.signalled_tokens == expected))
+            })
+            .await;
+        match result {
+            PollResult::Ready(indexed) => Ok(indexed),
+            PollResult::TimedOut => anyhow::bail!(
+                "signal on {deployment} not indexed as {expected} GRT within {timeout:?}"
+            ),
+        }
+    }
+}
+
+fn signer_address(secret: &str) -> Result<Address> {
+    let signer: PrivateKeySigner = secret.parse().context("parsing curator key")?;
+    Ok(signer.address())
+}
+
+fn parse_signal(output: &str) -> Result<u128> {
+    cast_parse_uint(output)
+        .parse()
+        .with_context(|| format!("parsing signal {output:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tax_rounds_in_the_pools_favour() {
+        assert_eq!(
+            tokens_after_tax(Grt::from_grt(100), 10_000),
+            Grt::from_grt(99)
+        );
+        assert_eq!(tokens_after_tax(Grt::from_grt(100), 0), Grt::from_grt(100));
+        // 999 * 1% = 9.99 → 9 wei of tax
+        assert_eq!(
+            tokens_after_tax(Grt::from_wei(999), 10_000),
+            Grt::from_wei(990)
+        );
+    }
+
+    #[test]
+    fn gross_up_covers_the_tax() {
+        for (tokens, tax) in [
+            (Grt::from_grt(1_000), 10_000),
+            (Grt::from_wei(990), 10_000),
+            (Grt::from_wei(1), 500_000),
+            (Grt::from_grt(7), 0),
+        ] {
+            let gross = tokens_before_tax(tokens, tax);
+            assert!(tokens_after_tax(gross, tax) >= tokens, "{tokens} at {tax}");
+        }
+        assert_eq!(
+            tokens_before_tax(Grt::from_grt(99), 10_000),
+            Grt::from_grt(100)
+        );
+    }
+}
@@ -0,0 +1,398 @@
+//! Delegation to the indexer's SubgraphService provision.
+//!
+//! In Horizon, delegation is per (service provider, verifier) pair: each
+//! provision has its own delegation pool, and the service provider sets a
+//! delegation fee cut per payment type. These helpers emulate Explorer's
+//! delegate/undelegate flows (see `docs/explorer/Goal.md`) plus the
+//! withdraw/redelegate calls Explorer makes after thawing:
+//!
+//!   - `delegate_as` → `GraphToken.approve` + `HorizonStaking.delegate`
+//!   - `undelegate_as` → `HorizonStaking.undelegate` (starts a thaw request)
+//!   - `withdraw_delegated_as` → `HorizonStaking.withdrawDelegated`
+//!   - `redelegate_as` → `HorizonStaking.redelegate` (withdraw + delegate)
+//!   - `delegation_pool` / `delegation_shares` → on-chain pool and delegator state
+//!   - `set_delegation_fee_cut` → `HorizonStaking.setDelegationFeeCut`
+//!
+//! The network subgraph mirrors the same state on `Delegator` / `DelegatedStake`
+//! and on the provision (`query_delegator`, `query_delegation_pool`).
+
+use alloy_primitives::{U256, hex};
+use alloy_sol_types::{SolType, sol};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::grt::PPM;
+use crate::{Address, Grt, TestNetwork};
+
+sol! {
+    /// `IHorizonStakingTypes.DelegationPool` as returned by `getDelegationPool`.
+    struct DelegationPoolState {
+        uint256 tokens;
+        uint256 shares;
+        uint256 tokensThawing;
+        uint256 sharesThawing;
+        uint256 thawingNonce;
+    }
+}
+
+/// `PaymentTypes` enum of the Horizon contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentType {
+    QueryFee = 0,
+    IndexingFee = 1,
+    IndexingRewards = 2,
+}
+
+/// `a * b / c` without intermediate overflow (Solidity `uint256` math).
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    let result = U256::from(a) * U256::from(b) / U256::from(c);
+    result.to::<u128>()
+}
+
+/// On-chain delegation pool of one provision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DelegationPool {
+    pub tokens: Grt,
+    pub shares: u128,
+    pub tokens_thawing: Grt,
+    pub shares_thawing: u128,
+    pub thawing_nonce: u64,
+}
+
+impl DelegationPool {
+    /// Decode ABI-encoded `getDelegationPool` output.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let s = <DelegationPoolState as SolType>::abi_decode(data)
+            .context("decoding DelegationPool")?;
+        Ok(Self {
+            tokens: Grt::from_wei(s.tokens.to()),
+            shares: s.shares.to(),
+            tokens_thawing: Grt::from_wei(s.tokensThawing.to()),
+            shares_thawing: s.sharesThawing.to(),
+            thawing_nonce: s.thawingNonce.to(),
+        })
+    }
+
+    /// Tokens backing the pool's shares (thawing tokens no longer earn).
+    pub fn active_tokens(&self) -> Grt {
+        self.tokens.saturating_sub(self.tokens_thawing)
+    }
+
+    /// Shares minted for delegating `tokens`, as `HorizonStaking._delegate`.
+    pub fn shares_for(&self, tokens: Grt) -> u128 {
+        let active = self.active_tokens();
+        if self.shares == 0 || active.is_zero() {
+            tokens.wei()
+        } else {
+            mul_div(tokens.wei(), self.shares, active.wei())
+        }
+    }
+
+    /// Tokens `shares` are worth, as `HorizonStaking._undelegate`.
+    pub fn tokens_for(&self, shares: u128) -> Grt {
+        if self.shares == 0 {
+            return Grt::ZERO;
+        }
+        Grt::from_wei(mul_div(shares, self.active_tokens().wei(), self.shares))
+    }
+}
+
+/// `DelegatedStake` entity: one delegator's stake with one indexer.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphDelegatedStake {
+    pub id: String,
+    #[serde(deserialize_with = "crate::graphql::entity_id")]
+    pub indexer: Address,
+    pub staked_tokens: Grt,
+    pub unstaked_tokens: Grt,
+    pub locked_tokens: Grt,
+    #[serde(deserialize_with = "crate::graphql::big_int")]
+    pub share_amount: u128,
+}
+
+/// `Delegator` entity from the network subgraph.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphDelegator {
+    pub id: Address,
+    pub total_staked_tokens: Grt,
+    pub total_unstaked_tokens: Grt,
+    pub stakes: Vec<SubgraphDelegatedStake>,
+}
+
+impl SubgraphDelegator {
+    /// The stake delegated to `indexer`, if any.
+    pub fn stake_with(&self, indexer: Address) -> Option<&SubgraphDelegatedStake> {
+        self.stakes.iter().find(|s| s.indexer == indexer)
+    }
+}
+
+/// Delegation pool fields of a `Provision` entity.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphDelegationPool {
+    pub id: String,
+    pub delegated_tokens: Grt,
+    #[serde(deserialize_with = "crate::graphql::big_int")]
+    pub delegator_shares: u128,
+    pub delegated_thawing_tokens: Grt,
+}
+
+impl TestNetwork {
+    /// Delegate `tokens` from `delegator_secret` to the indexer's
+    /// SubgraphService provision. Emulates Explorer "Delegate".
+    ///
+    /// Approves HorizonStaking first; the delegator needs GRT (`transfer_grt`).
+    pub fn delegate_as(&self, delegator_secret: &str, tokens: Grt) -> Result<()> {
+        let amount = tokens.to_wei_string();
+        self.cast_send_as(
+            delegator_secret,
+            self.contracts.grt_token,
+            "approve(address,uint256)",
+            &[&self.contracts.horizon_staking.to_string(), &amount],
+        )?;
+        self.cast_send_as(
+            delegator_secret,
+            self.contracts.horizon_staking,
+            "delegate(address,address,uint256,uint256)",
+            &[
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &amount,
+                "0",
+            ],
+        )
+        .context("delegating")?;
+        Ok(())
+    }
+
+    /// Undelegate `shares`, creating a thaw request that completes after the
+    /// provision's thawing period. Emulates Explorer "Undelegate".
+    pub fn undelegate_as(&self, delegator_secret: &str, shares: u128) -> Result<()> {
+        self.cast_send_as(
+            delegator_secret,
+            self.contracts.horizon_staking,
+            "undelegate(address,address,uint256)",
+            &[
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &shares.to_string(),
+            ],
+        )
+        .context("undelegating")?;
+        Ok(())
+    }
+
+    /// Withdraw up to `n_thaw_requests` thawed delegations back to the
+    /// delegator's wallet.
+    pub fn withdraw_delegated_as(
+        &self,
+        delegator_secret: &str,
+        n_thaw_requests: u64,
+    ) -> Result<()> {
+        self.cast_send_as(
+            delegator_secret,
+            self.contracts.horizon_staking,
+            "withdrawDelegated(address,address,uint256)",
+            &[
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &n_thaw_requests.to_string(),
+            ],
+        )
+        .context("withdrawing delegation")?;
+        Ok(())
+    }
+
+    /// Move thawed delegation from the indexer's provision to `new_indexer`'s
+    /// SubgraphService provision without it leaving the staking contract.
+    pub fn redelegate_as(
+        &self,
+        delegator_secret: &str,
+        new_indexer: Address,
+        n_thaw_requests: u64,
+    ) -> Result<()> {
+        let ss = self.contracts.subgraph_service.to_string();
+        self.cast_send_as(
+            delegator_secret,
+            self.contracts.horizon_staking,
+            "redelegate(address,address,address,address,uint256,uint256)",
+            &[
+                &self.indexer_address.to_string(),
+                &ss,
+                &new_indexer.to_string(),
+                &ss,
+                "0",
+                &n_thaw_requests.to_string(),
+            ],
+        )
+        .context("redelegating")?;
+        Ok(())
+    }
+
+    /// The delegation pool of `indexer`'s SubgraphService provision.
+    pub fn delegation_pool(&self, indexer: Address) -> Result<DelegationPool> {
+        let output = self.cast_call(
+            self.contracts.horizon_staking,
+            "getDelegationPool(address,address)",
+            &[
+                &indexer.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+            ],
+        )?;
+        let data = hex::decode(output.trim()).context("decoding getDelegationPool output")?;
+        DelegationPool::decode(&data)
+    }
+
+    /// Shares `delegator` holds in `indexer`'s SubgraphService pool.
+    pub fn delegation_shares(&self, indexer: Address, delegator: Address) -> Result<u128> {
+        let output = self.cast_call(
+            self.contracts.horizon_staking,
+            "getDelegation(address,address,address)(uint256)",
+            &[
+                &indexer.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &delegator.to_string(),
+            ],
+        )?;
+        crate::cast::cast_parse_uint(&output)
+            .parse()
+            .context("parsing delegation shares")
+    }
+
+    /// Delegation fee cut (PPM of the payment that goes to delegators) of
+    /// `indexer`'s SubgraphService provision for `payment_type`.
+    pub fn delegation_fee_cut(&self, indexer: Address, payment_type: PaymentType) -> Result<u32> {
+        let output = self.cast_call(
+            self.contracts.horizon_staking,
+            "getDelegationFeeCut(address,address,uint8)(uint256)",
+            &[
+                &indexer.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &(payment_type as u8).to_string(),
+            ],
+        )?;
+        crate::cast::cast_parse_uint(&output)
+            .parse()
+            .context("parsing delegation fee cut")
+    }
+
+    /// Set the local indexer's delegation fee cut for `payment_type`, as the
+    /// indexer (RECEIVER_SECRET).
+    pub fn set_delegation_fee_cut(
+        &self,
+        payment_type: PaymentType,
+        fee_cut_ppm: u32,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            fee_cut_ppm <= PPM,
+            "fee cut {fee_cut_ppm} exceeds {PPM} PPM"
+        );
+        self.cast_send_as_indexer(
+            self.contracts.horizon_staking,
+            "setDelegationFeeCut(address,address,uint8,uint256)",
+            &[
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &(payment_type as u8).to_string(),
+                &fee_cut_ppm.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The `Delegator` entity with its delegated stakes (`None` if unknown).
+    pub async fn query_delegator(&self, delegator: Address) -> Result<Option<SubgraphDelegator>> {
+        let query = format!(
+            r#"{{ delegator(id: "{delegator:#x}") {{
+                id totalStakedTokens totalUnstakedTokens
+                stakes {{
+                    id indexer {{ id }}
+                    stakedTokens unstakedTokens lockedTokens shareAmount
+                }}
+            }} }}"#
+        );
+        let resp = self.subgraph_query(&query).await?;
+        serde_json::from_value(resp["data"]["delegator"].clone()).context("parsing delegator")
+    }
+
+    /// Delegation pool state of `indexer`'s SubgraphService provision as
+    /// indexed by the network subgraph.
+    pub async fn query_delegation_pool(
+        &self,
+        indexer: Address,
+    ) -> Result<Option<SubgraphDelegationPool>> {
+        let query = format!(
+            r#"{{ provisions(where: {{ indexer: "{indexer:#x}", dataService: "{ss:#x}" }}) {{
+                id delegatedTokens delegatorShares delegatedThawingTokens
+            }} }}"#,
+            ss = self.contracts.subgraph_service,
+        );
+        let resp = self.subgraph_query(&query).await?;
+        let pools: Vec<SubgraphDelegationPool> =
+            serde_json::from_value(resp["data"]["provisions"].clone())
+                .context("parsing provision delegation pool")?;
+        Ok(pools.into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::SolValue;
+
+    #[test]
+    fn decodes_pool_and_converts_shares() {
+        let data = (
+            U256::from(1_100u64),
+            U256::from(1_000u64),
+            U256::from(100u64),
+            U256::from(50u64),
+            U256::from(3u64),
+        )
+            .abi_encode_params();
+        let pool = DelegationPool::decode(&data).unwrap();
+        assert_eq!(pool.tokens, Grt::from_wei(1_100));
+        assert_eq!(pool.shares, 1_000);
+        assert_eq!(pool.tokens_thawing, Grt::from_wei(100));
+        assert_eq!(pool.shares_thawing, 50);
+        assert_eq!(pool.thawing_nonce, 3);
+
+        assert_eq!(pool.active_tokens(), Grt::from_wei(1_000));
+        assert_eq!(pool.shares_for(Grt::from_wei(500)), 500);
+        assert_eq!(pool.tokens_for(250), Grt::from_wei(250));
+
+        // Empty pool: 1 share per wei; large values don't overflow
+        let empty = DelegationPool::default();
+        assert_eq!(
+            empty.shares_for(Grt::from_grt(1_000_000)),
+            Grt::from_grt(1_000_000).wei()
+        );
+        assert_eq!(empty.tokens_for(1), Grt::ZERO);
+    }
+
+    #[test]
+    fn parses_subgraph_delegator() {
+        let delegator: SubgraphDelegator = serde_json::from_value(serde_json::json!({
+            "id": "0x23618e81e3f5cdf7f54c3d65f7fbc0abf5b21e8f",
+            "totalStakedTokens": "2000000000000000000",
+            "totalUnstakedTokens": "0",
+            "stakes": [{
+                "id": "0x23618e81e3f5cdf7f54c3d65f7fbc0abf5b21e8f-0xf4ef6650e48d099a4972ea5b414dab86e1998bd3",
+                "indexer": { "id": "0xf4ef6650e48d099a4972ea5b414dab86e1998bd3" },
+                "stakedTokens": "2000000000000000000",
+                "unstakedTokens": "0",
+                "lockedTokens": "0",
+                "shareAmount": "2000000000000000000"
+            }]
+        }))
+        .unwrap();
+        let indexer =
+            crate::ids::parse_address("0xf4ef6650e48d099a4972ea5b414dab86e1998bd3").unwrap();
+        let stake = delegator.stake_with(indexer).unwrap();
+        assert_eq!(stake.staked_tokens, Grt::from_grt(2));
+        assert_eq!(stake.share_amount, Grt::from_grt(2).wei());
+        assert!(delegator.stake_with(Address::ZERO).is_none());
+    }
+}
@@ -0,0 +1,500 @@
+//! DisputeManager client: indexing and query disputes, arbitration, slashing.
+//!
+//! A fisherman deposits `disputeDeposit` GRT to open a dispute against an
+//! indexer; the arbitrator then resolves it:
+//!
+//!   - `create_indexing_dispute_as` → `createIndexingDispute(allocationId, poi, blockNumber)`
+//!   - `create_query_dispute_as` → `createQueryDispute(attestation)`
+//!   - `create_query_dispute_conflict_as` → `createQueryDisputeConflict(a1, a2)`
+//!     (same request, different responses; no deposit)
+//!   - `accept_dispute` / `accept_dispute_conflict` → slash the indexer's
+//!     provision, reward the fisherman, return the deposit
+//!   - `reject_dispute` → deposit burned; `draw_dispute` → deposit returned
+//!   - `cancel_dispute_as` → fisherman withdraws after the dispute period
+//!
+//! Accepting slashes `tokensSlash` from the provision via SubgraphService; the
+//! fisherman receives `min(fishermanRewardCut, maxVerifierCut)` of it
+//! (`expected_fisherman_reward`). Dispute IDs are taken from the creation
+//! events, so tests don't depend on how the contract derives them.
+
+use alloy_primitives::{Bytes, hex};
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{SolType, sol};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::attestation::Attestation;
+use crate::grt::PPM;
+use crate::{Address, B256, Grt, TestNetwork};
+
+sol! {
+    /// `IDisputeManager.Dispute` as returned by `disputes(bytes32)`.
+    struct DisputeState {
+        address indexer;
+        address fisherman;
+        uint256 deposit;
+        bytes32 relatedDisputeId;
+        uint8 disputeType;
+        uint8 status;
+        uint256 createdAt;
+        uint256 cancellableAt;
+        uint256 stakeSnapshot;
+    }
+}
+
+/// `IDisputeManager.DisputeType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeType {
+    Null,
+    IndexingDispute,
+    QueryDispute,
+    LegacyDispute,
+}
+
+/// `IDisputeManager.DisputeStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeStatus {
+    Null,
+    Accepted,
+    Rejected,
+    Drawn,
+    Pending,
+    Cancelled,
+}
+
+impl DisputeType {
+    fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Null,
+            1 => Self::IndexingDispute,
+            2 => Self::QueryDispute,
+            3 => Self::LegacyDispute,
+            v => anyhow::bail!("unknown dispute type {v}"),
+        })
+    }
+}
+
+impl DisputeStatus {
+    fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Null,
+            1 => Self::Accepted,
+            2 => Self::Rejected,
+            3 => Self::Drawn,
+            4 => Self::Pending,
+            5 => Self::Cancelled,
+            v => anyhow::bail!("unknown dispute status {v}"),
+        })
+    }
+}
+
+/// On-chain dispute state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dispute {
+    pub id: B256,
+    pub indexer: Address,
+    pub fisherman: Address,
+    pub deposit: Grt,
+    /// The other dispute of a conflicting-attestation pair (zero otherwise).
+    pub related_dispute_id: B256,
+    pub dispute_type: DisputeType,
+    pub status: DisputeStatus,
+    pub created_at: u64,
+    pub cancellable_at: u64,
+    /// Provision + delegation at creation, the base for `maxSlashingCut`.
+    pub stake_snapshot: Grt,
+}
+
+impl Dispute {
+    pub fn decode(id: B256, data: &[u8]) -> Result<Self> {
+        let s = <DisputeState as SolType>::abi_decode(data).context("decoding Dispute")?;
+        Ok(Self {
+            id,
+            indexer: s.indexer,
+            fisherman: s.fisherman,
+            deposit: Grt::from_wei(s.deposit.to()),
+            related_dispute_id: s.relatedDisputeId,
+            dispute_type: DisputeType::from_u8(s.disputeType)?,
+            status: DisputeStatus::from_u8(s.status)?,
+            created_at: s.createdAt.to(),
+            cancellable_at: s.cancellableAt.to(),
+            stake_snapshot: Grt::from_wei(s.stakeSnapshot.to()),
+        })
+    }
+
+    pub fn exists(&self) -> bool {
+        self.dispute_type != DisputeType::Null
+    }
+}
+
+/// DisputeManager configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisputeParameters {
+    pub arbitrator: Address,
+    /// Seconds before a fisherman may cancel.
+    pub dispute_period: u64,
+    pub dispute_deposit: Grt,
+    /// Share of the slashed tokens paid to the fisherman, in PPM.
+    pub fisherman_reward_cut: u32,
+    /// Max share of the stake snapshot that can be slashed, in PPM.
+    pub max_slashing_cut: u32,
+}
+
+impl DisputeParameters {
+    /// Largest `tokensSlash` the arbitrator may pass for `dispute`.
+    pub fn max_tokens_slash(&self, dispute: &Dispute) -> Grt {
+        dispute.stake_snapshot.mul_ppm(self.max_slashing_cut)
+    }
+}
+
+/// Tokens the fisherman is rewarded when `tokens_slash` is slashed from a
+/// provision of `provision_tokens` with `max_verifier_cut` (PPM).
+pub fn expected_fisherman_reward(
+    tokens_slash: Grt,
+    fisherman_reward_cut: u32,
+    max_verifier_cut: u32,
+    provision_tokens: Grt,
+) -> Grt {
+    let cut = fisherman_reward_cut.min(max_verifier_cut).min(PPM);
+    tokens_slash.min(provision_tokens).mul_ppm(cut)
+}
+
+/// `Dispute` entity from the network subgraph.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphDispute {
+    pub id: B256,
+    /// `SingleQuery`, `Conflicting` or `Indexing`.
+    #[serde(rename = "type")]
+    pub dispute_type: String,
+    pub status: String,
+    pub tokens_slashed: Grt,
+    pub tokens_rewarded: Grt,
+    pub tokens_burned: Grt,
+    pub deposit: Grt,
+    #[serde(deserialize_with = "crate::graphql::entity_id")]
+    pub indexer: Address,
+    #[serde(deserialize_with = "crate::graphql::entity_id")]
+    pub fisherman: Address,
+}
+
+impl TestNetwork {
+    /// Current DisputeManager configuration.
+    pub fn dispute_parameters(&self) -> Result<DisputeParameters> {
+        let dm = self.contracts.dispute_manager;
+        let uint = |sig: &str| -> Result<u128> {
+            let output = self.cast_call(dm, sig, &[])?;
+            crate::cast::cast_parse_uint(&output)
+                .parse()
+                .with_context(|| format!("parsing {sig}"))
+        };
+        Ok(DisputeParameters {
+            arbitrator: crate::ids::parse_address(&self.cast_call(
+                dm,
+                "arbitrator()(address)",
+                &[],
+            )?)?,
+            dispute_period: uint("disputePeriod()(uint64)")? as u64,
+            dispute_deposit: Grt::from_wei(uint("disputeDeposit()(uint256)")?),
+            fisherman_reward_cut: uint("fishermanRewardCut()(uint32)")? as u32,
+            max_slashing_cut: uint("maxSlashingCut()(uint32)")? as u32,
+        })
+    }
+
+    /// The private key of the arbitrator (account0 or the governor).
+    pub fn arbitrator_secret(&self) -> Result<&str> {
+        let arbitrator = self.dispute_parameters()?.arbitrator;
+        for secret in [&self.account0_secret, &self.account1_secret] {
+            let signer: PrivateKeySigner = secret.parse().context("parsing account key")?;
+            if signer.address() == arbitrator {
+                return Ok(secret);
+            }
+        }
+        anyhow::bail!("arbitrator {arbitrator} is neither account0 nor the governor")
+    }
+
+    /// On-chain dispute state (`exists()` is false for unknown IDs).
+    pub fn dispute(&self, id: B256) -> Result<Dispute> {
+        let output = self.cast_call(
+            self.contracts.dispute_manager,
+            "disputes(bytes32)",
+            &[&id.to_string()],
+        )?;
+        let data = hex::decode(output.trim()).context("decoding disputes output")?;
+        Dispute::decode(id, &data)
+    }
+
+    /// Open an indexing dispute against `allocation` for presenting `poi` at
+    /// `block_number`. Approves the deposit first; the fisherman needs GRT.
+    pub fn create_indexing_dispute_as(
+        &self,
+        fisherman_secret: &str,
+        allocation: Address,
+        poi: B256,
+        block_number: u64,
+    ) -> Result<Dispute> {
+        self.approve_dispute_deposit(fisherman_secret)?;
+        let disputes = self.send_creating_disputes(
+            fisherman_secret,
+            "createIndexingDispute(address,bytes32,uint256)",
+            &[
+                &allocation.to_string(),
+                &poi.to_string(),
+                &block_number.to_string(),
+            ],
+        )?;
+        disputes
+            .into_iter()
+            .next()
+            .context("no indexing dispute created")
+    }
+
+    /// Open a query dispute over a single attestation (e.g. from a gateway
+    /// response). Approves the deposit first; the fisherman needs GRT.
+    pub fn create_query_dispute_as(
+        &self,
+        fisherman_secret: &str,
+        attestation: &Attestation,
+    ) -> Result<Dispute> {
+        self.approve_dispute_deposit(fisherman_secret)?;
+        let data = Bytes::from(attestation.encode());
+        let disputes = self.send_creating_disputes(
+            fisherman_secret,
+            "createQueryDispute(bytes)",
+            &[&data.to_string()],
+        )?;
+        disputes
+            .into_iter()
+            .next()
+            .context("no query dispute created")
+    }
+
+    /// Open the pair of disputes for two conflicting attestations (same
+    /// request and deployment, different responses).
+    pub fn create_query_dispute_conflict_as(
+        &self,
+        fisherman_secret: &str,
+        attestation1: &Attestation,
+        attestation2: &Attestation,
+    ) -> Result<(Dispute, Dispute)> {
+        let disputes = self.send_creating_disputes(
+            fisherman_secret,
+            "createQueryDisputeConflict(bytes,bytes)",
+            &[
+                &Bytes::from(attestation1.encode()).to_string(),
+                &Bytes::from(attestation2.encode()).to_string(),
+            ],
+        )?;
+        match disputes.as_slice() {
+            [first, second] => Ok((*first, *second)),
+            other => anyhow::bail!("expected 2 conflicting disputes, got {}", other.len()),
+        }
+    }
+
+    /// Accept a dispute, slashing `tokens_slash` from the indexer's provision.
+    /// Requires the arbitrator.
+    pub fn accept_dispute(&self, id: B256, tokens_slash: Grt) -> Result<Dispute> {
+        self.cast_send_as(
+            self.arbitrator_secret()?,
+            self.contracts.dispute_manager,
+            "acceptDispute(bytes32,uint256)",
+            &[&id.to_string(), &tokens_slash.to_wei_string()],
+        )
+        .with_context(|| format!("accepting dispute {id}"))?;
+        self.dispute(id)
+    }
+
+    /// Accept one dispute of a conflicting pair; the related one is accepted
+    /// too (slashing `related_slash`) or drawn. Requires the arbitrator.
+    pub fn accept_dispute_conflict(
+        &self,
+        id: B256,
+        tokens_slash: Grt,
+        accept_related: bool,
+        related_slash: Grt,
+    ) -> Result<Dispute> {
+        self.cast_send_as(
+            self.arbitrator_secret()?,
+            self.contracts.dispute_manager,
+            "acceptDisputeConflict(bytes32,uint256,bool,uint256)",
+            &[
+                &id.to_string(),
+                &tokens_slash.to_wei_string(),
+                if accept_related { "true" } else { "false" },
+                &related_slash.to_wei_string(),
+            ],
+        )
+        .with_context(|| format!("accepting conflicting dispute {id}"))?;
+        self.dispute(id)
+    }
+
+    /// Reject a dispute; the fisherman's deposit is burned. Requires the arbitrator.
+    pub fn reject_dispute(&self, id: B256) -> Result<Dispute> {
+        self.resolve_dispute("rejectDispute(bytes32)", id)
+    }
+
+    /// Draw a dispute (and its related one); the deposit is returned.
+    /// Requires the arbitrator.
+    pub fn draw_dispute(&self, id: B256) -> Result<Dispute> {
+        self.resolve_dispute("drawDispute(bytes32)", id)
+    }
+
+    /// Cancel a pending dispute as its fisherman once `cancellable_at` has
+    /// passed; the deposit is returned.
+    pub fn cancel_dispute_as(&self, fisherman_secret: &str, id: B256) -> Result<Dispute> {
+        self.cast_send_as(
+            fisherman_secret,
+            self.contracts.dispute_manager,
+            "cancelDispute(bytes32)",
+            &[&id.to_string()],
+        )
+        .with_context(|| format!("cancelling dispute {id}"))?;
+        self.dispute(id)
+    }
+
+    /// The dispute as indexed by the network subgraph (`None` if not yet indexed).
+    pub async fn query_dispute(&self, id: B256) -> Result<Option<SubgraphDispute>> {
+        let query = format!(
+            r#"{{ dispute(id: "{id}") {{
+                id type status tokensSlashed tokensRewarded tokensBurned deposit
+                indexer {{ id }} fisherman {{ id }}
+            }} }}"#
+        );
+        let resp = self.subgraph_query(&query).await?;
+        serde_json::from_value(resp["data"]["dispute"].clone()).context("parsing dispute")
+    }
+
+    fn resolve_dispute(&self, sig: &str, id: B256) -> Result<Dispute> {
+        self.cast_send_as(
+            self.arbitrator_secret()?,
+            self.contracts.dispute_manager,
+            sig,
+            &[&id.to_string()],
+        )
+        .with_context(|| format!("{sig} {id}"))?;
+        self.dispute(id)
+    }
+
+    fn approve_dispute_deposit(&self, fisherman_secret: &str) -> Result<()> {
+        let deposit = self.dispute_parameters()?.dispute_deposit;
+        self.cast_send_as(
+            fisherman_secret,
+            self.contracts.grt_token,
+            "approve(address,uint256)",
+            &[
+                &self.contracts.dispute_manager.to_string(),
+                &deposit.to_wei_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Send a dispute-creating transaction and return the disputes it
+    /// created, read back from the first indexed topic of DisputeManager's
+    /// events in the transaction's block range.
+    fn send_creating_disputes(
+        &self,
+        fisherman_secret: &str,
+        sig: &str,
+        args: &[&str],
+    ) -> Result<Vec<Dispute>> {
+        let from_block = self.get_block_number_sync()?;
+        self.cast_send_as(fisherman_secret, self.contracts.dispute_manager, sig, args)
+            .with_context(|| format!("sending {sig}"))?;
+        let to_block = self.get_block_number_sync()?;
+        let fisherman: PrivateKeySigner =
+            fisherman_secret.parse().context("parsing fisherman key")?;
+
+        let mut disputes: Vec<Dispute> = Vec::new();
+        for log in self.cast_logs_json(self.contracts.dispute_manager, from_block, to_block)? {
+            let Some(id) = log["topics"]
+                .get(1)
+                .and_then(|t| t.as_str())
+                .and_then(|t| t.parse::<B256>().ok())
+            else {
+                continue;
+            };
+            if disputes.iter().any(|d| d.id == id) {
+                continue;
+            }
+            let dispute = self.dispute(id)?;
+            if dispute.exists()
+                && dispute.fisherman == fisherman.address()
+                && dispute.status == DisputeStatus::Pending
+            {
+                disputes.push(dispute);
+            }
+        }
+        Ok(disputes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn decodes_dispute() {
+        let related = B256::repeat_byte(0x22);
+        let state = DisputeState {
+            indexer: Address::repeat_byte(0x01),
+            fisherman: Address::repeat_byte(0x02),
+            deposit: U256::from(10u64).pow(U256::from(18u64)),
+            relatedDisputeId: related,
+            disputeType: 2,
+            status: 4,
+            createdAt: U256::from(1_700_000_000u64),
+            cancellableAt: U256::from(1_700_003_600u64),
+            stakeSnapshot: U256::from(5_000u64),
+        };
+        let data = <DisputeState as SolType>::abi_encode(&state);
+        let dispute = Dispute::decode(B256::repeat_byte(0x11), &data).unwrap();
+        assert!(dispute.exists());
+        assert_eq!(dispute.dispute_type, DisputeType::QueryDispute);
+        assert_eq!(dispute.status, DisputeStatus::Pending);
+        assert_eq!(dispute.deposit, Grt::ONE);
+        assert_eq!(dispute.related_dispute_id, related);
+        assert_eq!(dispute.cancellable_at, 1_700_003_600);
+
+        let unknown = Dispute::decode(B256::ZERO, &[0u8; 9 * 32]).unwrap();
+        assert!(!unknown.exists());
+        assert_eq!(unknown.status, DisputeStatus::Null);
+    }
+
+    #[test]
+    fn fisherman_reward_is_capped() {
+        let slash = Grt::from_grt(100);
+        // 50% reward cut, verifier allows up to 100%
+        assert_eq!(
+            expected_fisherman_reward(slash, 500_000, PPM, Grt::from_grt(1_000)),
+            Grt::from_grt(50)
+        );
+        // Provision's maxVerifierCut is lower than the reward cut
+        assert_eq!(
+            expected_fisherman_reward(slash, 500_000, 100_000, Grt::from_grt(1_000)),
+            Grt::from_grt(10)
+        );
+        // Only what the provision holds can be slashed
+        assert_eq!(
+            expected_fisherman_reward(slash, 500_000, PPM, Grt::from_grt(40)),
+            Grt::from_grt(20)
+        );
+    }
+
+    #[test]
+    fn max_tokens_slash_from_snapshot() {
+        let params = DisputeParameters {
+            arbitrator: Address::ZERO,
+            dispute_period: 0,
+            dispute_deposit: Grt::ONE,
+            fisherman_reward_cut: 0,
+            max_slashing_cut: 250_000,
+        };
+        let dispute = Dispute {
+            stake_snapshot: Grt::from_grt(1_000),
+            ..Dispute::decode(B256::ZERO, &[0u8; 9 * 32]).unwrap()
+        };
+        assert_eq!(params.max_tokens_slash(&dispute), Grt::from_grt(250));
+    }
+}
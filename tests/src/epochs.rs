@@ -0,0 +1,191 @@
+//! Epoch state, epoch length changes, and fast epoch advancement.
+//!
+//! EpochManager derives the epoch from the block number and the last length
+//! change:
+//!
+//!   - `currentEpoch = lastLengthUpdateEpoch + (block - lastLengthUpdateBlock) / epochLength`
+//!   - `setEpochLength` keeps the current epoch's start block, so the new
+//!     length applies to the epoch already in progress
+//!
+//! Shortening the length below the blocks the current epoch has already run
+//! therefore jumps straight past one or more epochs, which the block oracle
+//! never sees and the indexer-agent later fails on. `set_epoch_length` avoids
+//! this by landing such a change on the next epoch's first block, and
+//! `advance_to_epoch` shortens epochs (restoring the length afterwards) so
+//! tests needing several epochs don't mine full-length ones.
+
+use anyhow::{Context, Result};
+
+use crate::TestNetwork;
+use crate::cast::cast_parse_uint;
+use crate::governance::GovernanceParameter;
+
+/// Epoch length used by `advance_to_epoch`: comfortably more than the blocks
+/// mined while waiting for the subgraphs to sync each epoch.
+pub const FAST_EPOCH_LENGTH: u64 = 20;
+
+/// EpochManager's view at a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochState {
+    pub block: u64,
+    pub epoch: u64,
+    /// First block of `epoch`.
+    pub epoch_block: u64,
+    /// Epoch length in blocks.
+    pub length: u64,
+}
+
+impl EpochState {
+    pub fn blocks_into_epoch(&self) -> u64 {
+        self.block - self.epoch_block
+    }
+
+    /// Blocks to mine before the next epoch starts (at least one).
+    pub fn blocks_until_next(&self) -> u64 {
+        self.epoch_block + self.length - self.block
+    }
+
+    /// The state at a later `block`, with the length unchanged.
+    pub fn at(&self, block: u64) -> EpochState {
+        let epochs = (block - self.epoch_block) / self.length;
+        EpochState {
+            block,
+            epoch: self.epoch + epochs,
+            epoch_block: self.epoch_block + epochs * self.length,
+            length: self.length,
+        }
+    }
+
+    /// The state after `setEpochLength(length)` is mined in the next block.
+    pub fn with_length(&self, length: u64) -> EpochState {
+        let next = self.at(self.block + 1);
+        EpochState { length, ..next }.at(next.block)
+    }
+
+    /// Whether setting `length` in the next block would end the current epoch
+    /// early, skipping epochs.
+    pub fn length_change_skips(&self, length: u64) -> bool {
+        length == 0 || self.with_length(length).epoch != self.at(self.block + 1).epoch
+    }
+}
+
+impl TestNetwork {
+    /// EpochManager's state at the latest block.
+    pub fn epoch_state(&self) -> Result<EpochState> {
+        let em = self.contracts.epoch_manager;
+        let block = self.get_block_number_sync()?;
+        let read = |sig: &str| -> Result<u64> {
+            let raw = self.cast_call_at(block, em, sig, &[])?;
+            cast_parse_uint(&raw)
+                .parse()
+                .with_context(|| format!("parsing {sig} {raw:?}"))
+        };
+        Ok(EpochState {
+            block,
+            epoch: read("currentEpoch()(uint256)")?,
+            epoch_block: read("currentEpochBlock()(uint256)")?,
+            length: read("epochLength()(uint256)")?,
+        })
+    }
+
+    /// Set the epoch length (governor) without skipping epochs. If the
+    /// current epoch has already run `length` blocks, mines to the next epoch
+    /// and applies the change on its first block, then waits for the
+    /// subgraphs to sync that epoch. Returns the state afterwards.
+    pub async fn set_epoch_length(&self, length: u64) -> Result<EpochState> {
+        anyhow::ensure!(length > 0, "epoch length must be positive");
+        let state = self.epoch_state()?;
+        if state.length == length {
+            return Ok(state);
+        }
+        if state.length_change_skips(length) {
+            let blocks = state.blocks_until_next() - 1;
+            eprintln!(
+                "set_epoch_length: epoch {} is {} blocks in, mining {blocks} blocks \
+                 to apply {length} at the next epoch",
+                state.epoch,
+                state.blocks_into_epoch()
+            );
+            self.mine_blocks(blocks as u32).await?;
+        }
+        self.governance_set(GovernanceParameter::EpochLength, length)?;
+
+        let after = self.epoch_state()?;
+        eprintln!(
+            "set_epoch_length: {} → {length} blocks, epoch {}",
+            state.length, after.epoch
+        );
+        if after.epoch > state.epoch {
+            self.run_epoch_and_sync().await?;
+        }
+        Ok(after)
+    }
+
+    /// Advance to at least epoch `target` by the fastest path that keeps the
+    /// block oracle in step: for more than one epoch the length is shortened
+    /// to `FAST_EPOCH_LENGTH` and restored on arrival. Each epoch is still run
+    /// and synced individually. Returns the new epoch.
+    pub async fn advance_to_epoch(&self, target: u64) -> Result<u64> {
+        let state = self.epoch_state()?;
+        if state.epoch >= target {
+            return Ok(state.epoch);
+        }
+        eprintln!(
+            "advance_to_epoch: {} → {target}, epoch length {}",
+            state.epoch, state.length
+        );
+
+        let mut overrides = self.governance_override();
+        if target - state.epoch > 1 && state.length > FAST_EPOCH_LENGTH {
+            overrides.set_epoch_length(FAST_EPOCH_LENGTH).await?;
+        }
+        let mut epoch = self.epoch_state()?.epoch;
+        while epoch < target {
+            epoch = self.advance_epochs(1).await?;
+        }
+        // Lengthening never skips epochs
+        overrides.restore()?;
+        Ok(epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(block: u64) -> EpochState {
+        EpochState {
+            block,
+            epoch: 10,
+            epoch_block: 100,
+            length: 50,
+        }
+    }
+
+    #[test]
+    fn projects_epochs_at_later_blocks() {
+        let now = state(130);
+        assert_eq!(now.blocks_into_epoch(), 30);
+        assert_eq!(now.blocks_until_next(), 20);
+        let later = now.at(260);
+        assert_eq!((later.epoch, later.epoch_block), (13, 250));
+        assert_eq!(later.blocks_until_next(), 40);
+    }
+
+    #[test]
+    fn shortening_mid_epoch_skips() {
+        // setEpochLength would be mined in block 131, 31 blocks into epoch 10
+        let now = state(130);
+        assert!(!now.length_change_skips(32));
+        assert!(!now.length_change_skips(500));
+        assert!(now.length_change_skips(31));
+        assert!(now.length_change_skips(0));
+        let short = now.with_length(10);
+        assert_eq!((short.epoch, short.epoch_block), (13, 130));
+
+        // On the next epoch's first block any length is safe
+        let boundary = state(149);
+        assert!(!boundary.length_change_skips(1));
+        assert_eq!(boundary.with_length(1).epoch, 11);
+    }
+}
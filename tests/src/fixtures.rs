@@ -0,0 +1,80 @@
+//! Shared test fixtures: common amounts, timeouts and spare dev accounts.
+//!
+//! The Hardhat dev mnemonic funds accounts #0-#9 with ETH. #0 and #1 have
+//! network roles (see `.env`); #3-#9 have none, so tests give them roles of
+//! their own and fund them with GRT as needed.
+
+use crate::ids::address;
+use crate::{Address, Grt};
+use std::time::Duration;
+
+/// Allocation size used when tests (re)create allocations (0.01 GRT).
+pub const ALLOCATION_AMOUNT: Grt = Grt::from_wei(10_000_000_000_000_000);
+
+/// How long to wait for the network subgraph to index a transaction.
+pub const INDEX_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A Hardhat dev account: private key and address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DevAccount {
+    pub key: &'static str,
+    pub address: Address,
+}
+
+/// Hardhat account #3 — has ETH but no role.
+pub const ACCOUNT3: DevAccount = DevAccount {
+    key: "0x7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6",
+    address: address!("0x90F79bf6EB2c4f870365E785982E1f101E93b906"),
+};
+
+/// Hardhat account #4 — has ETH but no role.
+pub const ACCOUNT4: DevAccount = DevAccount {
+    key: "0x47e179ec197488593b187f80a00eb0da91f1b9d0b13f8733639f19c30a34926a",
+    address: address!("0x15d34AAf54267DB7D7c367839AAf71A00a2C6A65"),
+};
+
+/// Hardhat account #5 — has ETH but no role.
+pub const ACCOUNT5: DevAccount = DevAccount {
+    key: "0x8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba",
+    address: address!("0x9965507D1a55bcC2695C58ba16FB37d819B0A4dc"),
+};
+
+/// Hardhat account #6 — has ETH but no role.
+pub const ACCOUNT6: DevAccount = DevAccount {
+    key: "0x92db14e403b83dfe3df233f83dfa3a0d7096f21ca9b0d6d6b8d88b2b4ec1564e",
+    address: address!("0x976EA74026E726554dB657fA54763abd0C3a0aa9"),
+};
+
+/// Hardhat account #7 — has ETH but no role.
+pub const ACCOUNT7: DevAccount = DevAccount {
+    key: "0x4bbbf85ce3377467afe5d46f804f221813b2bb87f24d81f60f1fcdbf7cbf4356",
+    address: address!("0x14dC79964da2C08b23698B3D3cc7Ca32193d9955"),
+};
+
+/// Hardhat account #8 — has ETH but no role.
+pub const ACCOUNT8: DevAccount = DevAccount {
+    key: "0xdbda1821b80551c9d65939329250298aa3472ba22feea921c0cf5d620ea67b97",
+    address: address!("0x23618e81E3f5cdF7f54C3d65f7FBc0aBf5B21E8f"),
+};
+
+/// Hardhat account #9 — has ETH but no role.
+pub const ACCOUNT9: DevAccount = DevAccount {
+    key: "0x2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6",
+    address: address!("0xa0Ee7A142d267C1f36714E4a8F75612F20a79720"),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_signer_local::PrivateKeySigner;
+
+    #[test]
+    fn keys_match_addresses() {
+        for account in [
+            ACCOUNT3, ACCOUNT4, ACCOUNT5, ACCOUNT6, ACCOUNT7, ACCOUNT8, ACCOUNT9,
+        ] {
+            let signer: PrivateKeySigner = account.key.parse().unwrap();
+            assert_eq!(signer.address(), account.address, "{}", account.key);
+        }
+    }
+}
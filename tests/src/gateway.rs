@@ -0,0 +1,258 @@
+//! Typed gateway responses.
+//!
+//! The gateway answers almost everything with a GraphQL-shaped body, so a
+//! failed query is only distinguishable by its error message. A response
+//! is split into:
+//!
+//!   - `data`: the subgraph result (attested by the serving indexer)
+//!   - `graphql_errors`: errors produced by graph-node for the query itself
+//!   - `gateway_error`: the gateway could not serve the query at all
+//!
+//! Gateway error messages (edgeandnode/gateway `errors.rs`):
+//!   - `auth error: ...`            → `Unauthorized` (bad/missing API key)
+//!   - `subgraph not found: ...`    → `SubgraphNotFound`
+//!   - `no indexers found`          → `NoIndexers`
+//!   - `bad indexers: ...`          → `IndexerErrors`, or `StaleAttestationSigner`
+//!     when the indexer's attestation does not match its allocation
+//!   - `bad query: ...`             → `BadQuery`
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt;
+
+use crate::attestation::Attestation;
+
+/// Why the gateway failed to serve a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GatewayErrorKind {
+    /// Missing, unknown or unauthorized API key.
+    Unauthorized,
+    /// Subgraph or deployment unknown to the gateway.
+    SubgraphNotFound,
+    /// No indexer is allocated to (or eligible for) the deployment.
+    NoIndexers,
+    /// Every selected indexer failed to answer.
+    IndexerErrors,
+    /// The indexer signed with a key that doesn't match an active allocation,
+    /// typically right after an allocation was closed and recreated.
+    StaleAttestationSigner,
+    /// The gateway rejected the query document.
+    BadQuery,
+    /// Anything else (internal errors, non-JSON error pages).
+    Other,
+}
+
+/// A gateway-level failure with the original message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayError {
+    pub kind: GatewayErrorKind,
+    pub status: u16,
+    pub message: String,
+}
+
+impl GatewayError {
+    /// Classify a gateway error message. Returns `None` for messages that
+    /// aren't gateway errors (i.e. GraphQL errors passed through from
+    /// graph-node) on a successful HTTP status.
+    pub fn classify(status: u16, message: &str) -> Option<Self> {
+        use GatewayErrorKind::*;
+        let lower = message.to_lowercase();
+        let kind = if lower.starts_with("auth error") || status == 401 || status == 403 {
+            Unauthorized
+        } else if lower.starts_with("subgraph not found")
+            || lower.starts_with("deployment not found")
+            || status == 404
+        {
+            SubgraphNotFound
+        } else if lower.starts_with("no indexers") {
+            NoIndexers
+        } else if lower.starts_with("bad indexers") && lower.contains("attestation") {
+            StaleAttestationSigner
+        } else if lower.starts_with("bad indexers") {
+            IndexerErrors
+        } else if lower.starts_with("bad query") {
+            BadQuery
+        } else if !(200..300).contains(&status) {
+            Other
+        } else {
+            return None;
+        };
+        Some(GatewayError {
+            kind,
+            status,
+            message: message.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "gateway {:?} (HTTP {}): {}",
+            self.kind, self.status, self.message
+        )
+    }
+}
+
+impl std::error::Error for GatewayError {}
+
+/// A parsed gateway response.
+#[derive(Debug, Clone)]
+pub struct GatewayResponse {
+    pub status: u16,
+    pub data: Option<Value>,
+    pub graphql_errors: Vec<String>,
+    pub gateway_error: Option<GatewayError>,
+    pub attestation: Option<Attestation>,
+    /// Raw response body (the attested bytes).
+    pub body: String,
+}
+
+impl GatewayResponse {
+    /// Consume an HTTP response from the gateway.
+    pub async fn from_response(resp: reqwest::Response) -> Result<Self> {
+        let status = resp.status().as_u16();
+        let attestation = Attestation::from_headers(resp.headers())?;
+        let body = resp.text().await.context("reading gateway response")?;
+        Ok(Self::parse(status, body, attestation))
+    }
+
+    fn parse(status: u16, body: String, attestation: Option<Attestation>) -> Self {
+        let json: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+        let data = Some(json["data"].clone()).filter(|d| !d.is_null());
+        let messages: Vec<String> = json["errors"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|e| e["message"].as_str().unwrap_or_default().to_string())
+            .collect();
+
+        let mut gateway_error = None;
+        let mut graphql_errors = Vec::new();
+        for message in messages {
+            match GatewayError::classify(status, &message) {
+                Some(error) if gateway_error.is_none() => gateway_error = Some(error),
+                Some(_) => {}
+                None => graphql_errors.push(message),
+            }
+        }
+        if gateway_error.is_none() && data.is_none() && graphql_errors.is_empty() {
+            // No GraphQL body at all: an error page or empty response
+            let message = if body.trim().is_empty() {
+                format!("HTTP {status} with empty body")
+            } else {
+                body.trim().to_string()
+            };
+            gateway_error = Some(GatewayError {
+                kind: GatewayErrorKind::Other,
+                status,
+                message,
+            });
+        }
+        Self {
+            status,
+            data,
+            graphql_errors,
+            gateway_error,
+            attestation,
+            body,
+        }
+    }
+
+    /// Data returned without any gateway or GraphQL error.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+            && self.gateway_error.is_none()
+            && self.graphql_errors.is_empty()
+            && self.data.is_some()
+    }
+
+    /// The gateway failure kind, if any.
+    pub fn error_kind(&self) -> Option<GatewayErrorKind> {
+        self.gateway_error.as_ref().map(|e| e.kind)
+    }
+
+    /// The query data, or the reason there is none.
+    ///
+    /// A `GatewayError` is returned as the error source so callers can
+    /// `downcast_ref::<GatewayError>()` to assert the failure kind.
+    pub fn into_data(self) -> Result<Value> {
+        if let Some(error) = self.gateway_error {
+            return Err(error.into());
+        }
+        if !self.graphql_errors.is_empty() {
+            anyhow::bail!("GraphQL errors: {}", self.graphql_errors.join("; "));
+        }
+        self.data.context("gateway response has no data")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GatewayErrorKind::*;
+
+    fn parse(status: u16, body: &str) -> GatewayResponse {
+        GatewayResponse::parse(status, body.to_string(), None)
+    }
+
+    #[test]
+    fn successful_data() {
+        let resp = parse(200, r#"{"data":{"_meta":{"block":{"number":42}}}}"#);
+        assert!(resp.is_success());
+        assert_eq!(resp.error_kind(), None);
+        assert_eq!(resp.into_data().unwrap()["_meta"]["block"]["number"], 42);
+    }
+
+    #[test]
+    fn classifies_gateway_errors() {
+        for (status, message, kind) in [
+            (200, "auth error: API key not found", Unauthorized),
+            (401, "missing authorization header", Unauthorized),
+            (
+                200,
+                "subgraph not found: no allowed deployments",
+                SubgraphNotFound,
+            ),
+            (200, "no indexers found", NoIndexers),
+            (
+                200,
+                "bad indexers: {0xf4ef…: BadResponse(bad attestation: signer mismatch)}",
+                StaleAttestationSigner,
+            ),
+            (
+                200,
+                "bad indexers: {0xf4ef…: Unavailable(NoStatus)}",
+                IndexerErrors,
+            ),
+            (200, "bad query: failed to parse", BadQuery),
+            (500, "internal error: boom", Other),
+        ] {
+            let body = serde_json::json!({ "errors": [{ "message": message }] }).to_string();
+            let resp = parse(status, &body);
+            assert_eq!(resp.error_kind(), Some(kind), "{message}");
+            assert!(!resp.is_success());
+            let err = resp.into_data().unwrap_err();
+            assert_eq!(err.downcast_ref::<GatewayError>().unwrap().kind, kind);
+        }
+    }
+
+    #[test]
+    fn graphql_errors_are_not_gateway_errors() {
+        let resp = parse(
+            200,
+            r#"{"data":null,"errors":[{"message":"Type `Query` has no field `nope`"}]}"#,
+        );
+        assert_eq!(resp.error_kind(), None);
+        assert_eq!(resp.graphql_errors.len(), 1);
+        assert!(!resp.is_success());
+    }
+
+    #[test]
+    fn non_graphql_body_is_other() {
+        let resp = parse(502, "Bad Gateway");
+        assert_eq!(resp.error_kind(), Some(Other));
+        assert_eq!(resp.gateway_error.unwrap().message, "Bad Gateway");
+    }
+}
@@ -0,0 +1,294 @@
+//! Subgraph publishing on L2GNS.
+//!
+//! A subgraph is an NFT on L2GNS pointing at its current version's
+//! deployment. account0 publishes and owns every subgraph on the local
+//! network (`start-indexing` publishes the network, TAP and block-oracle
+//! subgraphs the same way as `scripts/publish-subgraph.sh`):
+//!
+//!   - `publish_subgraph` → `publishNewSubgraph(deployment, versionMeta, subgraphMeta)`,
+//!     returns the new `SubgraphId` from the `SubgraphPublished` event
+//!   - `publish_new_version` → `publishNewVersion(subgraph, deployment, versionMeta)`
+//!   - `deprecate_subgraph` → `deprecateSubgraph(subgraph)`
+//!   - `update_subgraph_metadata` → `updateSubgraphMetadata(subgraph, subgraphMeta)`
+//!
+//! Metadata arguments are the bytes32 digest of an IPFS metadata file;
+//! `B256::ZERO` publishes without metadata, like the shell scripts.
+//!
+//! The gateway resolves `/api/subgraphs/id/<id>` from the network subgraph's
+//! `Subgraph.currentVersion`, refreshed periodically. After a change,
+//! `wait_for_subgraph_version` waits for the network subgraph and
+//! `wait_for_gateway_subgraph` for the gateway to serve (or drop) the
+//! subgraph; `for_subgraph` points `gateway_query` at another subgraph.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::gateway::GatewayErrorKind;
+use crate::load::META_QUERY;
+use crate::polling::PollResult;
+use crate::{Address, B256, DeploymentId, SubgraphId, TestNetwork};
+
+const SUBGRAPH_PUBLISHED: &str = "SubgraphPublished(uint256,bytes32,uint32)";
+
+/// `Subgraph` entity from the network subgraph.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedSubgraph {
+    pub id: SubgraphId,
+    #[serde(deserialize_with = "crate::graphql::entity_id")]
+    pub owner: Address,
+    /// False once deprecated.
+    pub active: bool,
+    pub metadata_hash: Option<B256>,
+    pub current_version: Option<SubgraphVersion>,
+    pub versions: Vec<SubgraphVersion>,
+}
+
+impl PublishedSubgraph {
+    /// Deployment of the current version, if any.
+    pub fn current_deployment(&self) -> Option<DeploymentId> {
+        self.current_version.as_ref().map(|v| v.deployment)
+    }
+}
+
+/// `SubgraphVersion` entity: one published deployment of a subgraph.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphVersion {
+    /// 0 for the first version.
+    pub version: u32,
+    #[serde(rename = "subgraphDeployment", deserialize_with = "ipfs_hash")]
+    pub deployment: DeploymentId,
+    pub metadata_hash: Option<B256>,
+}
+
+/// Deserialize a `{ ipfsHash }` deployment reference.
+fn ipfs_hash<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<DeploymentId, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Deployment {
+        ipfs_hash: DeploymentId,
+    }
+    Ok(Deployment::deserialize(deserializer)?.ipfs_hash)
+}
+
+impl TestNetwork {
+    /// Publish a new subgraph on `deployment` as account0. Returns its ID.
+    pub fn publish_subgraph(
+        &self,
+        deployment: DeploymentId,
+        version_metadata: B256,
+        subgraph_metadata: B256,
+    ) -> Result<SubgraphId> {
+        let from_block = self.get_block_number_sync()?;
+        self.cast_send(
+            self.contracts.gns,
+            "publishNewSubgraph(bytes32,bytes32,bytes32)",
+            &[
+                &deployment.bytes32().to_string(),
+                &version_metadata.to_string(),
+                &subgraph_metadata.to_string(),
+            ],
+        )
+        .with_context(|| format!("publishing {deployment}"))?;
+        let to_block = self.get_block_number_sync()?;
+
+        let topic = self.cast_keccak(SUBGRAPH_PUBLISHED)?;
+        let logs = self.cast_logs_with_topic(self.contracts.gns, from_block, to_block, topic)?;
+        let deployment_topic = deployment.bytes32();
+        logs.iter()
+            .rev()
+            .find(|log| {
+                log["topics"][2]
+                    .as_str()
+                    .and_then(|t| t.parse::<B256>().ok())
+                    == Some(deployment_topic)
+            })
+            .and_then(|log| log["topics"][1].as_str())
+            .context("no SubgraphPublished event for the new subgraph")?
+            .parse()
+    }
+
+    /// Point `subgraph` at `deployment` as a new version.
+    pub fn publish_new_version(
+        &self,
+        subgraph: SubgraphId,
+        deployment: DeploymentId,
+        version_metadata: B256,
+    ) -> Result<()> {
+        self.cast_send(
+            self.contracts.gns,
+            "publishNewVersion(uint256,bytes32,bytes32)",
+            &[
+                &subgraph.uint256().to_string(),
+                &deployment.bytes32().to_string(),
+                &version_metadata.to_string(),
+            ],
+        )
+        .with_context(|| format!("publishing {deployment} as a new version of {subgraph}"))?;
+        Ok(())
+    }
+
+    /// Deprecate `subgraph`. Its name signal becomes withdrawable and it
+    /// can't be upgraded or re-activated.
+    pub fn deprecate_subgraph(&self, subgraph: SubgraphId) -> Result<()> {
+        self.cast_send(
+            self.contracts.gns,
+            "deprecateSubgraph(uint256)",
+            &[&subgraph.uint256().to_string()],
+        )
+        .with_context(|| format!("deprecating subgraph {subgraph}"))?;
+        Ok(())
+    }
+
+    /// Replace `subgraph`'s metadata digest.
+    pub fn update_subgraph_metadata(&self, subgraph: SubgraphId, metadata: B256) -> Result<()> {
+        self.cast_send(
+            self.contracts.gns,
+            "updateSubgraphMetadata(uint256,bytes32)",
+            &[&subgraph.uint256().to_string(), &metadata.to_string()],
+        )
+        .with_context(|| format!("updating metadata of subgraph {subgraph}"))?;
+        Ok(())
+    }
+
+    /// Whether `subgraph` is published and not deprecated.
+    pub fn subgraph_is_published(&self, subgraph: SubgraphId) -> Result<bool> {
+        let output = self.cast_call(
+            self.contracts.gns,
+            "isPublished(uint256)(bool)",
+            &[&subgraph.uint256().to_string()],
+        )?;
+        output
+            .trim()
+            .parse()
+            .with_context(|| format!("parsing isPublished {output:?}"))
+    }
+
+    /// Owner of the subgraph NFT. Reverts once the subgraph is deprecated,
+    /// which burns the NFT.
+    pub fn subgraph_owner(&self, subgraph: SubgraphId) -> Result<Address> {
+        let output = self.cast_call(
+            self.contracts.gns,
+            "ownerOf(uint256)(address)",
+            &[&subgraph.uint256().to_string()],
+        )?;
+        crate::ids::parse_address(&output)
+    }
+
+    /// A handle whose gateway queries (`gateway_query`, load helpers) go to
+    /// `subgraph` instead of `SUBGRAPH`.
+    pub fn for_subgraph(&self, subgraph: SubgraphId) -> TestNetwork {
+        TestNetwork {
+            subgraph_id: subgraph,
+            ..self.clone()
+        }
+    }
+
+    /// `subgraph` as indexed by the network subgraph (`None` if not indexed).
+    pub async fn query_subgraph(&self, subgraph: SubgraphId) -> Result<Option<PublishedSubgraph>> {
+        let query = format!(
+            r#"{{ subgraph(id: "{subgraph}") {{
+                id owner {{ id }} active metadataHash
+                currentVersion {{ version metadataHash subgraphDeployment {{ ipfsHash }} }}
+                versions(orderBy: version) {{ version metadataHash subgraphDeployment {{ ipfsHash }} }}
+            }} }}"#
+        );
+        let resp = self.subgraph_query(&query).await?;
+        serde_json::from_value(resp["data"]["subgraph"].clone()).context("parsing subgraph")
+    }
+
+    /// Wait until the network subgraph reports `subgraph` as active with
+    /// `deployment` as its current version.
+    pub async fn wait_for_subgraph_version(
+        &self,
+        subgraph: SubgraphId,
+        deployment: DeploymentId,
+        timeout: Duration,
+    ) -> Result<PublishedSubgraph> {
+        let result = self
+            .poll_until(timeout, Duration::from_secs(2), || async {
+                let indexed = self.query_subgraph(subgraph).await?;
+                Ok(indexed.filter(|s| s.active && s.current_deployment() == Some(deployment)))
+            })
+            .await;
+        match result {
+            PollResult::Ready(indexed) => Ok(indexed),
+            PollResult::TimedOut => anyhow::bail!(
+                "subgraph {subgraph} not indexed with version {deployment} within {timeout:?}"
+            ),
+        }
+    }
+
+    /// Wait until the gateway serves `subgraph` from `deployment` (an
+    /// attested response), or with `None` until it no longer knows the
+    /// subgraph (`SubgraphNotFound`, e.g. after deprecation).
+    pub async fn wait_for_gateway_subgraph(
+        &self,
+        subgraph: SubgraphId,
+        deployment: Option<DeploymentId>,
+        timeout: Duration,
+    ) -> Result<()> {
+        let target = self.for_subgraph(subgraph);
+        let result = self
+            .poll_until(timeout, Duration::from_secs(5), || async {
+                let resp = target.gateway_query(META_QUERY).await?;
+                let served = resp.attestation.as_ref().map(|a| a.deployment_id());
+                let done = match deployment {
+                    Some(deployment) => served == Some(deployment),
+                    None => resp.error_kind() == Some(GatewayErrorKind::SubgraphNotFound),
+                };
+                Ok(done.then_some(()))
+            })
+            .await;
+        match result {
+            PollResult::Ready(()) => Ok(()),
+            PollResult::TimedOut => match deployment {
+                Some(deployment) => anyhow::bail!(
+                    "gateway not serving subgraph {subgraph} from {deployment} within {timeout:?}"
+                ),
+                None => {
+                    anyhow::bail!("gateway still resolves subgraph {subgraph} after {timeout:?}")
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_subgraph_entity() {
+        let deployment = "QmRDGLp6BHwiH9HAE2NYEE3f7LrKuRqziHBv76trT4etgU";
+        let subgraph: PublishedSubgraph = serde_json::from_value(json!({
+            "id": "BFr2mx7FgkJ36Y6pE5BiXs1KmNUmVDCnL82KUSdcLW1g",
+            "owner": { "id": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266" },
+            "active": true,
+            "metadataHash": null,
+            "currentVersion": {
+                "version": 1,
+                "metadataHash": format!("0x{}", "ab".repeat(32)),
+                "subgraphDeployment": { "ipfsHash": deployment }
+            },
+            "versions": []
+        }))
+        .unwrap();
+        assert_eq!(
+            subgraph.id.to_string(),
+            "BFr2mx7FgkJ36Y6pE5BiXs1KmNUmVDCnL82KUSdcLW1g"
+        );
+        assert_eq!(
+            subgraph.current_deployment(),
+            Some(deployment.parse().unwrap())
+        );
+        assert_eq!(
+            subgraph.current_version.unwrap().metadata_hash,
+            Some(B256::repeat_byte(0xab))
+        );
+        assert_eq!(subgraph.metadata_hash, None);
+    }
+}
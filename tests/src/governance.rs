@@ -0,0 +1,442 @@
+//! Tunable protocol parameters and scoped overrides.
+//!
+//! Every parameter a test may want to change, with the contract call that
+//! reads and writes it and the account allowed to write it:
+//!
+//!   - RewardsManager (governor): issuance per block, minimum subgraph signal,
+//!     subgraph availability oracle, default reclaim address
+//!   - SubgraphService (owner = governor): maxPOIStaleness, stake-to-fees ratio,
+//!     curation cut, minimum provision, delegation ratio
+//!   - EpochManager (governor): epoch length
+//!   - L2Curation (governor): curation tax
+//!   - REO (OPERATOR_ROLE = account0): eligibility period, oracle update timeout
+//!
+//! `governance_override` returns a `GovernanceOverride` guard: parameters set
+//! through it are restored to their original values when it is dropped, even
+//! if the test fails in between. Use it instead of manual save/restore, e.g.
+//! to shorten maxPOIStaleness for a staleness scenario.
+
+use anyhow::{Context, Result};
+use std::fmt;
+
+use crate::cast::cast_parse_uint;
+use crate::epochs::EpochState;
+use crate::{Address, Grt, TestNetwork};
+
+/// A tunable protocol parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GovernanceParameter {
+    /// RewardsManager `issuancePerBlock` (GRT wei).
+    IssuancePerBlock,
+    /// RewardsManager `minimumSubgraphSignal` (GRT wei).
+    MinimumSubgraphSignal,
+    /// RewardsManager `subgraphAvailabilityOracle` (address allowed to deny).
+    SubgraphAvailabilityOracle,
+    /// RewardsManager default reclaim address.
+    DefaultReclaimAddress,
+    /// SubgraphService `maxPOIStaleness` (seconds).
+    MaxPoiStaleness,
+    /// SubgraphService `stakeToFeesRatio` (stake locked per query fee).
+    StakeToFeesRatio,
+    /// SubgraphService `curationFeesCut` (PPM of query fees to curators).
+    CurationCut,
+    /// SubgraphService minimum provision (GRT wei).
+    MinimumProvisionTokens,
+    /// SubgraphService delegation ratio.
+    DelegationRatio,
+    /// EpochManager epoch length (blocks). Shortening it mid-epoch can skip
+    /// epochs, which `governance_set` refuses; see `set_epoch_length`.
+    EpochLength,
+    /// L2Curation `curationTaxPercentage` (PPM of minted tokens burned).
+    CurationTaxPercentage,
+    /// REO eligibility period (seconds).
+    EligibilityPeriod,
+    /// REO oracle update timeout (seconds).
+    OracleUpdateTimeout,
+}
+
+/// Who may set a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GovernanceSigner {
+    /// account1: RewardsManager governor, SubgraphService owner.
+    Governor,
+    /// account0: REO OPERATOR_ROLE.
+    Operator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    RewardsManager,
+    SubgraphService,
+    EpochManager,
+    Curation,
+    Reo,
+}
+
+impl GovernanceParameter {
+    pub const ALL: [GovernanceParameter; 13] = [
+        GovernanceParameter::IssuancePerBlock,
+        GovernanceParameter::MinimumSubgraphSignal,
+        GovernanceParameter::SubgraphAvailabilityOracle,
+        GovernanceParameter::DefaultReclaimAddress,
+        GovernanceParameter::MaxPoiStaleness,
+        GovernanceParameter::StakeToFeesRatio,
+        GovernanceParameter::CurationCut,
+        GovernanceParameter::MinimumProvisionTokens,
+        GovernanceParameter::DelegationRatio,
+        GovernanceParameter::EpochLength,
+        GovernanceParameter::CurationTaxPercentage,
+        GovernanceParameter::EligibilityPeriod,
+        GovernanceParameter::OracleUpdateTimeout,
+    ];
+
+    /// `(contract, getter, setter)`. The getter is `None` where the value is
+    /// read some other way.
+    fn calls(self) -> (Target, Option<&'static str>, &'static str) {
+        use GovernanceParameter::*;
+        match self {
+            IssuancePerBlock => (
+                Target::RewardsManager,
+                Some("issuancePerBlock()(uint256)"),
+                "setIssuancePerBlock(uint256)",
+            ),
+            MinimumSubgraphSignal => (
+                Target::RewardsManager,
+                Some("minimumSubgraphSignal()(uint256)"),
+                "setMinimumSubgraphSignal(uint256)",
+            ),
+            SubgraphAvailabilityOracle => (
+                Target::RewardsManager,
+                Some("subgraphAvailabilityOracle()(address)"),
+                "setSubgraphAvailabilityOracle(address)",
+            ),
+            DefaultReclaimAddress => (
+                Target::RewardsManager,
+                Some("getDefaultReclaimAddress()(address)"),
+                "setDefaultReclaimAddress(address)",
+            ),
+            MaxPoiStaleness => (
+                Target::SubgraphService,
+                Some("maxPOIStaleness()(uint256)"),
+                "setMaxPOIStaleness(uint256)",
+            ),
+            StakeToFeesRatio => (
+                Target::SubgraphService,
+                Some("stakeToFeesRatio()(uint256)"),
+                "setStakeToFeesRatio(uint256)",
+            ),
+            CurationCut => (
+                Target::SubgraphService,
+                Some("curationFeesCut()(uint256)"),
+                "setCurationCut(uint256)",
+            ),
+            // Read from getProvisionTokensRange()
+            MinimumProvisionTokens => (
+                Target::SubgraphService,
+                None,
+                "setMinimumProvisionTokens(uint256)",
+            ),
+            DelegationRatio => (
+                Target::SubgraphService,
+                Some("getDelegationRatio()(uint32)"),
+                "setDelegationRatio(uint32)",
+            ),
+            EpochLength => (
+                Target::EpochManager,
+                Some("epochLength()(uint256)"),
+                "setEpochLength(uint256)",
+            ),
+            CurationTaxPercentage => (
+                Target::Curation,
+                Some("curationTaxPercentage()(uint32)"),
+                "setCurationTaxPercentage(uint32)",
+            ),
+            EligibilityPeriod => (
+                Target::Reo,
+                Some("getEligibilityPeriod()(uint256)"),
+                "setEligibilityPeriod(uint256)",
+            ),
+            OracleUpdateTimeout => (
+                Target::Reo,
+                Some("getOracleUpdateTimeout()(uint256)"),
+                "setOracleUpdateTimeout(uint256)",
+            ),
+        }
+    }
+
+    pub fn signer(self) -> GovernanceSigner {
+        match self.calls().0 {
+            Target::Reo => GovernanceSigner::Operator,
+            Target::RewardsManager
+            | Target::SubgraphService
+            | Target::EpochManager
+            | Target::Curation => GovernanceSigner::Governor,
+        }
+    }
+
+    /// Whether the value is an address (otherwise an integer).
+    pub fn is_address(self) -> bool {
+        self.calls()
+            .1
+            .is_some_and(|getter| getter.ends_with("(address)"))
+    }
+}
+
+impl fmt::Display for GovernanceParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A parameter value: an integer (wei, seconds, PPM, ratio) or an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterValue {
+    Uint(u128),
+    Address(Address),
+}
+
+impl ParameterValue {
+    pub fn as_uint(self) -> Option<u128> {
+        match self {
+            ParameterValue::Uint(value) => Some(value),
+            ParameterValue::Address(_) => None,
+        }
+    }
+
+    pub fn as_address(self) -> Option<Address> {
+        match self {
+            ParameterValue::Address(address) => Some(address),
+            ParameterValue::Uint(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ParameterValue {
+    /// As a `cast` argument.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterValue::Uint(value) => write!(f, "{value}"),
+            ParameterValue::Address(address) => write!(f, "{address}"),
+        }
+    }
+}
+
+impl From<u64> for ParameterValue {
+    fn from(value: u64) -> Self {
+        ParameterValue::Uint(value.into())
+    }
+}
+
+impl From<u128> for ParameterValue {
+    fn from(value: u128) -> Self {
+        ParameterValue::Uint(value)
+    }
+}
+
+impl From<Grt> for ParameterValue {
+    fn from(value: Grt) -> Self {
+        ParameterValue::Uint(value.wei())
+    }
+}
+
+impl From<Address> for ParameterValue {
+    fn from(value: Address) -> Self {
+        ParameterValue::Address(value)
+    }
+}
+
+/// Restores overridden parameters when dropped. Created by
+/// `TestNetwork::governance_override`.
+///
+/// Dropping covers tests that bail early with `?` or panic on an assertion,
+/// so tests only call `restore` where they need the originals back (and the
+/// restore checked) before the guard goes out of scope.
+#[must_use = "parameters are restored when the guard is dropped"]
+pub struct GovernanceOverride {
+    net: TestNetwork,
+    /// Original values, in the order they were first overridden.
+    originals: Vec<(GovernanceParameter, ParameterValue)>,
+}
+
+impl GovernanceOverride {
+    /// Set `parameter`, remembering its original value the first time.
+    pub fn set(
+        &mut self,
+        parameter: GovernanceParameter,
+        value: impl Into<ParameterValue>,
+    ) -> Result<()> {
+        self.remember(parameter)?;
+        self.net.governance_set(parameter, value.into())
+    }
+
+    /// Record `parameter`'s current value as its original, unless it is
+    /// already overridden.
+    pub(crate) fn remember(&mut self, parameter: GovernanceParameter) -> Result<()> {
+        if !self.originals.iter().any(|(p, _)| *p == parameter) {
+            let original = self.net.governance_get(parameter)?;
+            self.originals.push((parameter, original));
+        }
+        Ok(())
+    }
+
+    /// Set the epoch length via `TestNetwork::set_epoch_length`, which never
+    /// skips epochs, remembering the original length.
+    pub async fn set_epoch_length(&mut self, length: u64) -> Result<EpochState> {
+        self.remember(GovernanceParameter::EpochLength)?;
+        self.net.set_epoch_length(length).await
+    }
+
+    /// The value `parameter` had before it was overridden.
+    pub fn original(&self, parameter: GovernanceParameter) -> Option<ParameterValue> {
+        self.originals
+            .iter()
+            .find(|(p, _)| *p == parameter)
+            .map(|(_, value)| *value)
+    }
+
+    /// Restore all original values now, reporting the first failure (the
+    /// rest are still attempted).
+    pub fn restore(mut self) -> Result<()> {
+        self.restore_all()
+    }
+
+    fn restore_all(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        // Reverse order, in case parameters constrain each other
+        while let Some((parameter, value)) = self.originals.pop() {
+            let restored = self
+                .net
+                .governance_set(parameter, value)
+                .with_context(|| format!("restoring {parameter} to {value}"));
+            if result.is_ok() {
+                result = restored;
+            }
+        }
+        result
+    }
+}
+
+impl Drop for GovernanceOverride {
+    fn drop(&mut self) {
+        if let Err(e) = self.restore_all() {
+            eprintln!("  WARNING: {e:#}");
+        }
+    }
+}
+
+impl TestNetwork {
+    /// Current value of `parameter`.
+    pub fn governance_get(&self, parameter: GovernanceParameter) -> Result<ParameterValue> {
+        let (target, getter, _) = parameter.calls();
+        let Some(getter) = getter else {
+            // MinimumProvisionTokens
+            let (minimum, _) = self.provision_tokens_range()?;
+            return Ok(minimum.into());
+        };
+        let output = self.cast_call(self.governance_target(target)?, getter, &[])?;
+        if parameter.is_address() {
+            Ok(ParameterValue::Address(crate::ids::parse_address(&output)?))
+        } else {
+            let value = cast_parse_uint(&output)
+                .parse()
+                .with_context(|| format!("parsing {parameter} {output:?}"))?;
+            Ok(ParameterValue::Uint(value))
+        }
+    }
+
+    /// Set `parameter` as its governing account.
+    pub fn governance_set(
+        &self,
+        parameter: GovernanceParameter,
+        value: impl Into<ParameterValue>,
+    ) -> Result<()> {
+        let value = value.into();
+        anyhow::ensure!(
+            parameter.is_address() == matches!(value, ParameterValue::Address(_)),
+            "{parameter} can't be set to {value:?}"
+        );
+        if parameter == GovernanceParameter::EpochLength {
+            let length = u64::try_from(value.as_uint().context("epoch length is a uint")?)
+                .with_context(|| format!("epoch length {value} is out of range"))?;
+            let state = self.epoch_state()?;
+            anyhow::ensure!(
+                !state.length_change_skips(length),
+                "epoch length {length} would skip epochs from epoch {} \
+                 ({} blocks in); use set_epoch_length",
+                state.epoch,
+                state.blocks_into_epoch()
+            );
+        }
+        let (target, _, setter) = parameter.calls();
+        let to = self.governance_target(target)?;
+        let args = [value.to_string()];
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match parameter.signer() {
+            GovernanceSigner::Governor => self.cast_send_as_governor(to, setter, &args),
+            GovernanceSigner::Operator => self.cast_send(to, setter, &args),
+        }
+        .with_context(|| format!("setting {parameter} to {value}"))?;
+        Ok(())
+    }
+
+    /// A guard for temporary parameter changes; see `GovernanceOverride`.
+    pub fn governance_override(&self) -> GovernanceOverride {
+        GovernanceOverride {
+            net: self.clone(),
+            originals: Vec::new(),
+        }
+    }
+
+    fn governance_target(&self, target: Target) -> Result<Address> {
+        Ok(match target {
+            Target::RewardsManager => self.contracts.rewards_manager,
+            Target::SubgraphService => self.contracts.subgraph_service,
+            Target::EpochManager => self.contracts.epoch_manager,
+            Target::Curation => self.contracts.curation,
+            Target::Reo => self.contracts.reo.context("REO contract not deployed")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameters_have_typed_calls() {
+        for parameter in GovernanceParameter::ALL {
+            let (_, getter, setter) = parameter.calls();
+            let address_setter = setter.ends_with("(address)");
+            assert_eq!(parameter.is_address(), address_setter, "{parameter}");
+            if let Some(getter) = getter {
+                assert!(
+                    getter.contains(")("),
+                    "{parameter} getter lacks return type"
+                );
+            }
+        }
+        assert_eq!(
+            GovernanceParameter::EligibilityPeriod.signer(),
+            GovernanceSigner::Operator
+        );
+        assert_eq!(
+            GovernanceParameter::MaxPoiStaleness.signer(),
+            GovernanceSigner::Governor
+        );
+    }
+
+    #[test]
+    fn values_format_as_cast_arguments() {
+        assert_eq!(
+            ParameterValue::from(Grt::ONE).to_string(),
+            "1000000000000000000"
+        );
+        let address = Address::repeat_byte(0x11);
+        assert_eq!(
+            ParameterValue::from(address).to_string(),
+            address.to_string()
+        );
+        assert_eq!(ParameterValue::from(60u64).as_uint(), Some(60));
+        assert_eq!(ParameterValue::from(address).as_uint(), None);
+    }
+}
@@ -3,7 +3,8 @@
 use anyhow::{Context, Result};
 use serde_json::Value;
 
-use crate::TestNetwork;
+use crate::gateway::GatewayResponse;
+use crate::{Address, TestNetwork};
 
 impl TestNetwork {
     /// Execute a GraphQL query against the network subgraph (graph-node).
@@ -11,25 +12,50 @@ impl TestNetwork {
         self.graphql_post(&self.subgraph_url, query, None).await
     }
 
+    /// Execute a GraphQL query against graph-node's index-node status API.
+    pub async fn graph_node_status_query(&self, query: &str) -> Result<Value> {
+        self.graphql_post(&self.graph_node_status_url, query, None)
+            .await
+    }
+
     /// Execute a GraphQL query/mutation against the indexer management API.
     pub async fn management_query(&self, query: &str) -> Result<Value> {
         self.graphql_post(&self.management_url, query, None).await
     }
 
+    /// Execute a management API query/mutation with GraphQL variables.
+    /// Used for input objects (rules, actions) instead of string interpolation.
+    pub async fn management_query_with(&self, query: &str, variables: &Value) -> Result<Value> {
+        self.graphql_post(&self.management_url, query, Some(variables))
+            .await
+    }
+
     /// Send a query through the gateway for a specific subgraph.
-    pub async fn gateway_query(&self, query: &str) -> Result<reqwest::Response> {
+    ///
+    /// Transport failures are errors; gateway and GraphQL failures are
+    /// classified in the returned `GatewayResponse`.
+    pub async fn gateway_query(&self, query: &str) -> Result<GatewayResponse> {
+        let resp = self
+            .gateway_request(&reqwest::Client::new(), query)
+            .send()
+            .await
+            .context("sending gateway query")?;
+        GatewayResponse::from_response(resp).await
+    }
+
+    /// Build an authenticated gateway query request on a caller-owned client,
+    /// so repeated queries (load generation) reuse connections.
+    pub(crate) fn gateway_request(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+    ) -> reqwest::RequestBuilder {
         let url = format!("{}/api/subgraphs/id/{}", self.gateway_url, self.subgraph_id);
-        let client = reqwest::Client::new();
-        let body = serde_json::json!({ "query": query });
-        let resp = client
+        client
             .post(&url)
             .header("content-type", "application/json")
             .header("Authorization", format!("Bearer {}", self.gateway_api_key))
-            .json(&body)
-            .send()
-            .await
-            .context("sending gateway query")?;
-        Ok(resp)
+            .json(&serde_json::json!({ "query": query }))
     }
 
     /// Send N queries through the gateway. Returns (success_count, fail_count).
@@ -39,7 +65,7 @@ impl TestNetwork {
         let mut fail = 0;
         for _ in 0..count {
             match self.gateway_query(query).await {
-                Ok(resp) if resp.status().is_success() => success += 1,
+                Ok(resp) if resp.is_success() => success += 1,
                 _ => fail += 1,
             }
         }
@@ -47,10 +73,10 @@ impl TestNetwork {
     }
 
     /// Query the indexer entity from the network subgraph.
-    /// Address must be lowercase (subgraph convention).
     /// Includes fields needed for BaselineTestPlan 6.1 (indexer health).
-    pub async fn query_indexer(&self, address: &str) -> Result<Value> {
-        let addr = address.to_lowercase();
+    pub async fn query_indexer(&self, address: Address) -> Result<Value> {
+        // Subgraph entity IDs are lowercase hex
+        let addr = format!("{address:#x}");
         let query = format!(
             r#"{{ indexer(id: "{addr}") {{
                 id stakedTokens allocatedTokens availableStake url geoHash
@@ -65,8 +91,8 @@ impl TestNetwork {
     }
 
     /// Query provisions for an indexer from the network subgraph.
-    pub async fn query_provisions(&self, indexer: &str) -> Result<Value> {
-        let addr = indexer.to_lowercase();
+    pub async fn query_provisions(&self, indexer: Address) -> Result<Value> {
+        let addr = format!("{indexer:#x}");
         let query = format!(
             r#"{{ provisions(where: {{ indexer: "{addr}" }}) {{
                 id tokensProvisioned tokensAllocated tokensThawing thawingPeriod
@@ -78,8 +104,8 @@ impl TestNetwork {
     }
 
     /// Query active allocations for an indexer from the network subgraph.
-    pub async fn query_active_allocations(&self, indexer: &str) -> Result<Value> {
-        let addr = indexer.to_lowercase();
+    pub async fn query_active_allocations(&self, indexer: Address) -> Result<Value> {
+        let addr = format!("{indexer:#x}");
         let query = format!(
             r#"{{ allocations(where: {{ indexer: "{addr}", status: Active }}) {{
                 id allocatedTokens createdAtEpoch
@@ -91,11 +117,11 @@ impl TestNetwork {
     }
 
     /// Query a single allocation by ID from the network subgraph.
-    pub async fn query_allocation(&self, id: &str) -> Result<Value> {
-        let alloc_id = id.to_lowercase();
+    pub async fn query_allocation(&self, id: Address) -> Result<Value> {
+        let alloc_id = format!("{id:#x}");
         let query = format!(
             r#"{{ allocation(id: "{alloc_id}") {{
-                id status allocatedTokens indexingRewards
+                id status allocatedTokens indexingRewards indexer {{ id }}
                 createdAtEpoch closedAtEpoch
                 subgraphDeployment {{ ipfsHash }}
             }} }}"#
@@ -152,27 +178,6 @@ impl TestNetwork {
         }
     }
 
-    /// Query the bytes32 deployment ID for a given IPFS hash.
-    /// The subgraph stores the deployment ID as a hex string (e.g., "0xab12...").
-    pub async fn query_deployment_id(&self, ipfs_hash: &str) -> Result<String> {
-        let query = format!(
-            r#"{{ subgraphDeployments(where: {{ ipfsHash: "{ipfs_hash}" }}) {{
-                id ipfsHash signalledTokens stakedTokens
-            }} }}"#
-        );
-        let resp = self.subgraph_query(&query).await?;
-        let deployments = resp["data"]["subgraphDeployments"]
-            .as_array()
-            .context("expected deployment array")?;
-        let first = deployments
-            .first()
-            .context(format!("no deployment found for IPFS hash {ipfs_hash}"))?;
-        first["id"]
-            .as_str()
-            .map(String::from)
-            .context("deployment missing id")
-    }
-
     /// Query subgraph deployments with signal info, ordered by signal amount.
     pub async fn query_deployments_with_signal(&self) -> Result<Value> {
         let query = r#"{ subgraphDeployments(
@@ -220,3 +225,31 @@ impl TestNetwork {
         Ok(json)
     }
 }
+
+/// Deserialize a subgraph `BigInt` string that is not a token amount
+/// (shares, timestamps): `#[serde(deserialize_with = "crate::graphql::big_int")]`.
+pub(crate) fn big_int<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    use serde::Deserialize;
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+/// Deserialize a `{ id }` entity reference as its address:
+/// `#[serde(deserialize_with = "crate::graphql::entity_id")]`.
+pub(crate) fn entity_id<'de, D>(deserializer: D) -> Result<crate::Address, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+    #[derive(Deserialize)]
+    struct Entity {
+        id: crate::Address,
+    }
+    Ok(Entity::deserialize(deserializer)?.id)
+}
@@ -0,0 +1,329 @@
+//! Exact GRT amounts.
+//!
+//! GRT has 18 decimals. Contracts, `cast` output and the network subgraph
+//! (`BigInt` fields) use wei, while the indexer management API uses decimal
+//! GRT strings (`BigDecimal`, e.g. `"0.01"`). `Grt` stores the wei amount as a
+//! `u128`, so parsing, formatting and comparisons never lose precision.
+//!
+//! Serde:
+//!   - `Grt` (de)serializes as a wei string (subgraph `BigInt`)
+//!   - `#[serde(with = "crate::grt::decimal")]` (de)serializes decimal GRT
+//!     (management API `BigDecimal`)
+
+use alloy_primitives::U256;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Number of decimals of the GRT token.
+pub const DECIMALS: usize = 18;
+
+/// Wei per whole GRT (10^18).
+const WEI_PER_GRT: u128 = 1_000_000_000_000_000_000;
+
+/// Parts per million, the unit of protocol cuts and taxes.
+pub const PPM: u32 = 1_000_000;
+
+/// An exact GRT amount, stored in wei.
+///
+/// `Display` and `FromStr` use decimal GRT (`"1000"`, `"0.01"`), matching what
+/// the management API expects. Use `from_wei`/`parse_wei`/`to_wei_string` for
+/// contract calls and subgraph values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Grt(u128);
+
+impl Grt {
+    pub const ZERO: Grt = Grt(0);
+    pub const ONE: Grt = Grt(WEI_PER_GRT);
+
+    /// Amount from a wei value.
+    pub const fn from_wei(wei: u128) -> Self {
+        Grt(wei)
+    }
+
+    /// Amount from a whole number of GRT (e.g. `Grt::from_grt(1000)`).
+    /// Panics on overflow.
+    pub const fn from_grt(grt: u128) -> Self {
+        Grt(grt
+            .checked_mul(WEI_PER_GRT)
+            .expect("GRT amount overflows u128 wei"))
+    }
+
+    /// The amount in wei.
+    pub const fn wei(self) -> u128 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Parse a wei amount (subgraph `BigInt`, `cast` output).
+    /// Accepts `cast`'s human-readable suffix (`"1000 [1e3]"`).
+    pub fn parse_wei(s: &str) -> Result<Self> {
+        let digits = crate::cast::cast_parse_uint(s.trim());
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            anyhow::bail!("invalid wei amount {s:?}");
+        }
+        let wei = digits
+            .parse::<u128>()
+            .with_context(|| format!("wei amount {s:?} out of range"))?;
+        Ok(Grt(wei))
+    }
+
+    /// Parse a decimal GRT amount (management API `BigDecimal`, e.g. `"0.01"`).
+    /// Rejects more than 18 fractional digits rather than rounding.
+    pub fn parse_grt(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+        let valid = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && frac.is_empty()) || !valid(whole) || !valid(frac) {
+            anyhow::bail!("invalid GRT amount {s:?}");
+        }
+        if frac.len() > DECIMALS {
+            anyhow::bail!("GRT amount {s:?} has more than {DECIMALS} decimals");
+        }
+        let whole: u128 = if whole.is_empty() {
+            0
+        } else {
+            whole
+                .parse()
+                .with_context(|| format!("GRT amount {s:?} out of range"))?
+        };
+        let frac: u128 = format!("{frac:0<DECIMALS$}")
+            .parse()
+            .with_context(|| format!("invalid GRT amount {s:?}"))?;
+        whole
+            .checked_mul(WEI_PER_GRT)
+            .and_then(|w| w.checked_add(frac))
+            .map(Grt)
+            .with_context(|| format!("GRT amount {s:?} out of range"))
+    }
+
+    /// The amount as a wei string, for `cast` arguments and subgraph filters.
+    pub fn to_wei_string(self) -> String {
+        self.0.to_string()
+    }
+
+    pub fn checked_add(self, rhs: Grt) -> Option<Grt> {
+        self.0.checked_add(rhs.0).map(Grt)
+    }
+
+    pub fn checked_sub(self, rhs: Grt) -> Option<Grt> {
+        self.0.checked_sub(rhs.0).map(Grt)
+    }
+
+    pub fn saturating_sub(self, rhs: Grt) -> Grt {
+        Grt(self.0.saturating_sub(rhs.0))
+    }
+
+    /// `ppm` parts per million of the amount, rounded down like the
+    /// contracts' `PPMMath.mulPPM`.
+    pub fn mul_ppm(self, ppm: u32) -> Grt {
+        let product = U256::from(self.0) * U256::from(ppm) / U256::from(PPM);
+        Grt(product.to())
+    }
+}
+
+impl fmt::Display for Grt {
+    /// Decimal GRT with trailing zeros trimmed (`1000`, `0.01`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.0 / WEI_PER_GRT;
+        let frac = self.0 % WEI_PER_GRT;
+        if frac == 0 {
+            write!(f, "{whole}")
+        } else {
+            let frac = format!("{frac:0>DECIMALS$}");
+            write!(f, "{whole}.{}", frac.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for Grt {
+    type Err = anyhow::Error;
+
+    /// Parses decimal GRT (see `Grt::parse_grt`).
+    fn from_str(s: &str) -> Result<Self> {
+        Grt::parse_grt(s)
+    }
+}
+
+impl Add for Grt {
+    type Output = Grt;
+
+    fn add(self, rhs: Grt) -> Grt {
+        self.checked_add(rhs).expect("GRT addition overflow")
+    }
+}
+
+impl AddAssign for Grt {
+    fn add_assign(&mut self, rhs: Grt) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Grt {
+    type Output = Grt;
+
+    fn sub(self, rhs: Grt) -> Grt {
+        self.checked_sub(rhs).expect("GRT subtraction underflow")
+    }
+}
+
+impl SubAssign for Grt {
+    fn sub_assign(&mut self, rhs: Grt) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for Grt {
+    fn sum<I: Iterator<Item = Grt>>(iter: I) -> Grt {
+        iter.fold(Grt::ZERO, Add::add)
+    }
+}
+
+impl Serialize for Grt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_wei_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Grt {
+    /// Accepts a wei string (subgraph `BigInt`) or a JSON integer.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match StringOrNumber::deserialize(deserializer)? {
+            StringOrNumber::String(s) => Grt::parse_wei(&s).map_err(serde::de::Error::custom),
+            StringOrNumber::Number(n) => Ok(Grt(u128::from(n))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(u64),
+}
+
+/// Serde adapter for decimal GRT strings (management API `BigDecimal`).
+///
+/// ```ignore
+/// #[serde(with = "crate::grt::decimal")]
+/// indexing_rewards: Grt,
+/// ```
+pub mod decimal {
+    use super::{Grt, StringOrNumber};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Grt, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Grt, D::Error> {
+        match StringOrNumber::deserialize(deserializer)? {
+            StringOrNumber::String(s) => Grt::parse_grt(&s).map_err(serde::de::Error::custom),
+            StringOrNumber::Number(n) => Ok(Grt::from_grt(u128::from(n))),
+        }
+    }
+}
+
+/// Serde adapter for optional decimal GRT (`null` or absent is `None`).
+pub mod decimal_opt {
+    use super::Grt;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<Grt>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(grt) => super::decimal::serialize(grt, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Grt>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::decimal")] Grt);
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(grt)| grt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_ppm_rounds_down() {
+        assert_eq!(Grt::from_grt(10).mul_ppm(100_000), Grt::ONE);
+        assert_eq!(Grt::from_wei(999).mul_ppm(500_000), Grt::from_wei(499));
+        assert_eq!(Grt::ONE.mul_ppm(0), Grt::ZERO);
+        assert_eq!(Grt::ONE.mul_ppm(PPM), Grt::ONE);
+        assert_eq!(
+            Grt::from_wei(u128::MAX).mul_ppm(PPM),
+            Grt::from_wei(u128::MAX)
+        );
+    }
+
+    #[test]
+    fn parse_and_format_decimal() {
+        let cases = [
+            ("0", 0),
+            ("0.01", 10_000_000_000_000_000),
+            (".5", 500_000_000_000_000_000),
+            ("1000", 1_000 * WEI_PER_GRT),
+            ("1.000000000000000001", WEI_PER_GRT + 1),
+        ];
+        for (input, wei) in cases {
+            assert_eq!(
+                Grt::parse_grt(input).unwrap(),
+                Grt::from_wei(wei),
+                "{input}"
+            );
+        }
+        assert_eq!(Grt::from_wei(10_000_000_000_000_000).to_string(), "0.01");
+        assert_eq!(Grt::from_grt(1000).to_string(), "1000");
+        assert_eq!(
+            Grt::from_wei(WEI_PER_GRT + 1).to_string(),
+            "1.000000000000000001"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_decimal() {
+        for input in ["", ".", "1.2.3", "-1", "1e18", "0.0000000000000000001"] {
+            assert!(
+                Grt::parse_grt(input).is_err(),
+                "{input:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_wei_accepts_cast_output() {
+        assert_eq!(
+            Grt::parse_wei("1000000000000000000000 [1e21]").unwrap(),
+            Grt::from_grt(1000)
+        );
+        assert!(Grt::parse_wei("1.5").is_err());
+    }
+
+    #[test]
+    fn serde_wei_and_decimal() {
+        #[derive(Deserialize, Serialize)]
+        struct Row {
+            wei: Grt,
+            #[serde(with = "decimal")]
+            grt: Grt,
+        }
+        let row: Row =
+            serde_json::from_str(r#"{ "wei": "10000000000000000", "grt": "0.01" }"#).unwrap();
+        assert_eq!(row.wei, row.grt);
+        assert_eq!(
+            serde_json::to_value(&row).unwrap(),
+            serde_json::json!({ "wei": "10000000000000000", "grt": "0.01" })
+        );
+    }
+}
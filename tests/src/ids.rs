@@ -0,0 +1,273 @@
+//! Typed addresses and deployment IDs.
+//!
+//! Contracts and `cast` use checksummed or lowercase hex addresses, the network
+//! subgraph stores them lowercase, and deployments appear either as an IPFS
+//! CIDv0 (`Qm...`, management API and gateway) or as the bytes32 sha256 digest
+//! inside it (contracts, subgraph entity IDs). These types validate once at the
+//! boundary and format for each consumer:
+//!
+//!   - `Address`: `{}` is EIP-55 checksummed, `{:#x}` is lowercase (subgraph IDs)
+//!   - `DeploymentId`: `{}` is the `Qm...` CID, `bytes32()` is the contract form
+//!   - `SubgraphId`: `{}` is base58 (gateway URLs, subgraph entity IDs),
+//!     `uint256()` is the GNS form
+//!
+//! CID ⇄ bytes32 conversion is done natively (same as `ipfs_hash_to_hex` in
+//! `shared/lib.sh`, which the shell scripts keep using), so no subgraph
+//! round-trip is needed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+pub use alloy_primitives::{Address, B256, U256, address, b256};
+
+/// Multihash prefix of a CIDv0: sha2-256 (0x12), 32-byte digest (0x20).
+const MULTIHASH_SHA256: [u8; 2] = [0x12, 0x20];
+
+/// Parse a hex address, checksum-aware.
+///
+/// All-lowercase and all-uppercase addresses are accepted as-is; mixed-case
+/// addresses must carry a valid EIP-55 checksum, so a mistyped character is
+/// rejected instead of silently pointing at another account.
+pub fn parse_address(s: &str) -> Result<Address> {
+    let s = s.trim();
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    let mixed_case =
+        hex.bytes().any(|b| b.is_ascii_uppercase()) && hex.bytes().any(|b| b.is_ascii_lowercase());
+    if mixed_case {
+        Address::parse_checksummed(s, None).with_context(|| format!("invalid checksum in {s:?}"))
+    } else {
+        s.parse().with_context(|| format!("invalid address {s:?}"))
+    }
+}
+
+/// A subgraph deployment ID: the sha256 digest of the deployment manifest.
+///
+/// `Display`/`Serialize` produce the IPFS CIDv0 (`Qm...`). `FromStr` and
+/// `Deserialize` accept either the CID or the 0x-prefixed bytes32 hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DeploymentId(B256);
+
+impl DeploymentId {
+    pub const fn new(bytes32: B256) -> Self {
+        DeploymentId(bytes32)
+    }
+
+    /// Decode an IPFS CIDv0 (`Qm...`).
+    pub fn from_ipfs_hash(cid: &str) -> Result<Self> {
+        let bytes = bs58::decode(cid)
+            .into_vec()
+            .with_context(|| format!("invalid base58 in IPFS hash {cid:?}"))?;
+        match bytes.split_first_chunk::<2>() {
+            Some((prefix, digest)) if *prefix == MULTIHASH_SHA256 && digest.len() == 32 => {
+                Ok(DeploymentId(B256::from_slice(digest)))
+            }
+            _ => anyhow::bail!("{cid:?} is not a sha2-256 CIDv0"),
+        }
+    }
+
+    /// The IPFS CIDv0 (`Qm...`), as used by the management API and graph-node.
+    pub fn ipfs_hash(&self) -> String {
+        let mut bytes = MULTIHASH_SHA256.to_vec();
+        bytes.extend_from_slice(self.0.as_slice());
+        bs58::encode(bytes).into_string()
+    }
+
+    /// The bytes32 form, as used by contracts and subgraph entity IDs.
+    pub const fn bytes32(&self) -> B256 {
+        self.0
+    }
+}
+
+impl From<B256> for DeploymentId {
+    fn from(bytes32: B256) -> Self {
+        DeploymentId(bytes32)
+    }
+}
+
+impl fmt::Display for DeploymentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.ipfs_hash())
+    }
+}
+
+impl FromStr for DeploymentId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.starts_with("0x") {
+            let bytes32: B256 = s
+                .parse()
+                .with_context(|| format!("invalid bytes32 deployment ID {s:?}"))?;
+            Ok(DeploymentId(bytes32))
+        } else {
+            DeploymentId::from_ipfs_hash(s)
+        }
+    }
+}
+
+impl Serialize for DeploymentId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.ipfs_hash())
+    }
+}
+
+impl<'de> Deserialize<'de> for DeploymentId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A GNS subgraph ID (the NFT token ID minted by `publishNewSubgraph`).
+///
+/// `Display`/`Serialize` produce the base58 form used by the gateway
+/// (`/api/subgraphs/id/<id>`) and the network subgraph. `FromStr` and
+/// `Deserialize` accept either base58 or 0x-prefixed hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubgraphId(B256);
+
+impl SubgraphId {
+    pub const fn new(bytes32: B256) -> Self {
+        SubgraphId(bytes32)
+    }
+
+    pub fn base58(&self) -> String {
+        bs58::encode(self.0.as_slice()).into_string()
+    }
+
+    /// The `uint256` form GNS functions take.
+    pub fn uint256(&self) -> U256 {
+        U256::from_be_bytes(self.0.0)
+    }
+
+    pub const fn bytes32(&self) -> B256 {
+        self.0
+    }
+}
+
+impl From<U256> for SubgraphId {
+    fn from(id: U256) -> Self {
+        SubgraphId(B256::from(id.to_be_bytes()))
+    }
+}
+
+impl fmt::Display for SubgraphId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.base58())
+    }
+}
+
+impl FromStr for SubgraphId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.starts_with("0x") {
+            let id: U256 = s
+                .parse()
+                .with_context(|| format!("invalid hex subgraph ID {s:?}"))?;
+            return Ok(id.into());
+        }
+        let bytes = bs58::decode(s)
+            .into_vec()
+            .with_context(|| format!("invalid base58 in subgraph ID {s:?}"))?;
+        anyhow::ensure!(
+            bytes.len() <= 32,
+            "subgraph ID {s:?} is longer than 32 bytes"
+        );
+        // Leading zero bytes are dropped by base58 encoders that work on the integer
+        let mut bytes32 = [0u8; 32];
+        bytes32[32 - bytes.len()..].copy_from_slice(&bytes);
+        Ok(SubgraphId(B256::from(bytes32)))
+    }
+}
+
+impl Serialize for SubgraphId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.base58())
+    }
+}
+
+impl<'de> Deserialize<'de> for SubgraphId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deployment from CHEATSHEET.md and its digest (`ipfs_hash_to_hex`).
+    const CID: &str = "QmRcucmbxAXLaAZkkCR8Bdj1X7QGPLjfRmQ5H6tFhGqiHX";
+    const BYTES32: B256 =
+        b256!("0x30bd35d223f4a673b34667502c2ee987df35e81e20bf4454f0d548980632c6f2");
+
+    #[test]
+    fn cid_round_trip() {
+        let id = DeploymentId::from_ipfs_hash(CID).unwrap();
+        assert_eq!(id.bytes32(), BYTES32);
+        assert_eq!(DeploymentId::new(BYTES32).ipfs_hash(), CID);
+        assert_eq!(id.to_string(), CID);
+        let hex: DeploymentId = BYTES32.to_string().parse().unwrap();
+        assert_eq!(hex, id);
+    }
+
+    #[test]
+    fn rejects_non_cidv0() {
+        for input in [
+            "",
+            "Qm",
+            "not-base58-0OIl",
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+        ] {
+            assert!(DeploymentId::from_ipfs_hash(input).is_err(), "{input:?}");
+        }
+        assert!("0x1234".parse::<DeploymentId>().is_err());
+    }
+
+    #[test]
+    fn serde_accepts_cid_and_bytes32() {
+        let id = DeploymentId::new(BYTES32);
+        let from_hex: DeploymentId = serde_json::from_value(serde_json::json!(BYTES32)).unwrap();
+        let from_cid: DeploymentId =
+            serde_json::from_value(serde_json::json!(id.ipfs_hash())).unwrap();
+        assert_eq!(from_hex, id);
+        assert_eq!(from_cid, id);
+        assert_eq!(
+            serde_json::to_value(id).unwrap(),
+            serde_json::json!(id.ipfs_hash())
+        );
+    }
+
+    #[test]
+    fn subgraph_id_forms() {
+        // SUBGRAPH in .env
+        let base58 = "BFr2mx7FgkJ36Y6pE5BiXs1KmNUmVDCnL82KUSdcLW1g";
+        let id: SubgraphId = base58.parse().unwrap();
+        assert_eq!(id.to_string(), base58);
+        assert_eq!(SubgraphId::from(id.uint256()), id);
+        let from_hex: SubgraphId = id.bytes32().to_string().parse().unwrap();
+        assert_eq!(from_hex, id);
+
+        let small = SubgraphId::from(U256::from(1u64));
+        assert_eq!(small.to_string().parse::<SubgraphId>().unwrap(), small);
+        assert!("0OIl".parse::<SubgraphId>().is_err());
+    }
+
+    #[test]
+    fn address_checksum() {
+        let checksummed = "0xf4EF6650E48d099a4972ea5B414daB86e1998Bd3";
+        let addr = parse_address(checksummed).unwrap();
+        assert_eq!(addr.to_string(), checksummed);
+        assert_eq!(format!("{addr:#x}"), checksummed.to_lowercase());
+        assert_eq!(parse_address(&checksummed.to_lowercase()).unwrap(), addr);
+        // One character flipped to the wrong case breaks the checksum.
+        assert!(parse_address("0xf4eF6650E48d099a4972ea5B414daB86e1998Bd3").is_err());
+        assert!(parse_address("0x1234").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Additional indexers created on demand.
+//!
+//! The local network runs one indexer stack (agent, service, tap-agent). For
+//! multi-indexer scenarios — competing allocations, reward splits, a second
+//! actor on a denied deployment — `spawn_indexer` creates a bare on-chain
+//! indexer without any off-chain components:
+//!
+//!   1. generate a key, fund it with ETH (gas) and GRT from account0
+//!   2. `stake` its own GRT on HorizonStaking
+//!   3. `provision` to SubgraphService with the local indexer's parameters
+//!   4. `register` with SubgraphService
+//!
+//! The result is a `TestNetwork` whose `indexer_address`/`receiver_secret`
+//! belong to the new indexer, so the staking, provision, allocation
+//! (`start_service`/`stop_service`) and `collect_indexing_rewards` helpers act
+//! for it unchanged. Agent-backed helpers (management API, indexer-service,
+//! TAP) still talk to the local indexer's stack.
+//!
+//! The registered URL doesn't serve queries: close a spawned indexer's
+//! allocations before the test ends so the gateway never selects it.
+
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::{Context, Result};
+
+use crate::registration::Registration;
+use crate::{Grt, TestNetwork};
+
+/// ETH sent to a spawned indexer for gas.
+const GAS_FUNDING_ETHER: u64 = 10;
+
+/// Registered URL of spawned indexers (nothing listens there).
+const SPAWNED_INDEXER_URL: &str = "http://spawned-indexer.invalid/";
+
+impl TestNetwork {
+    /// A handle acting as the indexer owning `indexer_secret`: every
+    /// indexer-scoped helper uses its address and signs with its key.
+    pub fn as_indexer(&self, indexer_secret: &str) -> Result<TestNetwork> {
+        let signer: PrivateKeySigner = indexer_secret.parse().context("parsing indexer key")?;
+        Ok(TestNetwork {
+            indexer_address: signer.address(),
+            receiver_secret: indexer_secret.to_string(),
+            ..self.clone()
+        })
+    }
+
+    /// Create, fund, stake, provision and register a new indexer with
+    /// `provision_tokens` in its SubgraphService provision (at least the
+    /// minimum provision). Returns a handle acting as that indexer.
+    pub fn spawn_indexer(&self, provision_tokens: Grt) -> Result<TestNetwork> {
+        let (minimum, _) = self.provision_tokens_range()?;
+        anyhow::ensure!(
+            provision_tokens >= minimum,
+            "provision of {provision_tokens} GRT is below the {minimum} GRT minimum"
+        );
+
+        let signer = PrivateKeySigner::random();
+        let secret = signer.to_bytes().to_string();
+        let indexer = self.as_indexer(&secret)?;
+        let address = indexer.indexer_address;
+
+        self.transfer_eth(address, GAS_FUNDING_ETHER)?;
+        self.transfer_grt(address, provision_tokens)?;
+        indexer.stake_own_tokens(provision_tokens)?;
+
+        // Same parameters as the local indexer, so they're within
+        // SubgraphService's ranges
+        let template = self.provision()?;
+        indexer
+            .cast_send_as_indexer(
+                self.contracts.horizon_staking,
+                "provision(address,address,uint256,uint32,uint64)",
+                &[
+                    &address.to_string(),
+                    &self.contracts.subgraph_service.to_string(),
+                    &provision_tokens.to_wei_string(),
+                    &template.max_verifier_cut.to_string(),
+                    &template.thawing_period.to_string(),
+                ],
+            )
+            .with_context(|| format!("provisioning spawned indexer {address}"))?;
+
+        let registration = Registration::new(SPAWNED_INDEXER_URL, 0.0, 0.0)?;
+        self.register_indexer_as(&secret, &registration)?;
+        Ok(indexer)
+    }
+
+    /// Stake GRT held by the indexer itself (`HorizonStaking.stake`), as
+    /// opposed to `stake_tokens`, which stakes account0's GRT on its behalf.
+    pub fn stake_own_tokens(&self, amount: Grt) -> Result<()> {
+        let amount = amount.to_wei_string();
+        self.cast_send_as_indexer(
+            self.contracts.grt_token,
+            "approve(address,uint256)",
+            &[&self.contracts.horizon_staking.to_string(), &amount],
+        )?;
+        self.cast_send_as_indexer(self.contracts.horizon_staking, "stake(uint256)", &[&amount])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+//! Issuance configuration: RewardsManager's own rate or an IssuanceAllocator.
+//!
+//! Before the issuance upgrade RewardsManager mints `issuancePerBlock` itself.
+//! With an IssuanceAllocator (issuance.json) the allocator's rate is split
+//! over targets, each receiving its share in one or both of two ways:
+//!
+//!   - allocator-minting → the allocator mints to the target on
+//!     `distributeIssuance()` (`IssuanceDistributed` events)
+//!   - self-minting → the target mints its own share; RewardsManager does so
+//!     when rewards are taken, at the rate `getAllocatedIssuancePerBlock()`
+//!
+//! `issuance_config` reads the whole picture, so reward tests can check
+//! whether RewardsManager issues anything before asserting that accumulators
+//! grow; `assert_accrued` does both.
+
+use alloy_primitives::{U256, keccak256};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt;
+
+use crate::cast::cast_parse_uint;
+use crate::{Address, Grt, TestNetwork};
+
+const ISSUANCE_DISTRIBUTED: &str = "IssuanceDistributed(address,uint256)";
+
+/// One allocator target's share of issuance, per block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IssuanceTarget {
+    pub address: Address,
+    /// Minted to the target by `distributeIssuance()`.
+    pub allocator_minting_rate: Grt,
+    /// Minted by the target itself.
+    pub self_minting_rate: Grt,
+}
+
+impl IssuanceTarget {
+    pub fn total_rate(&self) -> Grt {
+        self.allocator_minting_rate + self.self_minting_rate
+    }
+
+    pub fn is_self_minting(&self) -> bool {
+        !self.self_minting_rate.is_zero()
+    }
+}
+
+/// How issuance is configured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuanceConfig {
+    /// `None` before the issuance upgrade.
+    pub allocator: Option<Address>,
+    /// The allocator's total rate, or RewardsManager's own without one.
+    pub issuance_per_block: Grt,
+    /// Allocator targets (empty without an allocator).
+    pub targets: Vec<IssuanceTarget>,
+    /// Issuance RewardsManager distributes to deployments per block.
+    pub rewards_manager_rate: Grt,
+}
+
+impl IssuanceConfig {
+    pub fn uses_allocator(&self) -> bool {
+        self.allocator.is_some()
+    }
+
+    pub fn target(&self, address: Address) -> Option<&IssuanceTarget> {
+        self.targets.iter().find(|t| t.address == address)
+    }
+
+    /// Issuance not assigned to any target.
+    pub fn unallocated_rate(&self) -> Grt {
+        let allocated = self
+            .targets
+            .iter()
+            .fold(Grt::ZERO, |total, t| total + t.total_rate());
+        self.issuance_per_block.saturating_sub(allocated)
+    }
+
+    /// Whether indexing rewards accrue at all: without issuance to
+    /// RewardsManager its accumulators stay flat whatever the signal.
+    pub fn rewards_accrue(&self) -> bool {
+        !self.rewards_manager_rate.is_zero()
+    }
+}
+
+/// One `IssuanceDistributed` event: GRT the allocator minted to a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IssuanceDistribution {
+    pub block: u64,
+    pub target: Address,
+    pub amount: Grt,
+}
+
+impl IssuanceDistribution {
+    /// Decode a `cast logs --json` entry.
+    pub fn from_log(log: &Value) -> Result<IssuanceDistribution> {
+        let target = log["topics"][1]
+            .as_str()
+            .context("IssuanceDistributed log without target topic")?;
+        let data = log["data"]
+            .as_str()
+            .context("IssuanceDistributed log without data")?;
+        let amount = data.strip_prefix("0x").unwrap_or(data);
+        let amount = U256::from_str_radix(amount.get(..64).unwrap_or(amount), 16)
+            .with_context(|| format!("parsing IssuanceDistributed data {data:?}"))?;
+        let block = log["blockNumber"]
+            .as_str()
+            .context("IssuanceDistributed log without blockNumber")?;
+        Ok(IssuanceDistribution {
+            block: u64::from_str_radix(block.trim_start_matches("0x"), 16)
+                .with_context(|| format!("parsing block number {block:?}"))?,
+            target: Address::from_word(target.parse().context("parsing target topic")?),
+            amount: Grt::from_wei(
+                amount
+                    .try_into()
+                    .context("distributed amount out of range")?,
+            ),
+        })
+    }
+}
+
+impl TestNetwork {
+    /// RewardsManager's issuance per block at the end of `block`: its
+    /// allocation from the IssuanceAllocator, or its own `issuancePerBlock`
+    /// on contracts without allocator support.
+    pub fn rewards_issuance_per_block_at(&self, block: u64) -> Result<Grt> {
+        let rm = self.contracts.rewards_manager;
+        let output = self
+            .cast_call_at(block, rm, "getAllocatedIssuancePerBlock()(uint256)", &[])
+            .or_else(|_| self.cast_call_at(block, rm, "issuancePerBlock()(uint256)", &[]))?;
+        Grt::parse_wei(&output).context("parsing RewardsManager issuance per block")
+    }
+
+    /// The current issuance configuration.
+    pub fn issuance_config(&self) -> Result<IssuanceConfig> {
+        let block = self.get_block_number_sync()?;
+        let rewards_manager_rate = self.rewards_issuance_per_block_at(block)?;
+        let Some(allocator) = self.contracts.issuance_allocator else {
+            return Ok(IssuanceConfig {
+                allocator: None,
+                issuance_per_block: rewards_manager_rate,
+                targets: Vec::new(),
+                rewards_manager_rate,
+            });
+        };
+
+        let output = self
+            .cast_call_at(block, allocator, "getIssuancePerBlock()(uint256)", &[])
+            .or_else(|_| self.cast_call_at(block, allocator, "issuancePerBlock()(uint256)", &[]))?;
+        let issuance_per_block =
+            Grt::parse_wei(&output).context("parsing allocator issuance per block")?;
+        let output = self.cast_call_at(block, allocator, "getTargets()(address[])", &[])?;
+        let targets = parse_address_array(&output)?
+            .into_iter()
+            .map(|target| self.issuance_target_at(target, block))
+            .collect::<Result<_>>()?;
+
+        Ok(IssuanceConfig {
+            allocator: Some(allocator),
+            issuance_per_block,
+            targets,
+            rewards_manager_rate,
+        })
+    }
+
+    /// Check `{what}` grew from `before` to `after`, unless RewardsManager
+    /// gets no issuance (nothing can accrue then, so the check is skipped).
+    pub fn assert_accrued<T: PartialOrd + fmt::Display>(
+        &self,
+        what: &str,
+        before: T,
+        after: T,
+    ) -> Result<()> {
+        let issuance = self.issuance_config()?;
+        if !issuance.rewards_accrue() {
+            eprintln!("  SKIP {what} growth check: no issuance to RewardsManager ({issuance:?})");
+            return Ok(());
+        }
+        anyhow::ensure!(
+            after > before,
+            "{what} should grow at {} GRT/block issuance: {before} → {after}",
+            issuance.rewards_manager_rate
+        );
+        Ok(())
+    }
+
+    /// `target`'s allocation at the end of `block`.
+    pub fn issuance_target_at(&self, target: Address, block: u64) -> Result<IssuanceTarget> {
+        let allocator = self
+            .contracts
+            .issuance_allocator
+            .context("IssuanceAllocator not deployed")?;
+        // TargetIssuancePerBlock: allocatorIssuanceRate, allocatorIssuanceBlockAppliedTo,
+        // selfIssuanceRate, selfIssuanceBlockAppliedTo
+        let output = self.cast_call_at(
+            block,
+            allocator,
+            "getTargetIssuancePerBlock(address)(uint256,uint256,uint256,uint256)",
+            &[&target.to_string()],
+        )?;
+        let values: Vec<&str> = output.lines().collect();
+        let [allocator_rate, _, self_rate, _] = values[..] else {
+            anyhow::bail!("unexpected getTargetIssuancePerBlock output {output:?}");
+        };
+        Ok(IssuanceTarget {
+            address: target,
+            allocator_minting_rate: Grt::from_wei(cast_parse_uint(allocator_rate).parse()?),
+            self_minting_rate: Grt::from_wei(cast_parse_uint(self_rate).parse()?),
+        })
+    }
+
+    /// Allocator-minted issuance in `from_block..=to_block` (empty without
+    /// an allocator).
+    pub fn issuance_distributions(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IssuanceDistribution>> {
+        let Some(allocator) = self.contracts.issuance_allocator else {
+            return Ok(Vec::new());
+        };
+        let logs = self.cast_logs_with_topic(
+            allocator,
+            from_block,
+            to_block,
+            keccak256(ISSUANCE_DISTRIBUTED),
+        )?;
+        logs.iter().map(IssuanceDistribution::from_log).collect()
+    }
+
+    /// Mint pending allocator-minted issuance to all targets (permissionless).
+    pub fn distribute_issuance(&self) -> Result<()> {
+        let allocator = self
+            .contracts
+            .issuance_allocator
+            .context("IssuanceAllocator not deployed")?;
+        self.cast_send(allocator, "distributeIssuance()", &[])?;
+        Ok(())
+    }
+}
+
+/// Parse cast's `[0x..., 0x...]` array output.
+fn parse_address_array(output: &str) -> Result<Vec<Address>> {
+    output
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(crate::ids::parse_address)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn config_splits_rates_by_target() {
+        let rm = Address::repeat_byte(0x01);
+        let other = Address::repeat_byte(0x02);
+        let config = IssuanceConfig {
+            allocator: Some(Address::repeat_byte(0xaa)),
+            issuance_per_block: Grt::from_grt(100),
+            targets: vec![
+                IssuanceTarget {
+                    address: rm,
+                    allocator_minting_rate: Grt::ZERO,
+                    self_minting_rate: Grt::from_grt(60),
+                },
+                IssuanceTarget {
+                    address: other,
+                    allocator_minting_rate: Grt::from_grt(30),
+                    self_minting_rate: Grt::ZERO,
+                },
+            ],
+            rewards_manager_rate: Grt::from_grt(60),
+        };
+        assert!(config.target(rm).unwrap().is_self_minting());
+        assert!(!config.target(other).unwrap().is_self_minting());
+        assert_eq!(config.unallocated_rate(), Grt::from_grt(10));
+        assert!(config.rewards_accrue());
+    }
+
+    #[test]
+    fn parses_distributions_and_target_lists() {
+        let target = Address::repeat_byte(0x22);
+        let log = json!({
+            "blockNumber": "0x10",
+            "topics": [
+                keccak256(ISSUANCE_DISTRIBUTED).to_string(),
+                target.into_word().to_string(),
+            ],
+            "data": format!("0x{:064x}", 5_000u64),
+        });
+        let distribution = IssuanceDistribution::from_log(&log).unwrap();
+        assert_eq!(distribution.block, 16);
+        assert_eq!(distribution.target, target);
+        assert_eq!(distribution.amount, Grt::from_wei(5_000));
+
+        assert!(parse_address_array("[]").unwrap().is_empty());
+        assert_eq!(
+            parse_address_array(&format!("[{target}, {target}]")).unwrap(),
+            vec![target, target]
+        );
+    }
+}
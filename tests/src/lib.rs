@@ -3,11 +3,35 @@
 //! Provides `TestNetwork` — a typed interface to the local network services
 //! (chain RPC, subgraph, gateway, indexer management API, contract calls).
 
+pub mod allocation;
+pub mod attestation;
 pub mod cast;
+pub mod curation;
+pub mod delegation;
+pub mod disputes;
+pub mod epochs;
+pub mod fixtures;
+pub mod gateway;
+pub mod gns;
+pub mod governance;
 pub mod graphql;
+pub mod grt;
+pub mod ids;
+pub mod indexers;
+pub mod issuance;
+pub mod load;
 pub mod management;
 pub mod polling;
+pub mod reclaim;
+pub mod registration;
+pub mod rewards_model;
 pub mod staking;
+pub mod subgraph_deploy;
+pub mod tap;
+pub mod thaw;
+
+pub use grt::Grt;
+pub use ids::{Address, B256, DeploymentId, SubgraphId};
 
 use anyhow::{Context, Result};
 use std::collections::HashMap;
@@ -26,9 +50,17 @@ pub struct TestNetwork {
     pub tap_subgraph_url: String,
     pub gateway_url: String,
     pub management_url: String,
+    /// indexer-service query endpoint (bypasses the gateway).
+    pub indexer_service_url: String,
     pub gateway_api_key: String,
-    pub subgraph_id: String,
-    pub indexer_address: String,
+    /// Kubo RPC API (`/api/v0/...`), shared with graph-node.
+    pub ipfs_url: String,
+    /// graph-node admin JSON-RPC (`subgraph_create`, `subgraph_deploy`, ...).
+    pub graph_node_admin_url: String,
+    /// graph-node index-node status API (`indexingStatuses`).
+    pub graph_node_status_url: String,
+    pub subgraph_id: SubgraphId,
+    pub indexer_address: Address,
     pub account0_secret: String,
     /// The governor's private key (ACCOUNT1_SECRET). Needed for RewardsManager
     /// governance operations (setReclaimAddress, setMinimumSubgraphSignal, etc.).
@@ -47,13 +79,23 @@ pub struct TestNetwork {
 /// Contract addresses loaded from the config-local Docker volume.
 #[derive(Debug, Clone, Default)]
 pub struct Contracts {
-    pub epoch_manager: String,
-    pub rewards_manager: String,
-    pub horizon_staking: String,
-    pub subgraph_service: String,
-    pub payments_escrow: String,
-    pub grt_token: String,
-    pub reo: Option<String>,
+    pub epoch_manager: Address,
+    pub rewards_manager: Address,
+    pub horizon_staking: Address,
+    pub subgraph_service: Address,
+    /// Attestation EIP-712 verifying contract (subgraph-service.json).
+    pub dispute_manager: Address,
+    pub payments_escrow: Address,
+    /// TAP V2 receipt EIP-712 verifying contract (horizon.json).
+    pub graph_tally_collector: Address,
+    pub grt_token: Address,
+    /// Curation signal per deployment (horizon.json).
+    pub curation: Address,
+    /// Subgraph publishing and name signal (subgraph-service.json).
+    pub gns: Address,
+    pub reo: Option<Address>,
+    /// Issuance distribution across targets (issuance.json, optional).
+    pub issuance_allocator: Option<Address>,
 }
 
 impl TestNetwork {
@@ -93,6 +135,26 @@ impl TestNetwork {
             .get("INDEXER_MANAGEMENT_PORT")
             .cloned()
             .unwrap_or("7600".into());
+        let graph_admin_port = vars
+            .get("GRAPH_NODE_ADMIN_PORT")
+            .cloned()
+            .unwrap_or("8020".into());
+        let graph_status_port = vars
+            .get("GRAPH_NODE_STATUS_PORT")
+            .cloned()
+            .unwrap_or("8030".into());
+        let ipfs_host = std::env::var("IPFS_HOST")
+            .unwrap_or_else(|_| vars.get("IPFS_HOST").cloned().unwrap_or("localhost".into()));
+        let ipfs_port = vars.get("IPFS_RPC_PORT").cloned().unwrap_or("5001".into());
+        let service_host = std::env::var("INDEXER_SERVICE_HOST").unwrap_or_else(|_| {
+            vars.get("INDEXER_SERVICE_HOST")
+                .cloned()
+                .unwrap_or("localhost".into())
+        });
+        let service_port = vars
+            .get("INDEXER_SERVICE_PORT")
+            .cloned()
+            .unwrap_or("7601".into());
 
         let rpc_url = format!("http://{chain_host}:{chain_port}");
         let subgraph_url = format!("http://{graph_host}:{graph_port}/subgraphs/name/graph-network");
@@ -102,6 +164,10 @@ impl TestNetwork {
             format!("http://{graph_host}:{graph_port}/subgraphs/name/semiotic/tap");
         let gateway_url = format!("http://{gateway_host}:{gateway_port}");
         let management_url = format!("http://{mgmt_host}:{mgmt_port}");
+        let indexer_service_url = format!("http://{service_host}:{service_port}");
+        let ipfs_url = format!("http://{ipfs_host}:{ipfs_port}");
+        let graph_node_admin_url = format!("http://{graph_host}:{graph_admin_port}");
+        let graph_node_status_url = format!("http://{graph_host}:{graph_status_port}/graphql");
 
         let gateway_api_key = vars
             .get("GATEWAY_API_KEY")
@@ -109,12 +175,14 @@ impl TestNetwork {
             .unwrap_or("deadbeefdeadbeefdeadbeefdeadbeef".into());
         let subgraph_id = vars
             .get("SUBGRAPH")
-            .cloned()
-            .context("SUBGRAPH not set in .env")?;
+            .context("SUBGRAPH not set in .env")
+            .and_then(|v| v.parse())
+            .context("parsing SUBGRAPH")?;
         let indexer_address = vars
             .get("RECEIVER_ADDRESS")
-            .cloned()
-            .context("RECEIVER_ADDRESS not set in .env")?;
+            .context("RECEIVER_ADDRESS not set in .env")
+            .and_then(|v| ids::parse_address(v))
+            .context("parsing RECEIVER_ADDRESS")?;
         let account0_secret = vars
             .get("ACCOUNT0_SECRET")
             .cloned()
@@ -146,7 +214,11 @@ impl TestNetwork {
             tap_subgraph_url,
             gateway_url,
             management_url,
+            indexer_service_url,
             gateway_api_key,
+            ipfs_url,
+            graph_node_admin_url,
+            graph_node_status_url,
             subgraph_id,
             indexer_address,
             account0_secret,
@@ -209,62 +281,70 @@ fn load_contracts() -> Result<Contracts> {
     let horizon: serde_json::Value =
         serde_json::from_str(&horizon_json).context("parsing horizon.json")?;
 
-    let epoch_manager = horizon["1337"]["EpochManager"]["address"]
-        .as_str()
-        .context("EpochManager address not found in horizon.json")?
-        .to_string();
+    let epoch_manager = contract_address(&horizon, "EpochManager", "horizon.json")?;
 
-    let rewards_manager = horizon["1337"]["RewardsManager"]["address"]
-        .as_str()
-        .context("RewardsManager address not found in horizon.json")?
-        .to_string();
+    let rewards_manager = contract_address(&horizon, "RewardsManager", "horizon.json")?;
 
-    let horizon_staking = horizon["1337"]["HorizonStaking"]["address"]
-        .as_str()
-        .context("HorizonStaking address not found in horizon.json")?
-        .to_string();
+    let horizon_staking = contract_address(&horizon, "HorizonStaking", "horizon.json")?;
 
-    let payments_escrow = horizon["1337"]["PaymentsEscrow"]["address"]
-        .as_str()
-        .context("PaymentsEscrow address not found in horizon.json")?
-        .to_string();
+    let payments_escrow = contract_address(&horizon, "PaymentsEscrow", "horizon.json")?;
 
-    let grt_token = horizon["1337"]["L2GraphToken"]["address"]
-        .as_str()
-        .context("L2GraphToken address not found in horizon.json")?
-        .to_string();
+    let graph_tally_collector = contract_address(&horizon, "GraphTallyCollector", "horizon.json")?;
+
+    let grt_token = contract_address(&horizon, "L2GraphToken", "horizon.json")?;
+
+    let curation = contract_address(&horizon, "L2Curation", "horizon.json")?;
 
-    // SubgraphService is in a separate address book
+    // SubgraphService, DisputeManager and GNS are in a separate address book
     let ss_json = docker_cat("graph-node", "/opt/config/subgraph-service.json")
         .context("reading subgraph-service.json from graph-node container")?;
     let ss: serde_json::Value =
         serde_json::from_str(&ss_json).context("parsing subgraph-service.json")?;
-    let subgraph_service = ss["1337"]["SubgraphService"]["address"]
-        .as_str()
-        .context("SubgraphService address not found in subgraph-service.json")?
-        .to_string();
+    let subgraph_service = contract_address(&ss, "SubgraphService", "subgraph-service.json")?;
+    let dispute_manager = contract_address(&ss, "DisputeManager", "subgraph-service.json")?;
+    let gns = contract_address(&ss, "L2GNS", "subgraph-service.json")?;
 
-    // REO address is in issuance.json (optional — may not be deployed)
-    let reo = docker_cat("graph-node", "/opt/config/issuance.json")
-        .ok()
-        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
-        .and_then(|v| {
-            v["1337"]["RewardsEligibilityOracle"]["address"]
-                .as_str()
-                .map(String::from)
-        });
+    // REO and IssuanceAllocator are in issuance.json (optional — may not be deployed)
+    // A missing file or entry means "not deployed"; a malformed one is an error
+    let issuance = match docker_cat("graph-node", "/opt/config/issuance.json") {
+        Ok(json) => serde_json::from_str(&json).context("parsing issuance.json")?,
+        Err(_) => serde_json::Value::Null,
+    };
+    let optional = |name: &str| -> Result<Option<Address>> {
+        issuance["1337"][name]["address"]
+            .as_str()
+            .map(|a| {
+                ids::parse_address(a).with_context(|| format!("{name} address in issuance.json"))
+            })
+            .transpose()
+    };
+    let reo = optional("RewardsEligibilityOracle")?;
+    let issuance_allocator = optional("IssuanceAllocator")?;
 
     Ok(Contracts {
         epoch_manager,
         rewards_manager,
         horizon_staking,
         subgraph_service,
+        dispute_manager,
         payments_escrow,
+        graph_tally_collector,
         grt_token,
+        curation,
+        gns,
         reo,
+        issuance_allocator,
     })
 }
 
+/// Look up a contract address in an address book (`{"1337": {Name: {"address": ...}}}`).
+fn contract_address(book: &serde_json::Value, name: &str, file: &str) -> Result<Address> {
+    let address = book["1337"][name]["address"]
+        .as_str()
+        .with_context(|| format!("{name} address not found in {file}"))?;
+    ids::parse_address(address).with_context(|| format!("{name} address in {file}"))
+}
+
 /// Read a file from a running Docker container.
 fn docker_cat(container: &str, path: &str) -> Result<String> {
     let output = std::process::Command::new("docker")
@@ -0,0 +1,439 @@
+//! Concurrent gateway load generation.
+//!
+//! `send_gateway_queries` is sequential and only counts successes, which is
+//! enough for a smoke test but not for exercising TAP receipt volume or
+//! observing gateway behaviour under load. `run_gateway_load` drives queries
+//! either at a fixed rate (open loop) or with a fixed number of in-flight
+//! workers (closed loop) for a duration, cycling through a query mix, and
+//! reports:
+//!
+//!   - latency percentiles (nearest-rank) over all answered queries
+//!   - HTTP status and gateway error kind breakdowns
+//!   - which indexer served each query, via the attestation signer
+//!     (allocation ID) resolved through the network subgraph
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+use crate::gateway::{GatewayError, GatewayErrorKind, GatewayResponse};
+use crate::{Address, TestNetwork};
+
+/// Default query: cheap, always answerable, still attested.
+pub const META_QUERY: &str = r#"{ _meta { block { number } } }"#;
+
+/// How queries are scheduled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadMode {
+    /// Start a query every `1 / qps` seconds regardless of outstanding ones.
+    Rate(f64),
+    /// Keep exactly this many queries in flight.
+    Concurrency(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    /// Queries sent round-robin.
+    pub queries: Vec<String>,
+    pub mode: LoadMode,
+    pub duration: Duration,
+    /// Per-request timeout; timed out requests count as transport errors.
+    pub timeout: Duration,
+}
+
+impl LoadConfig {
+    /// Fixed request rate (queries per second) of `META_QUERY`.
+    pub fn rate(qps: f64, duration: Duration) -> Self {
+        Self::new(LoadMode::Rate(qps), duration)
+    }
+
+    /// Fixed number of concurrent workers sending `META_QUERY`.
+    pub fn concurrency(workers: usize, duration: Duration) -> Self {
+        Self::new(LoadMode::Concurrency(workers), duration)
+    }
+
+    fn new(mode: LoadMode, duration: Duration) -> Self {
+        Self {
+            queries: vec![META_QUERY.to_string()],
+            mode,
+            duration,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Replace the query mix.
+    pub fn with_queries<S: Into<String>>(mut self, queries: impl IntoIterator<Item = S>) -> Self {
+        self.queries = queries.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Outcome of a single gateway query.
+#[derive(Debug, Clone)]
+pub struct QuerySample {
+    /// Index into `LoadConfig::queries`.
+    pub query_index: usize,
+    /// HTTP status, `None` on transport failure (connect, timeout).
+    pub status: Option<u16>,
+    pub latency: Duration,
+    /// Attestation signer, i.e. the allocation that served the query.
+    pub allocation: Option<Address>,
+    /// Indexer owning `allocation`, resolved after the run.
+    pub indexer: Option<Address>,
+    pub gateway_error: Option<GatewayError>,
+    pub graphql_errors: Vec<String>,
+    /// Connect/timeout/body errors; `status` may be set if only the body failed.
+    pub transport_error: Option<String>,
+}
+
+impl QuerySample {
+    /// HTTP 2xx with a `data` payload and no errors.
+    pub fn is_success(&self) -> bool {
+        self.status.is_some_and(|s| (200..300).contains(&s))
+            && self.gateway_error.is_none()
+            && self.graphql_errors.is_empty()
+            && self.transport_error.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadReport {
+    pub samples: Vec<QuerySample>,
+    pub elapsed: Duration,
+    pub statuses: BTreeMap<u16, usize>,
+    /// Gateway failure kind → count.
+    pub gateway_errors: BTreeMap<GatewayErrorKind, usize>,
+    /// Queries answered with GraphQL (graph-node) errors.
+    pub graphql_errors: usize,
+    /// Indexer → number of queries it served.
+    pub served_by: BTreeMap<Address, usize>,
+    pub transport_errors: usize,
+}
+
+impl LoadReport {
+    fn new(samples: Vec<QuerySample>, elapsed: Duration) -> Self {
+        let mut statuses = BTreeMap::new();
+        let mut gateway_errors = BTreeMap::new();
+        let mut served_by = BTreeMap::new();
+        let mut graphql_errors = 0;
+        let mut transport_errors = 0;
+        for sample in &samples {
+            if let Some(status) = sample.status {
+                *statuses.entry(status).or_default() += 1;
+            }
+            if let Some(error) = &sample.gateway_error {
+                *gateway_errors.entry(error.kind).or_default() += 1;
+            }
+            if !sample.graphql_errors.is_empty() {
+                graphql_errors += 1;
+            }
+            if sample.transport_error.is_some() {
+                transport_errors += 1;
+            }
+            if let Some(indexer) = sample.indexer {
+                *served_by.entry(indexer).or_default() += 1;
+            }
+        }
+        Self {
+            samples,
+            elapsed,
+            statuses,
+            gateway_errors,
+            graphql_errors,
+            served_by,
+            transport_errors,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn successes(&self) -> usize {
+        self.samples.iter().filter(|s| s.is_success()).count()
+    }
+
+    pub fn success_ratio(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.successes() as f64 / self.total() as f64
+    }
+
+    /// Completed queries per second over the whole run.
+    pub fn throughput(&self) -> f64 {
+        self.total() as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Nearest-rank latency percentile (`p` in 0..=100) over answered queries.
+    pub fn latency_percentile(&self, p: f64) -> Option<Duration> {
+        let mut latencies: Vec<Duration> = self
+            .samples
+            .iter()
+            .filter(|s| s.status.is_some())
+            .map(|s| s.latency)
+            .collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort();
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * latencies.len() as f64).ceil() as usize;
+        Some(latencies[rank.saturating_sub(1)])
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |p| {
+            self.latency_percentile(p)
+                .map(|d| format!("{}ms", d.as_millis()))
+                .unwrap_or("-".into())
+        };
+        writeln!(
+            f,
+            "{} queries in {:.1}s ({:.1} q/s), {} ok ({:.0}%), {} transport errors",
+            self.total(),
+            self.elapsed.as_secs_f64(),
+            self.throughput(),
+            self.successes(),
+            self.success_ratio() * 100.0,
+            self.transport_errors,
+        )?;
+        writeln!(
+            f,
+            "  latency p50={} p90={} p99={} max={}",
+            ms(50.0),
+            ms(90.0),
+            ms(99.0),
+            ms(100.0)
+        )?;
+        writeln!(f, "  status: {:?}", self.statuses)?;
+        for (kind, count) in &self.gateway_errors {
+            writeln!(f, "  gateway error {kind:?}: {count}")?;
+        }
+        if self.graphql_errors > 0 {
+            writeln!(f, "  GraphQL errors: {}", self.graphql_errors)?;
+        }
+        for (indexer, count) in &self.served_by {
+            writeln!(f, "  served by {indexer}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Per-task shared state.
+struct Shared {
+    network: TestNetwork,
+    client: reqwest::Client,
+    queries: Vec<String>,
+}
+
+impl Shared {
+    async fn send(&self, query_index: usize) -> QuerySample {
+        let query = &self.queries[query_index];
+        let start = Instant::now();
+        let result = self
+            .network
+            .gateway_request(&self.client, query)
+            .send()
+            .await;
+        let mut sample = QuerySample {
+            query_index,
+            status: None,
+            latency: Duration::ZERO,
+            allocation: None,
+            indexer: None,
+            gateway_error: None,
+            graphql_errors: Vec::new(),
+            transport_error: None,
+        };
+        let resp = match result {
+            Ok(resp) => resp,
+            Err(e) => {
+                sample.latency = start.elapsed();
+                sample.transport_error = Some(e.to_string());
+                return sample;
+            }
+        };
+        sample.status = Some(resp.status().as_u16());
+        let resp = GatewayResponse::from_response(resp).await;
+        sample.latency = start.elapsed();
+        match resp {
+            Ok(resp) => {
+                let domain = self.network.attestation_domain();
+                sample.allocation = resp
+                    .attestation
+                    .and_then(|a| a.recover_signer(&domain).ok());
+                sample.gateway_error = resp.gateway_error;
+                sample.graphql_errors = resp.graphql_errors;
+            }
+            Err(e) => sample.transport_error = Some(format!("{e:#}")),
+        }
+        sample
+    }
+}
+
+impl TestNetwork {
+    /// Drive gateway queries according to `config` and aggregate the results.
+    ///
+    /// Every answered query with an attestation is attributed to the indexer
+    /// owning the signing allocation; closed allocations still resolve.
+    pub async fn run_gateway_load(&self, config: &LoadConfig) -> Result<LoadReport> {
+        anyhow::ensure!(!config.queries.is_empty(), "load config has no queries");
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .context("building HTTP client")?;
+        let shared = Arc::new(Shared {
+            network: self.clone(),
+            client,
+            queries: config.queries.clone(),
+        });
+        let query_count = config.queries.len();
+
+        let start = Instant::now();
+        let deadline = start + config.duration;
+        let mut tasks = JoinSet::new();
+        match config.mode {
+            LoadMode::Rate(qps) => {
+                anyhow::ensure!(qps > 0.0, "query rate must be positive");
+                let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / qps));
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                let mut sent = 0;
+                loop {
+                    ticker.tick().await;
+                    // A tick can land past the deadline
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+                    let shared = shared.clone();
+                    let index = sent % query_count;
+                    tasks.spawn(async move { vec![shared.send(index).await] });
+                    sent += 1;
+                }
+            }
+            LoadMode::Concurrency(workers) => {
+                anyhow::ensure!(workers > 0, "concurrency must be positive");
+                for worker in 0..workers {
+                    let shared = shared.clone();
+                    tasks.spawn(async move {
+                        let mut samples = Vec::new();
+                        let mut index = worker;
+                        while Instant::now() < deadline {
+                            samples.push(shared.send(index % query_count).await);
+                            index += workers;
+                        }
+                        samples
+                    });
+                }
+            }
+        }
+        let mut samples = Vec::new();
+        while let Some(result) = tasks.join_next().await {
+            samples.extend(result.context("load task panicked")?);
+        }
+        let elapsed = start.elapsed();
+
+        let mut indexers: HashMap<Address, Option<Address>> = HashMap::new();
+        for sample in &mut samples {
+            let Some(allocation) = sample.allocation else {
+                continue;
+            };
+            sample.indexer = match indexers.get(&allocation) {
+                Some(indexer) => *indexer,
+                None => {
+                    let indexer = self.allocation_indexer(allocation).await?;
+                    indexers.insert(allocation, indexer);
+                    indexer
+                }
+            };
+        }
+        Ok(LoadReport::new(samples, elapsed))
+    }
+
+    /// The indexer owning an allocation, `None` if the subgraph doesn't know it.
+    async fn allocation_indexer(&self, allocation: Address) -> Result<Option<Address>> {
+        let alloc = self.query_allocation(allocation).await?;
+        if alloc.is_null() {
+            return Ok(None);
+        }
+        let indexer = serde_json::from_value(alloc["indexer"]["id"].clone())
+            .context("parsing allocation indexer")?;
+        Ok(Some(indexer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(status: Option<u16>, ms: u64) -> QuerySample {
+        QuerySample {
+            query_index: 0,
+            status,
+            latency: Duration::from_millis(ms),
+            allocation: None,
+            indexer: None,
+            gateway_error: None,
+            graphql_errors: Vec::new(),
+            transport_error: None,
+        }
+    }
+
+    fn failed(status: u16, message: &str) -> QuerySample {
+        QuerySample {
+            gateway_error: GatewayError::classify(status, message),
+            ..sample(Some(status), 1)
+        }
+    }
+
+    #[test]
+    fn percentiles_are_nearest_rank() {
+        let samples = (1..=10).map(|ms| sample(Some(200), ms * 10)).collect();
+        let report = LoadReport::new(samples, Duration::from_secs(1));
+        let pct = |p| report.latency_percentile(p).unwrap().as_millis();
+        assert_eq!(pct(0.0), 10);
+        assert_eq!(pct(50.0), 50);
+        assert_eq!(pct(90.0), 90);
+        assert_eq!(pct(99.0), 100);
+        assert_eq!(pct(100.0), 100);
+        assert_eq!(report.throughput(), 10.0);
+    }
+
+    #[test]
+    fn aggregates_statuses_and_errors() {
+        let samples = vec![
+            sample(Some(200), 5),
+            failed(200, "bad indexers: {}"),
+            failed(200, "auth error: API key not found"),
+            QuerySample {
+                graphql_errors: vec!["no field".into()],
+                ..sample(Some(200), 2)
+            },
+            QuerySample {
+                transport_error: Some("timed out".into()),
+                ..sample(None, 30_000)
+            },
+        ];
+        let report = LoadReport::new(samples, Duration::from_secs(2));
+        assert_eq!(report.successes(), 1);
+        assert_eq!(report.transport_errors, 1);
+        assert_eq!(report.graphql_errors, 1);
+        assert_eq!(report.statuses, BTreeMap::from([(200, 4)]));
+        assert_eq!(
+            report.gateway_errors,
+            BTreeMap::from([
+                (GatewayErrorKind::Unauthorized, 1),
+                (GatewayErrorKind::IndexerErrors, 1)
+            ])
+        );
+        // Transport failures don't skew latency.
+        assert_eq!(
+            report.latency_percentile(100.0),
+            Some(Duration::from_millis(5))
+        );
+    }
+}
@@ -1,19 +1,427 @@
 //! Indexer management API helpers (indexer-agent GraphQL mutations).
+//!
+//! Covers the operations behind `graph indexer allocations`,
+//! `graph indexer rules`, `graph indexer actions` and `graph indexer cost`.
+//! Rule amounts and thresholds are wei strings in the API (the CLI converts
+//! from GRT), while allocation and action amounts are decimal GRT.
 
 use anyhow::{Context, Result};
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::time::Duration;
 
-use crate::TestNetwork;
+use crate::polling::PollResult;
+use crate::{Address, DeploymentId, Grt, TestNetwork};
 
 /// Protocol network identifier for the local chain.
 const PROTOCOL_NETWORK: &str = "eip155:1337";
 
+/// Result of the `createAllocation` mutation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAllocationResult {
+    pub allocation: Address,
+    pub deployment: DeploymentId,
+    /// Decimal GRT in the management API response.
+    #[serde(with = "crate::grt::decimal")]
+    pub allocated_tokens: Grt,
+}
+
+/// Result of the `closeAllocation` mutation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloseAllocationResult {
+    pub allocation: Address,
+    #[serde(with = "crate::grt::decimal")]
+    pub allocated_tokens: Grt,
+    #[serde(with = "crate::grt::decimal")]
+    pub indexing_rewards: Grt,
+}
+
+/// Result of the `reallocateAllocation` mutation: the closed allocation and
+/// its replacement on the same deployment.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReallocateAllocationResult {
+    pub closed_allocation: Address,
+    #[serde(with = "crate::grt::decimal")]
+    pub indexing_rewards_collected: Grt,
+    pub receipts_worth_collecting: bool,
+    pub created_allocation: Address,
+    #[serde(with = "crate::grt::decimal")]
+    pub created_allocation_stake: Grt,
+}
+
+/// An allocation as reported by the `indexerAllocations` query.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexerAllocation {
+    pub id: Address,
+    pub subgraph_deployment: DeploymentId,
+    /// Wei in the management API response.
+    pub allocated_tokens: Grt,
+    pub created_at_epoch: u64,
+    pub closed_at_epoch: Option<u64>,
+    pub status: String,
+}
+
+impl IndexerAllocation {
+    pub fn is_active(&self) -> bool {
+        self.closed_at_epoch.is_none()
+    }
+}
+
+/// How the agent decides whether to allocate to a deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecisionBasis {
+    /// Allocate when the rule's thresholds (signal, stake, fees) are met.
+    Rules,
+    /// Never allocate; close existing allocations.
+    Never,
+    /// Always allocate.
+    Always,
+    /// Index the deployment but don't allocate to it.
+    Offchain,
+}
+
+/// What an indexing rule's identifier refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentifierType {
+    Deployment,
+    Subgraph,
+    /// The `global` rule; defaults merged into every other rule.
+    Group,
+}
+
+/// Identifier of the global rule.
+pub const GLOBAL_RULE: &str = "global";
+
+/// An indexing rule as returned by `indexingRule(s)`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexingRule {
+    pub identifier: String,
+    pub identifier_type: IdentifierType,
+    pub allocation_amount: Option<Grt>,
+    pub allocation_lifetime: Option<u64>,
+    pub auto_renewal: bool,
+    pub parallel_allocations: Option<u32>,
+    pub max_allocation_percentage: Option<f64>,
+    pub min_signal: Option<Grt>,
+    pub max_signal: Option<Grt>,
+    pub min_stake: Option<Grt>,
+    pub min_average_query_fees: Option<Grt>,
+    pub custom: Option<String>,
+    pub decision_basis: DecisionBasis,
+    pub require_supported: bool,
+    pub safety: bool,
+    pub protocol_network: String,
+}
+
+/// Input for `setIndexingRule`. Unset fields keep their current value (or the
+/// global default for a new rule), like `graph indexer rules set`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexingRuleInput {
+    pub identifier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier_type: Option<IdentifierType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decision_basis: Option<DecisionBasis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocation_amount: Option<Grt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocation_lifetime: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_renewal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_signal: Option<Grt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_signal: Option<Grt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_stake: Option<Grt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_average_query_fees: Option<Grt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_supported: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety: Option<bool>,
+    pub protocol_network: String,
+}
+
+impl IndexingRuleInput {
+    /// A rule for a single deployment.
+    pub fn deployment(deployment: DeploymentId) -> Self {
+        Self {
+            identifier: deployment.to_string(),
+            identifier_type: Some(IdentifierType::Deployment),
+            protocol_network: PROTOCOL_NETWORK.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The global rule.
+    pub fn global() -> Self {
+        Self {
+            identifier: GLOBAL_RULE.into(),
+            identifier_type: Some(IdentifierType::Group),
+            protocol_network: PROTOCOL_NETWORK.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn decision_basis(mut self, basis: DecisionBasis) -> Self {
+        self.decision_basis = Some(basis);
+        self
+    }
+
+    pub fn allocation_amount(mut self, amount: Grt) -> Self {
+        self.allocation_amount = Some(amount);
+        self
+    }
+
+    /// Thresholds used with `DecisionBasis::Rules`.
+    pub fn min_signal(mut self, signal: Grt) -> Self {
+        self.min_signal = Some(signal);
+        self
+    }
+
+    pub fn min_stake(mut self, stake: Grt) -> Self {
+        self.min_stake = Some(stake);
+        self
+    }
+}
+
+/// Lifecycle state of a queued action.
+///
+/// queued → approved → deploying → pending → success | failed, or canceled
+/// from queued/approved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionStatus {
+    Queued,
+    Approved,
+    Deploying,
+    Pending,
+    Success,
+    Failed,
+    Canceled,
+}
+
+impl ActionStatus {
+    /// The agent will not touch the action again.
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Success | Self::Failed | Self::Canceled)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionType {
+    Allocate,
+    Unallocate,
+    Reallocate,
+    PresentPoi,
+    Resize,
+}
+
+/// An action in the agent's queue (also the shape of `executeApprovedActions`
+/// results).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Action {
+    pub id: u64,
+    pub status: ActionStatus,
+    #[serde(rename = "type")]
+    pub action_type: ActionType,
+    #[serde(rename = "deploymentID")]
+    pub deployment_id: Option<DeploymentId>,
+    #[serde(rename = "allocationID")]
+    pub allocation_id: Option<Address>,
+    #[serde(default, with = "crate::grt::decimal_opt")]
+    pub amount: Option<Grt>,
+    pub poi: Option<String>,
+    pub force: Option<bool>,
+    pub priority: Option<i64>,
+    pub source: String,
+    pub reason: String,
+    pub transaction: Option<String>,
+    pub failure_reason: Option<String>,
+    pub protocol_network: String,
+}
+
+/// Input for `queueActions`, as built by `graph indexer actions queue`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionInput {
+    pub status: ActionStatus,
+    #[serde(rename = "type")]
+    pub action_type: ActionType,
+    #[serde(rename = "deploymentID", skip_serializing_if = "Option::is_none")]
+    pub deployment_id: Option<DeploymentId>,
+    #[serde(rename = "allocationID", skip_serializing_if = "Option::is_none")]
+    pub allocation_id: Option<Address>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "crate::grt::decimal_opt"
+    )]
+    pub amount: Option<Grt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poi: Option<String>,
+    /// Block the POI is resolved at; needed with `force` (see `close_allocation`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poi_block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+    pub source: String,
+    pub reason: String,
+    pub priority: i64,
+    pub protocol_network: String,
+}
+
+/// `source` of actions queued by this crate.
+pub const ACTION_SOURCE: &str = "local-network-tests";
+
+impl ActionInput {
+    fn new(action_type: ActionType) -> Self {
+        Self {
+            status: ActionStatus::Queued,
+            action_type,
+            deployment_id: None,
+            allocation_id: None,
+            amount: None,
+            poi: None,
+            poi_block_number: None,
+            force: None,
+            source: ACTION_SOURCE.into(),
+            reason: "manual".into(),
+            priority: 0,
+            protocol_network: PROTOCOL_NETWORK.into(),
+        }
+    }
+
+    /// `graph indexer actions queue allocate <deployment> <amount>`
+    pub fn allocate(deployment: DeploymentId, amount: Grt) -> Self {
+        Self {
+            deployment_id: Some(deployment),
+            amount: Some(amount),
+            ..Self::new(ActionType::Allocate)
+        }
+    }
+
+    /// `graph indexer actions queue unallocate <deployment> <allocation>`
+    pub fn unallocate(deployment: DeploymentId, allocation: Address) -> Self {
+        Self {
+            deployment_id: Some(deployment),
+            allocation_id: Some(allocation),
+            ..Self::new(ActionType::Unallocate)
+        }
+    }
+
+    /// Close with the POI resolved at `block_number`, skipping POI safety checks.
+    pub fn forced_at(mut self, block_number: u64) -> Self {
+        self.force = Some(true);
+        self.poi_block_number = Some(block_number);
+        self
+    }
+
+    pub fn reason(mut self, reason: &str) -> Self {
+        self.reason = reason.into();
+        self
+    }
+}
+
+/// Filter for the `actions` query. Unset fields match everything.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ActionStatus>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub action_type: Option<ActionType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// The statuses an action went through while being watched.
+#[derive(Debug, Clone)]
+pub struct ActionWatch {
+    /// The action in its terminal state.
+    pub action: Action,
+    /// Distinct statuses observed, in order (polling may skip fast ones).
+    pub transitions: Vec<ActionStatus>,
+}
+
+/// Identifier of the global cost model, used for deployments without one.
+pub const GLOBAL_COST_MODEL: &str = "global";
+
+/// An Agora cost model (`graph indexer cost get/set`). Also the
+/// `setCostModel` input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostModel {
+    /// Deployment IPFS hash, or `global`.
+    pub deployment: String,
+    pub model: Option<String>,
+}
+
+impl CostModel {
+    pub fn deployment(deployment: DeploymentId, model: &str) -> Self {
+        Self {
+            deployment: deployment.to_string(),
+            model: Some(model.into()),
+        }
+    }
+
+    pub fn global(model: &str) -> Self {
+        Self {
+            deployment: GLOBAL_COST_MODEL.into(),
+            model: Some(model.into()),
+        }
+    }
+
+    /// The price of a model with only a `default` statement; `None` for
+    /// anything else (conditional statements, variables).
+    pub fn flat_price(&self) -> Option<Grt> {
+        agora_flat_price(self.model.as_deref()?)
+    }
+}
+
+/// An Agora model charging `price` GRT for every query: `default => <price>;`
+pub fn agora_flat(price: Grt) -> String {
+    format!("default => {price};")
+}
+
+/// Parse a model made of a single `default => <price>;` statement.
+pub fn agora_flat_price(model: &str) -> Option<Grt> {
+    let price = model
+        .trim()
+        .strip_prefix("default")?
+        .trim_start()
+        .strip_prefix("=>")?
+        .trim()
+        .strip_suffix(';')?;
+    price.trim().parse().ok()
+}
+
+const ACTION_FIELDS: &str = "id status type deploymentID allocationID amount poi force \
+    priority source reason transaction failureReason protocolNetwork";
+
+const INDEXING_RULE_FIELDS: &str = "identifier identifierType allocationAmount \
+    allocationLifetime autoRenewal parallelAllocations maxAllocationPercentage \
+    minSignal maxSignal minStake minAverageQueryFees custom decisionBasis \
+    requireSupported safety protocolNetwork";
+
 impl TestNetwork {
     /// Create an allocation via the indexer management API.
-    /// `deployment` is the IPFS hash (e.g., "QmXU9FEf...").
-    /// `amount` is in GRT (e.g., "0.01").
-    /// Returns the mutation result with `allocation` (ID), `deployment`, `allocatedTokens`.
-    pub async fn create_allocation(&self, deployment: &str, amount: &str) -> Result<Value> {
+    /// `deployment` is sent as its IPFS hash (e.g., "QmXU9FEf...").
+    /// `amount` is sent as decimal GRT, which is what the management API expects.
+    pub async fn create_allocation(
+        &self,
+        deployment: DeploymentId,
+        amount: Grt,
+    ) -> Result<CreateAllocationResult> {
         let query = format!(
             r#"mutation {{
                 createAllocation(
@@ -29,7 +437,8 @@ impl TestNetwork {
         resp["data"]["createAllocation"]
             .as_object()
             .context("createAllocation returned null")?;
-        Ok(resp["data"]["createAllocation"].clone())
+        serde_json::from_value(resp["data"]["createAllocation"].clone())
+            .context("parsing createAllocation result")
     }
 
     /// Close an allocation via the indexer management API.
@@ -38,8 +447,7 @@ impl TestNetwork {
     /// returns null when `force=true` is used without a block number.
     /// Uses the subgraph's latest indexed block (not the chain tip) to ensure
     /// graph-node has the block hash cached.
-    /// Returns the mutation result with `allocation`, `allocatedTokens`, `indexingRewards`.
-    pub async fn close_allocation(&self, allocation_id: &str) -> Result<Value> {
+    pub async fn close_allocation(&self, allocation_id: Address) -> Result<CloseAllocationResult> {
         let block_number = self.subgraph_block_number().await?;
         let query = format!(
             r#"mutation {{
@@ -57,17 +465,433 @@ impl TestNetwork {
         resp["data"]["closeAllocation"]
             .as_object()
             .context("closeAllocation returned null")?;
-        Ok(resp["data"]["closeAllocation"].clone())
+        serde_json::from_value(resp["data"]["closeAllocation"].clone())
+            .context("parsing closeAllocation result")
+    }
+
+    /// Close an allocation and open a new one on the same deployment with
+    /// `amount` in a single transaction, via the indexer management API.
+    /// Emulates `graph indexer allocations reallocate` (BaselineTestPlan 4.5).
+    ///
+    /// Resolves the POI at the subgraph's latest block, like `close_allocation`.
+    pub async fn reallocate_allocation(
+        &self,
+        allocation_id: Address,
+        amount: Grt,
+    ) -> Result<ReallocateAllocationResult> {
+        let block_number = self.subgraph_block_number().await?;
+        let query = format!(
+            r#"mutation {{
+                reallocateAllocation(
+                    allocation: "{allocation_id}",
+                    blockNumber: {block_number},
+                    amount: "{amount}",
+                    force: true,
+                    protocolNetwork: "{PROTOCOL_NETWORK}"
+                ) {{
+                    closedAllocation indexingRewardsCollected receiptsWorthCollecting
+                    createdAllocation createdAllocationStake
+                }}
+            }}"#
+        );
+        let resp = self.management_query(&query).await?;
+        resp["data"]["reallocateAllocation"]
+            .as_object()
+            .context("reallocateAllocation returned null")?;
+        serde_json::from_value(resp["data"]["reallocateAllocation"].clone())
+            .context("parsing reallocateAllocation result")
     }
 
     /// Get allocations from the indexer management API.
-    pub async fn get_allocations(&self) -> Result<Value> {
+    pub async fn get_allocations(&self) -> Result<Vec<IndexerAllocation>> {
         let query = format!(
             r#"{{ indexerAllocations(protocolNetwork: "{PROTOCOL_NETWORK}") {{
                 id subgraphDeployment allocatedTokens createdAtEpoch closedAtEpoch status
             }} }}"#
         );
         let resp = self.management_query(&query).await?;
-        Ok(resp["data"]["indexerAllocations"].clone())
+        serde_json::from_value(resp["data"]["indexerAllocations"].clone())
+            .context("parsing indexerAllocations")
+    }
+
+    /// List indexing rules (`graph indexer rules get all`).
+    /// With `merged`, each rule is combined with the global defaults.
+    pub async fn indexing_rules(&self, merged: bool) -> Result<Vec<IndexingRule>> {
+        let query = format!(
+            r#"query($merged: Boolean!, $network: String) {{
+                indexingRules(merged: $merged, protocolNetwork: $network) {{ {INDEXING_RULE_FIELDS} }}
+            }}"#
+        );
+        let vars = serde_json::json!({ "merged": merged, "network": PROTOCOL_NETWORK });
+        let resp = self.management_query_with(&query, &vars).await?;
+        serde_json::from_value(resp["data"]["indexingRules"].clone())
+            .context("parsing indexingRules")
+    }
+
+    /// Get a single rule (`graph indexer rules get <identifier>`).
+    /// Returns `None` if no rule exists for the identifier.
+    pub async fn indexing_rule(
+        &self,
+        identifier: &str,
+        merged: bool,
+    ) -> Result<Option<IndexingRule>> {
+        let query = format!(
+            r#"query($identifier: IndexingRuleIdentifier!, $merged: Boolean!) {{
+                indexingRule(identifier: $identifier, merged: $merged) {{ {INDEXING_RULE_FIELDS} }}
+            }}"#
+        );
+        let vars = serde_json::json!({
+            "identifier": { "identifier": identifier, "protocolNetwork": PROTOCOL_NETWORK },
+            "merged": merged,
+        });
+        let resp = self.management_query_with(&query, &vars).await?;
+        serde_json::from_value(resp["data"]["indexingRule"].clone()).context("parsing indexingRule")
+    }
+
+    /// Create or update a rule (`graph indexer rules set`).
+    pub async fn set_indexing_rule(&self, rule: &IndexingRuleInput) -> Result<IndexingRule> {
+        let query = format!(
+            r#"mutation($rule: IndexingRuleInput!) {{
+                setIndexingRule(rule: $rule) {{ {INDEXING_RULE_FIELDS} }}
+            }}"#
+        );
+        let vars = serde_json::json!({ "rule": rule });
+        let resp = self.management_query_with(&query, &vars).await?;
+        serde_json::from_value(resp["data"]["setIndexingRule"].clone())
+            .context("parsing setIndexingRule result")
+    }
+
+    /// Delete rules by identifier (`graph indexer rules delete`).
+    pub async fn delete_indexing_rules(&self, identifiers: &[&str]) -> Result<bool> {
+        let query = r#"mutation($identifiers: [IndexingRuleIdentifier!]!) {
+            deleteIndexingRules(identifiers: $identifiers)
+        }"#;
+        let identifiers: Vec<_> = identifiers
+            .iter()
+            .map(|id| serde_json::json!({ "identifier": id, "protocolNetwork": PROTOCOL_NETWORK }))
+            .collect();
+        let vars = serde_json::json!({ "identifiers": identifiers });
+        let resp = self.management_query_with(query, &vars).await?;
+        resp["data"]["deleteIndexingRules"]
+            .as_bool()
+            .context("deleteIndexingRules returned no result")
+    }
+
+    /// Wait for the agent's reconciliation loop to act on a deployment rule:
+    /// until the indexer has an active allocation on `deployment` (`allocated`)
+    /// or none (`!allocated`).
+    ///
+    /// Mines a block per poll, like `start-indexing`, so the agent's
+    /// block-driven loop keeps ticking. Returns the active allocation, if any.
+    pub async fn wait_for_rule_reconciliation(
+        &self,
+        deployment: DeploymentId,
+        allocated: bool,
+        timeout: Duration,
+    ) -> Result<Option<IndexerAllocation>> {
+        let result = self
+            .poll_until(timeout, Duration::from_secs(5), || async {
+                self.mine_blocks(1).await?;
+                let active = self
+                    .get_allocations()
+                    .await?
+                    .into_iter()
+                    .find(|a| a.is_active() && a.subgraph_deployment == deployment);
+                Ok(match (allocated, active) {
+                    (true, Some(alloc)) => Some(Some(alloc)),
+                    (false, None) => Some(None),
+                    _ => None,
+                })
+            })
+            .await;
+        match result {
+            PollResult::Ready(alloc) => Ok(alloc),
+            PollResult::TimedOut => anyhow::bail!(
+                "agent did not {} {deployment} within {timeout:?}",
+                if allocated {
+                    "allocate to"
+                } else {
+                    "close allocations on"
+                }
+            ),
+        }
+    }
+
+    /// Queue actions (`graph indexer actions queue`).
+    pub async fn queue_actions(&self, actions: &[ActionInput]) -> Result<Vec<Action>> {
+        let query = format!(
+            r#"mutation($actions: [ActionInput!]!) {{
+                queueActions(actions: $actions) {{ {ACTION_FIELDS} }}
+            }}"#
+        );
+        let vars = serde_json::json!({ "actions": actions });
+        let resp = self.management_query_with(&query, &vars).await?;
+        serde_json::from_value(resp["data"]["queueActions"].clone())
+            .context("parsing queueActions result")
+    }
+
+    /// List actions (`graph indexer actions get`), newest first.
+    pub async fn actions(&self, filter: &ActionFilter) -> Result<Vec<Action>> {
+        let query = format!(
+            r#"query($filter: ActionFilter!) {{
+                actions(filter: $filter, orderBy: id, orderDirection: desc) {{ {ACTION_FIELDS} }}
+            }}"#
+        );
+        let mut filter = serde_json::to_value(filter)?;
+        filter["protocolNetwork"] = PROTOCOL_NETWORK.into();
+        let vars = serde_json::json!({ "filter": filter });
+        let resp = self.management_query_with(&query, &vars).await?;
+        serde_json::from_value(resp["data"]["actions"].clone()).context("parsing actions")
+    }
+
+    /// Get a single action by ID, `None` if it doesn't exist (e.g. deleted).
+    pub async fn action(&self, id: u64) -> Result<Option<Action>> {
+        let query =
+            format!(r#"query($id: String!) {{ action(actionID: $id) {{ {ACTION_FIELDS} }} }}"#);
+        let vars = serde_json::json!({ "id": id.to_string() });
+        let resp = self.management_query_with(&query, &vars).await?;
+        serde_json::from_value(resp["data"]["action"].clone()).context("parsing action")
+    }
+
+    /// Approve queued actions (`graph indexer actions approve`).
+    pub async fn approve_actions(&self, ids: &[u64]) -> Result<Vec<Action>> {
+        self.action_ids_mutation("approveActions", ids).await
+    }
+
+    /// Cancel queued or approved actions (`graph indexer actions cancel`).
+    pub async fn cancel_actions(&self, ids: &[u64]) -> Result<Vec<Action>> {
+        self.action_ids_mutation("cancelActions", ids).await
+    }
+
+    /// Delete actions (`graph indexer actions delete`). Returns the count deleted.
+    pub async fn delete_actions(&self, ids: &[u64]) -> Result<u64> {
+        let query = r#"mutation($ids: [String!]!) { deleteActions(actionIDs: $ids) }"#;
+        let vars = serde_json::json!({ "ids": ids.iter().map(u64::to_string).collect::<Vec<_>>() });
+        let resp = self.management_query_with(query, &vars).await?;
+        resp["data"]["deleteActions"]
+            .as_u64()
+            .context("deleteActions returned no count")
+    }
+
+    /// Execute all approved actions in one batch (`graph indexer actions execute`).
+    pub async fn execute_approved_actions(&self) -> Result<Vec<Action>> {
+        let query = format!(r#"mutation {{ executeApprovedActions {{ {ACTION_FIELDS} }} }}"#);
+        let resp = self.management_query(&query).await?;
+        serde_json::from_value(resp["data"]["executeApprovedActions"].clone())
+            .context("parsing executeApprovedActions result")
+    }
+
+    async fn action_ids_mutation(&self, mutation: &str, ids: &[u64]) -> Result<Vec<Action>> {
+        let query = format!(
+            r#"mutation($ids: [String!]!) {{ {mutation}(actionIDs: $ids) {{ {ACTION_FIELDS} }} }}"#
+        );
+        let vars = serde_json::json!({ "ids": ids.iter().map(u64::to_string).collect::<Vec<_>>() });
+        let resp = self.management_query_with(&query, &vars).await?;
+        serde_json::from_value(resp["data"][mutation].clone())
+            .with_context(|| format!("parsing {mutation} result"))
+    }
+
+    /// Watch an action until it reaches a terminal status (success, failed,
+    /// canceled), recording the statuses it passes through.
+    pub async fn watch_action(&self, id: u64, timeout: Duration) -> Result<ActionWatch> {
+        let transitions = RefCell::new(Vec::new());
+        let result = self
+            .poll_until(timeout, Duration::from_secs(2), || async {
+                let action = self
+                    .action(id)
+                    .await?
+                    .with_context(|| format!("action {id} not found"))?;
+                let mut seen = transitions.borrow_mut();
+                if seen.last() != Some(&action.status) {
+                    seen.push(action.status);
+                }
+                Ok(action.status.is_terminal().then_some(action))
+            })
+            .await;
+        let transitions = transitions.into_inner();
+        match result {
+            PollResult::Ready(action) => Ok(ActionWatch {
+                action,
+                transitions,
+            }),
+            PollResult::TimedOut => {
+                anyhow::bail!("action {id} not finished after {timeout:?} (seen {transitions:?})")
+            }
+        }
+    }
+
+    /// List cost models (`graph indexer cost get all`), optionally only for
+    /// `deployments`. Deployments without their own model get the global one.
+    pub async fn cost_models(
+        &self,
+        deployments: Option<&[DeploymentId]>,
+    ) -> Result<Vec<CostModel>> {
+        let query = r#"query($deployments: [String!]) {
+            costModels(deployments: $deployments) { deployment model }
+        }"#;
+        let deployments: Option<Vec<String>> =
+            deployments.map(|ds| ds.iter().map(DeploymentId::to_string).collect());
+        let vars = serde_json::json!({ "deployments": deployments });
+        let resp = self.management_query_with(query, &vars).await?;
+        serde_json::from_value(resp["data"]["costModels"].clone()).context("parsing costModels")
+    }
+
+    /// Get the cost model applied to `deployment` (`graph indexer cost get`),
+    /// falling back to the global model.
+    pub async fn cost_model(&self, deployment: DeploymentId) -> Result<Option<CostModel>> {
+        self.cost_model_for(&deployment.to_string()).await
+    }
+
+    /// Get the global cost model (`graph indexer cost get global`).
+    pub async fn global_cost_model(&self) -> Result<Option<CostModel>> {
+        self.cost_model_for(GLOBAL_COST_MODEL).await
+    }
+
+    async fn cost_model_for(&self, deployment: &str) -> Result<Option<CostModel>> {
+        let query = r#"query($deployment: String!) {
+            costModel(deployment: $deployment) { deployment model }
+        }"#;
+        let vars = serde_json::json!({ "deployment": deployment });
+        let resp = self.management_query_with(query, &vars).await?;
+        serde_json::from_value(resp["data"]["costModel"].clone()).context("parsing costModel")
+    }
+
+    /// Create or replace a cost model (`graph indexer cost set model`).
+    /// Invalid Agora is rejected by the agent.
+    pub async fn set_cost_model(&self, model: &CostModel) -> Result<CostModel> {
+        let query = r#"mutation($costModel: CostModelInput!) {
+            setCostModel(costModel: $costModel) { deployment model }
+        }"#;
+        let vars = serde_json::json!({ "costModel": model });
+        let resp = self.management_query_with(query, &vars).await?;
+        serde_json::from_value(resp["data"]["setCostModel"].clone())
+            .context("parsing setCostModel result")
+    }
+
+    /// Delete cost models (`graph indexer cost delete`). Returns how many
+    /// were deleted.
+    pub async fn delete_cost_models(&self, deployments: &[DeploymentId]) -> Result<u64> {
+        let query = r#"mutation($deployments: [String!]!) {
+            deleteCostModels(deployments: $deployments)
+        }"#;
+        let deployments: Vec<String> = deployments.iter().map(DeploymentId::to_string).collect();
+        let vars = serde_json::json!({ "deployments": deployments });
+        let resp = self.management_query_with(query, &vars).await?;
+        resp["data"]["deleteCostModels"]
+            .as_u64()
+            .context("deleteCostModels returned no count")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_input_serializes_like_cli() {
+        let deployment: DeploymentId = "QmRcucmbxAXLaAZkkCR8Bdj1X7QGPLjfRmQ5H6tFhGqiHX"
+            .parse()
+            .unwrap();
+        let input = IndexingRuleInput::deployment(deployment)
+            .decision_basis(DecisionBasis::Always)
+            .allocation_amount(Grt::from_grt(1));
+        assert_eq!(
+            serde_json::to_value(&input).unwrap(),
+            serde_json::json!({
+                "identifier": "QmRcucmbxAXLaAZkkCR8Bdj1X7QGPLjfRmQ5H6tFhGqiHX",
+                "identifierType": "deployment",
+                "decisionBasis": "always",
+                "allocationAmount": "1000000000000000000",
+                "protocolNetwork": "eip155:1337",
+            })
+        );
+    }
+
+    #[test]
+    fn parses_rule() {
+        let rule: IndexingRule = serde_json::from_value(serde_json::json!({
+            "identifier": "global", "identifierType": "group",
+            "allocationAmount": "10000000000000000000", "allocationLifetime": null,
+            "autoRenewal": true, "parallelAllocations": null,
+            "maxAllocationPercentage": null, "minSignal": null, "maxSignal": null,
+            "minStake": null, "minAverageQueryFees": null, "custom": null,
+            "decisionBasis": "rules", "requireSupported": true, "safety": true,
+            "protocolNetwork": "eip155:1337",
+        }))
+        .unwrap();
+        assert_eq!(rule.identifier_type, IdentifierType::Group);
+        assert_eq!(rule.allocation_amount, Some(Grt::from_grt(10)));
+        assert_eq!(rule.decision_basis, DecisionBasis::Rules);
+    }
+
+    #[test]
+    fn action_input_serializes_like_cli() {
+        let deployment = DeploymentId::new(crate::B256::repeat_byte(1));
+        let allocate = ActionInput::allocate(deployment, Grt::from_wei(10_000_000_000_000_000));
+        let json = serde_json::to_value(&allocate).unwrap();
+        assert_eq!(json["type"], "allocate");
+        assert_eq!(json["status"], "queued");
+        assert_eq!(json["deploymentID"], deployment.to_string());
+        assert_eq!(json["amount"], "0.01");
+        assert!(json.get("allocationID").is_none());
+
+        let unallocate = ActionInput::unallocate(deployment, Address::repeat_byte(2)).forced_at(42);
+        let json = serde_json::to_value(&unallocate).unwrap();
+        assert_eq!(json["type"], "unallocate");
+        assert_eq!(json["force"], true);
+        assert_eq!(json["poiBlockNumber"], 42);
+        assert!(json.get("amount").is_none());
+    }
+
+    #[test]
+    fn parses_action_result() {
+        let action: Action = serde_json::from_value(serde_json::json!({
+            "id": 7, "status": "failed", "type": "present_poi",
+            "deploymentID": "QmRcucmbxAXLaAZkkCR8Bdj1X7QGPLjfRmQ5H6tFhGqiHX",
+            "allocationID": "0xf4ef6650e48d099a4972ea5b414dab86e1998bd3",
+            "amount": null, "poi": null, "force": null, "priority": 0,
+            "source": "indexerCLI", "reason": "manual", "transaction": null,
+            "failureReason": "IE069", "protocolNetwork": "eip155:1337",
+        }))
+        .unwrap();
+        assert_eq!(action.action_type, ActionType::PresentPoi);
+        assert!(action.status.is_terminal());
+        assert_eq!(action.amount, None);
+        assert_eq!(action.failure_reason.as_deref(), Some("IE069"));
+    }
+
+    #[test]
+    fn parses_reallocate_result() {
+        let result: ReallocateAllocationResult = serde_json::from_value(serde_json::json!({
+            "closedAllocation": "0xf4ef6650e48d099a4972ea5b414dab86e1998bd3",
+            "indexingRewardsCollected": "1.5",
+            "receiptsWorthCollecting": false,
+            "createdAllocation": "0x0000000000000000000000000000000000000001",
+            "createdAllocationStake": "0.01",
+        }))
+        .unwrap();
+        assert_eq!(
+            result.indexing_rewards_collected,
+            Grt::from_wei(1_500_000_000_000_000_000)
+        );
+        assert_eq!(
+            result.created_allocation_stake,
+            Grt::from_wei(10_000_000_000_000_000)
+        );
+        assert_ne!(result.closed_allocation, result.created_allocation);
+    }
+
+    #[test]
+    fn agora_flat_round_trips() {
+        let price = Grt::from_wei(20_000_000_000_000);
+        let model = agora_flat(price);
+        assert_eq!(model, "default => 0.00002;");
+        assert_eq!(agora_flat_price(&model), Some(price));
+        assert_eq!(agora_flat_price("  default=>1 ; "), Some(Grt::ONE));
+        assert_eq!(agora_flat_price("query { a } => 1;\ndefault => 2;"), None);
+        assert_eq!(
+            CostModel::global("default => 0.5;").flat_price(),
+            Some(Grt::from_wei(500_000_000_000_000_000))
+        );
     }
 }
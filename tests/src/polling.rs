@@ -99,48 +99,42 @@ impl TestNetwork {
     ///
     /// Returns the new epoch number.
     pub async fn advance_epochs(&self, n: u32) -> Result<u64> {
-        let em = &self.contracts.epoch_manager;
-        let raw = self.cast_call(em, "epochLength()(uint256)", &[])?;
-        let epoch_length: u64 = cast_parse_uint(&raw)
-            .parse()
-            .context("parsing epochLength")?;
-
-        let mut new_epoch = 0u64;
+        let mut new_epoch = self.epoch_state()?.epoch;
         for i in 0..n {
-            let raw = self.cast_call(em, "currentEpoch()(uint256)", &[])?;
-            let current_epoch: u64 = cast_parse_uint(&raw)
-                .parse()
-                .context("parsing currentEpoch")?;
-            let current_block: u64 = self.get_block_number().await?;
-            let raw = self.cast_call(em, "currentEpochBlock()(uint256)", &[])?;
-            let epoch_block: u64 = cast_parse_uint(&raw)
-                .parse()
-                .context("parsing currentEpochBlock")?;
-
-            let blocks_in_epoch = current_block.saturating_sub(epoch_block);
-            let blocks_to_mine = epoch_length - blocks_in_epoch;
+            // Re-read every step: the epoch length may have changed mid-epoch
+            let state = self.epoch_state()?;
+            let blocks_to_mine = state.blocks_until_next();
 
             eprintln!(
-                "advance_epochs: step {}/{n}, epoch={current_epoch}, \
+                "advance_epochs: step {}/{n}, epoch={}, \
                  mining {blocks_to_mine} blocks",
-                i + 1
+                i + 1,
+                state.epoch
             );
 
             self.mine_blocks(blocks_to_mine as u32).await?;
+            new_epoch = self.run_epoch_and_sync().await?;
+        }
+
+        Ok(new_epoch)
+    }
 
-            // Emit the EpochRun event so the network subgraph updates.
-            self.cast_send(em, "runEpoch()", &[])?;
+    /// Run the epoch that just started and wait for both subgraphs to index
+    /// it. Returns the epoch.
+    pub(crate) async fn run_epoch_and_sync(&self) -> Result<u64> {
+        let em = self.contracts.epoch_manager;
 
-            let raw = self.cast_call(em, "currentEpoch()(uint256)", &[])?;
-            new_epoch = cast_parse_uint(&raw)
-                .parse()
-                .context("parsing new currentEpoch")?;
+        // Emit the EpochRun event so the network subgraph updates.
+        self.cast_send(em, "runEpoch()", &[])?;
 
-            // Wait for both subgraphs to index this epoch before advancing further.
-            // The block-oracle needs to process each epoch individually to avoid gaps.
-            self.wait_for_epoch_sync(new_epoch).await?;
-        }
+        let raw = self.cast_call(em, "currentEpoch()(uint256)", &[])?;
+        let new_epoch = cast_parse_uint(&raw)
+            .parse()
+            .context("parsing new currentEpoch")?;
 
+        // Wait for both subgraphs to index this epoch before advancing further.
+        // The block-oracle needs to process each epoch individually to avoid gaps.
+        self.wait_for_epoch_sync(new_epoch).await?;
         Ok(new_epoch)
     }
 
@@ -211,9 +205,6 @@ impl TestNetwork {
     /// The indexer-agent may pause subgraphs during test runs; this ensures
     /// they keep indexing.
     async fn resume_subgraphs(&self) {
-        let admin_url = self
-            .subgraph_url
-            .replace(":8000/subgraphs/name/graph-network", ":8020/");
         let client = reqwest::Client::new();
         for name in ["graph-network", "block-oracle", "semiotic/tap"] {
             // Get the deployment ID for this subgraph
@@ -238,7 +229,7 @@ impl TestNetwork {
                 Err(_) => continue,
             };
             let _ = client
-                .post(&admin_url)
+                .post(&self.graph_node_admin_url)
                 .header("content-type", "application/json")
                 .json(&serde_json::json!({
                     "jsonrpc": "2.0",
@@ -280,6 +271,17 @@ impl TestNetwork {
         Ok(())
     }
 
+    /// Advance chain time so the next block's timestamp is at least
+    /// `timestamp`, e.g. a thaw request's `thawingUntil`. No-op if the chain
+    /// is already past it. Returns the new block timestamp.
+    pub async fn advance_time_to(&self, timestamp: u64) -> Result<u64> {
+        let now = self.get_block_timestamp()?;
+        if timestamp > now {
+            self.advance_time(timestamp - now).await?;
+        }
+        self.get_block_timestamp()
+    }
+
     /// Get the latest block number from the chain.
     pub async fn get_block_number(&self) -> Result<u64> {
         let client = reqwest::Client::new();
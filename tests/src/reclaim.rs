@@ -0,0 +1,335 @@
+//! Reclaim conditions and the `RewardsReclaimed` ledger.
+//!
+//! When RewardsManager can't pay rewards it mints them to a reclaim address
+//! instead, chosen by the condition (`RewardsCondition` in the contracts):
+//!
+//!   - `getReclaimAddress(keccak256("<CONDITION>"))` if set
+//!   - else `getDefaultReclaimAddress()`
+//!   - else the rewards are not minted at all (no event)
+//!
+//! Each reclaim emits `RewardsReclaimed(reason, amount, indexer, allocationID,
+//! subgraphDeploymentID)`. The event doesn't name the destination, so
+//! `rewards_reclaimed` resolves it from the reclaim configuration at the
+//! event's block (every transaction is mined in its own block, so that is the
+//! configuration the transaction saw). Subgraph-level reclaims (`NO_SIGNAL`,
+//! `BELOW_MINIMUM_SIGNAL`, ...) have a zero indexer and allocation.
+//! `check_reclaim_ledger` checks that a reclaim address received exactly
+//! the GRT the ledger routed to it.
+
+use alloy_primitives::{U256, keccak256};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::{Address, B256, Grt, TestNetwork};
+
+const REWARDS_RECLAIMED: &str = "RewardsReclaimed(bytes32,uint256,address,address,bytes32)";
+
+/// A reason RewardsManager withholds rewards (`RewardsCondition` library).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReclaimCondition {
+    /// No curation signal anywhere: issuance has no deployment to go to.
+    NoSignal,
+    /// Deployment's signal is below `minimumSubgraphSignal`.
+    BelowMinimumSignal,
+    /// Deployment has signal but nothing allocated to it.
+    NoAllocatedTokens,
+    /// Deployment denied by the availability oracle.
+    SubgraphDenied,
+    /// Indexer not eligible in the REO.
+    IndexerIneligible,
+    /// No POI presented within `maxPOIStaleness`.
+    StalePoi,
+    /// Zero POI presented.
+    ZeroPoi,
+    /// POI presented in the allocation's creation epoch (deferred, not
+    /// reclaimed).
+    AllocationTooYoung,
+    /// Rewards pending when the allocation is closed.
+    CloseAllocation,
+}
+
+impl ReclaimCondition {
+    pub const ALL: [ReclaimCondition; 9] = [
+        ReclaimCondition::NoSignal,
+        ReclaimCondition::BelowMinimumSignal,
+        ReclaimCondition::NoAllocatedTokens,
+        ReclaimCondition::SubgraphDenied,
+        ReclaimCondition::IndexerIneligible,
+        ReclaimCondition::StalePoi,
+        ReclaimCondition::ZeroPoi,
+        ReclaimCondition::AllocationTooYoung,
+        ReclaimCondition::CloseAllocation,
+    ];
+
+    /// The identifier the contracts hash, e.g. `STALE_POI`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ReclaimCondition::NoSignal => "NO_SIGNAL",
+            ReclaimCondition::BelowMinimumSignal => "BELOW_MINIMUM_SIGNAL",
+            ReclaimCondition::NoAllocatedTokens => "NO_ALLOCATED_TOKENS",
+            ReclaimCondition::SubgraphDenied => "SUBGRAPH_DENIED",
+            ReclaimCondition::IndexerIneligible => "INDEXER_INELIGIBLE",
+            ReclaimCondition::StalePoi => "STALE_POI",
+            ReclaimCondition::ZeroPoi => "ZERO_POI",
+            ReclaimCondition::AllocationTooYoung => "ALLOCATION_TOO_YOUNG",
+            ReclaimCondition::CloseAllocation => "CLOSE_ALLOCATION",
+        }
+    }
+
+    /// `keccak256(name)`, the key of `setReclaimAddress` and the event reason.
+    pub fn hash(self) -> B256 {
+        keccak256(self.name())
+    }
+
+    pub fn from_hash(hash: B256) -> Option<ReclaimCondition> {
+        Self::ALL.into_iter().find(|c| c.hash() == hash)
+    }
+}
+
+impl fmt::Display for ReclaimCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One `RewardsReclaimed` event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReclaimEvent {
+    pub block: u64,
+    pub condition: ReclaimCondition,
+    pub amount: Grt,
+    /// Zero for subgraph-level reclaims.
+    pub indexer: Address,
+    /// Zero for subgraph-level reclaims.
+    pub allocation: Address,
+    pub deployment: B256,
+    /// Where the GRT was minted (resolved from the reclaim configuration).
+    pub destination: Address,
+}
+
+impl ReclaimEvent {
+    /// Decode a `cast logs --json` entry. `destination` is left zero.
+    pub fn from_log(log: &Value) -> Result<ReclaimEvent> {
+        let topic = |i: usize| -> Result<B256> {
+            log["topics"][i]
+                .as_str()
+                .with_context(|| format!("RewardsReclaimed log without topic {i}"))?
+                .parse()
+                .context("parsing RewardsReclaimed topic")
+        };
+        let reason = topic(1)?;
+        let condition = ReclaimCondition::from_hash(reason)
+            .with_context(|| format!("unknown reclaim reason {reason}"))?;
+
+        let data = log["data"]
+            .as_str()
+            .context("RewardsReclaimed log without data")?;
+        let data = data.strip_prefix("0x").unwrap_or(data);
+        anyhow::ensure!(
+            data.len() == 128,
+            "unexpected RewardsReclaimed data {data:?}"
+        );
+        let amount: U256 = U256::from_str_radix(&data[..64], 16)?;
+        let deployment: B256 = data[64..].parse()?;
+
+        let block = log["blockNumber"]
+            .as_str()
+            .context("RewardsReclaimed log without blockNumber")?;
+        let block = u64::from_str_radix(block.trim_start_matches("0x"), 16)
+            .with_context(|| format!("parsing block number {block:?}"))?;
+
+        Ok(ReclaimEvent {
+            block,
+            condition,
+            amount: Grt::from_wei(amount.try_into().context("reclaimed amount out of range")?),
+            indexer: Address::from_word(topic(2)?),
+            allocation: Address::from_word(topic(3)?),
+            deployment,
+            destination: Address::ZERO,
+        })
+    }
+}
+
+/// Reclaims over a block range, summable by condition and destination.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReclaimLedger {
+    pub events: Vec<ReclaimEvent>,
+}
+
+impl ReclaimLedger {
+    pub fn total(&self) -> Grt {
+        self.sum(|_| true)
+    }
+
+    pub fn for_condition(&self, condition: ReclaimCondition) -> Grt {
+        self.sum(|e| e.condition == condition)
+    }
+
+    /// GRT minted to `destination`, across all conditions.
+    pub fn to_destination(&self, destination: Address) -> Grt {
+        self.sum(|e| e.destination == destination)
+    }
+
+    /// Totals per (condition, destination).
+    pub fn totals(&self) -> BTreeMap<(ReclaimCondition, Address), Grt> {
+        let mut totals = BTreeMap::new();
+        for e in &self.events {
+            let total = totals
+                .entry((e.condition, e.destination))
+                .or_insert(Grt::ZERO);
+            *total += e.amount;
+        }
+        totals
+    }
+
+    fn sum(&self, filter: impl Fn(&ReclaimEvent) -> bool) -> Grt {
+        self.events
+            .iter()
+            .filter(|e| filter(e))
+            .fold(Grt::ZERO, |total, e| total + e.amount)
+    }
+}
+
+impl TestNetwork {
+    /// All `RewardsReclaimed` events in `from_block..=to_block`, with their
+    /// destinations.
+    pub fn rewards_reclaimed(&self, from_block: u64, to_block: u64) -> Result<ReclaimLedger> {
+        let topic = keccak256(REWARDS_RECLAIMED);
+        let logs =
+            self.cast_logs_with_topic(self.contracts.rewards_manager, from_block, to_block, topic)?;
+        let mut events = Vec::with_capacity(logs.len());
+        for log in &logs {
+            let mut event = ReclaimEvent::from_log(log)?;
+            event.destination = self.reclaim_destination_at(event.condition, event.block)?;
+            events.push(event);
+        }
+        Ok(ReclaimLedger { events })
+    }
+
+    /// Check the ledger over `from_block+1..=to_block` against `destination`'s
+    /// GRT balance: every GRT it received is a logged reclaim routed to it.
+    pub fn check_reclaim_ledger(
+        &self,
+        destination: Address,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<ReclaimLedger> {
+        let ledger = self.rewards_reclaimed(from_block + 1, to_block)?;
+        for ((condition, to), amount) in ledger.totals() {
+            eprintln!("  Ledger: {amount} GRT reclaimed as {condition} to {to}");
+        }
+        let received = self.grt_balance_of_at(destination, to_block)?
+            - self.grt_balance_of_at(destination, from_block)?;
+        let logged = ledger.to_destination(destination);
+        anyhow::ensure!(
+            logged == received,
+            "RewardsReclaimed events account for {logged} GRT to {destination}, \
+             but it received {received} GRT over blocks {from_block}..={to_block}"
+        );
+        Ok(ledger)
+    }
+
+    /// Where `condition`'s rewards were minted at the end of `block`: its
+    /// reclaim address, else the default (zero if neither is set).
+    pub fn reclaim_destination_at(
+        &self,
+        condition: ReclaimCondition,
+        block: u64,
+    ) -> Result<Address> {
+        let rm = self.contracts.rewards_manager;
+        let output = self.cast_call_at(
+            block,
+            rm,
+            "getReclaimAddress(bytes32)(address)",
+            &[&condition.hash().to_string()],
+        )?;
+        let address = crate::ids::parse_address(&output)?;
+        if address != Address::ZERO {
+            return Ok(address);
+        }
+        let output = self.cast_call_at(block, rm, "getDefaultReclaimAddress()(address)", &[])?;
+        crate::ids::parse_address(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn condition_hashes_are_canonical() {
+        // cast keccak <NAME>
+        let expected = [
+            "0xc4f0cc019da7ea08f11c5297dc7707d01f1d04d2d2abb872bd6ccaded60fe62f",
+            "0x286bc720d7379cdd3bb6c5af9b205d2205b5459efcdb8cb91fe5275377afda00",
+            "0xb80fe864da0ef81142bb55113892e83f1ca4204b8f95de9e0c8db69293e537a1",
+            "0xc0f4a5620db2f97e7c3a4ba7058497eaa0d497538b2666d66bd6932f25345c88",
+            "0xfcadc72cad493def76767524554db9da829b6aca9457c0187f63000dba3c9439",
+            "0xe677423ace949fe7684efc4b33b0b10dc0f71b38c22370d74dad5ff6bec3e311",
+            "0xf067261e30ea99a11911c4e98249a1645a4870b3ef56b8aa8b8967e15a543095",
+            "0x2954e3d6871833e8c64d9f3d7f36cfa71ced919225bb897d18981ad793942092",
+            "0x3021a5ea86e7115dadc0819121dc2b1f58b45c2372d2e93b593567f0dd797df8",
+        ];
+        for (condition, hash) in ReclaimCondition::ALL.into_iter().zip(expected) {
+            assert_eq!(condition.hash().to_string(), hash, "{condition}");
+        }
+        for condition in ReclaimCondition::ALL {
+            assert_eq!(
+                ReclaimCondition::from_hash(condition.hash()),
+                Some(condition)
+            );
+        }
+        assert_eq!(ReclaimCondition::from_hash(B256::ZERO), None);
+    }
+
+    #[test]
+    fn decodes_and_sums_events() {
+        let indexer = Address::repeat_byte(0x11);
+        let log = |condition: ReclaimCondition, amount: u64| {
+            json!({
+                "blockNumber": "0x2a",
+                "topics": [
+                    keccak256(REWARDS_RECLAIMED).to_string(),
+                    condition.hash().to_string(),
+                    indexer.into_word().to_string(),
+                    B256::ZERO.to_string(),
+                ],
+                "data": format!("0x{amount:064x}{}", "ab".repeat(32)),
+            })
+        };
+        let event = ReclaimEvent::from_log(&log(ReclaimCondition::StalePoi, 7)).unwrap();
+        assert_eq!(event.block, 42);
+        assert_eq!(event.amount, Grt::from_wei(7));
+        assert_eq!(event.indexer, indexer);
+        assert_eq!(event.allocation, Address::ZERO);
+        assert_eq!(event.deployment, B256::repeat_byte(0xab));
+
+        let destination = Address::repeat_byte(0x55);
+        let mut events = Vec::new();
+        for (condition, amount) in [
+            (ReclaimCondition::StalePoi, 7),
+            (ReclaimCondition::StalePoi, 3),
+            (ReclaimCondition::NoSignal, 5),
+        ] {
+            let mut event = ReclaimEvent::from_log(&log(condition, amount)).unwrap();
+            if condition == ReclaimCondition::StalePoi {
+                event.destination = destination;
+            }
+            events.push(event);
+        }
+        let ledger = ReclaimLedger { events };
+        assert_eq!(ledger.total(), Grt::from_wei(15));
+        assert_eq!(
+            ledger.for_condition(ReclaimCondition::StalePoi),
+            Grt::from_wei(10)
+        );
+        assert_eq!(ledger.to_destination(destination), Grt::from_wei(10));
+        assert_eq!(
+            ledger.totals()[&(ReclaimCondition::NoSignal, Address::ZERO)],
+            Grt::from_wei(5)
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Indexer registration with SubgraphService (url, geohash, payments destination).
+//!
+//! indexer-agent registers the indexer on startup from
+//! `INDEXER_AGENT_PUBLIC_INDEXER_URL` and `INDEXER_AGENT_INDEXER_GEO_COORDINATES`
+//! (geohashed). These helpers do the same from Rust so URL changes,
+//! re-registration and extra indexers can be tested:
+//!
+//!   - `register_indexer` → `SubgraphService.register(indexer, abi.encode(url, geohash, paymentsDestination))`
+//!   - `set_payments_destination` → `SubgraphService.setPaymentsDestination`
+//!   - `geohash` → encode/decode/validate, as the agent geohashes its coordinates
+//!
+//! The network subgraph exposes the registered `url`/`geoHash` on the Indexer
+//! entity (see `query_indexer`).
+
+use alloy_primitives::Bytes;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::SolValue;
+use anyhow::{Context, Result};
+
+use crate::{Address, TestNetwork};
+
+/// Geohash encoding (base32, interleaved longitude/latitude bits).
+pub mod geohash {
+    use anyhow::Result;
+
+    const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+    /// Longest geohash accepted (~19mm cells); the agent uses 9 characters.
+    pub const MAX_PRECISION: usize = 12;
+
+    /// Geohash of a coordinate with `precision` characters.
+    pub fn encode(lat: f64, lon: f64, precision: usize) -> Result<String> {
+        anyhow::ensure!((-90.0..=90.0).contains(&lat), "latitude {lat} out of range");
+        anyhow::ensure!(
+            (-180.0..=180.0).contains(&lon),
+            "longitude {lon} out of range"
+        );
+        anyhow::ensure!(
+            (1..=MAX_PRECISION).contains(&precision),
+            "precision {precision} out of range 1..={MAX_PRECISION}"
+        );
+        let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+        let mut hash = String::with_capacity(precision);
+        let mut even = true;
+        while hash.len() < precision {
+            let mut index = 0;
+            for _ in 0..5 {
+                let (range, value) = if even {
+                    (&mut lon_range, lon)
+                } else {
+                    (&mut lat_range, lat)
+                };
+                let mid = (range.0 + range.1) / 2.0;
+                index <<= 1;
+                if value >= mid {
+                    index |= 1;
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+            hash.push(ALPHABET[index] as char);
+        }
+        Ok(hash)
+    }
+
+    /// Bounds of the cell a geohash denotes: `((lat_min, lat_max), (lon_min, lon_max))`.
+    pub fn bounds(hash: &str) -> Result<((f64, f64), (f64, f64))> {
+        validate(hash)?;
+        let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+        let mut even = true;
+        for c in hash.bytes() {
+            let index = ALPHABET.iter().position(|&a| a == c).unwrap_or_default();
+            for bit in (0..5).rev() {
+                let range: &mut (f64, f64) = if even { &mut lon_range } else { &mut lat_range };
+                let mid = (range.0 + range.1) / 2.0;
+                if index >> bit & 1 == 1 {
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+        }
+        Ok((lat_range, lon_range))
+    }
+
+    /// Center `(lat, lon)` of a geohash cell.
+    pub fn decode(hash: &str) -> Result<(f64, f64)> {
+        let ((lat_min, lat_max), (lon_min, lon_max)) = bounds(hash)?;
+        Ok(((lat_min + lat_max) / 2.0, (lon_min + lon_max) / 2.0))
+    }
+
+    /// Non-empty, at most `MAX_PRECISION` characters, lowercase base32 alphabet.
+    pub fn validate(hash: &str) -> Result<()> {
+        anyhow::ensure!(!hash.is_empty(), "empty geohash");
+        anyhow::ensure!(
+            hash.len() <= MAX_PRECISION,
+            "geohash {hash:?} longer than {MAX_PRECISION} characters"
+        );
+        if let Some(c) = hash.bytes().find(|c| !ALPHABET.contains(c)) {
+            anyhow::bail!("invalid geohash character {:?} in {hash:?}", c as char);
+        }
+        Ok(())
+    }
+}
+
+/// What an indexer registers with SubgraphService.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registration {
+    /// Public indexer-service URL the gateway routes queries to.
+    pub url: String,
+    pub geohash: String,
+    /// Where query fees and rewards are sent; zero keeps them in the provision.
+    pub payments_destination: Address,
+}
+
+impl Registration {
+    /// A registration at `lat`/`lon`, geohashed with 9 characters like the agent.
+    pub fn new(url: &str, lat: f64, lon: f64) -> Result<Self> {
+        Ok(Self {
+            url: url.into(),
+            geohash: geohash::encode(lat, lon, 9)?,
+            payments_destination: Address::ZERO,
+        })
+    }
+
+    pub fn payments_destination(mut self, destination: Address) -> Self {
+        self.payments_destination = destination;
+        self
+    }
+
+    /// The checks SubgraphService applies (non-empty url and geohash), plus
+    /// an http(s) URL and a well-formed geohash.
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(!self.url.is_empty(), "empty indexer URL");
+        anyhow::ensure!(
+            self.url.starts_with("http://") || self.url.starts_with("https://"),
+            "indexer URL {:?} is not http(s)",
+            self.url
+        );
+        geohash::validate(&self.geohash)
+    }
+
+    /// `register` data: `abi.encode(url, geohash, paymentsDestination)`.
+    pub fn encode(&self) -> Vec<u8> {
+        (
+            self.url.clone(),
+            self.geohash.clone(),
+            self.payments_destination,
+        )
+            .abi_encode_params()
+    }
+}
+
+impl TestNetwork {
+    /// Register (or re-register) the local indexer with SubgraphService, as
+    /// the indexer (RECEIVER_SECRET). BaselineTestPlan 1.2.
+    pub fn register_indexer(&self, registration: &Registration) -> Result<()> {
+        self.register_indexer_as(&self.receiver_secret, registration)
+            .map(drop)
+    }
+
+    /// Register the indexer owning `indexer_secret` (e.g. an extra indexer
+    /// with its own provision). Returns the indexer address.
+    pub fn register_indexer_as(
+        &self,
+        indexer_secret: &str,
+        registration: &Registration,
+    ) -> Result<Address> {
+        registration.validate()?;
+        let indexer: PrivateKeySigner = indexer_secret.parse().context("parsing indexer key")?;
+        let indexer = indexer.address();
+        let data = Bytes::from(registration.encode());
+        self.cast_send_as(
+            indexer_secret,
+            self.contracts.subgraph_service,
+            "register(address,bytes)",
+            &[&indexer.to_string(), &data.to_string()],
+        )
+        .with_context(|| format!("registering indexer {indexer}"))?;
+        Ok(indexer)
+    }
+
+    /// `register` without client-side validation, for negative tests.
+    /// Returns `Ok(false)` if the call reverts.
+    pub fn try_register_indexer(&self, registration: &Registration) -> Result<bool> {
+        let data = Bytes::from(registration.encode());
+        self.cast_send_may_revert(
+            &self.receiver_secret,
+            self.contracts.subgraph_service,
+            "register(address,bytes)",
+            &[&self.indexer_address.to_string(), &data.to_string()],
+        )
+    }
+
+    /// Set where the local indexer's payments go, as the indexer.
+    pub fn set_payments_destination(&self, destination: Address) -> Result<()> {
+        self.cast_send_as_indexer(
+            self.contracts.subgraph_service,
+            "setPaymentsDestination(address)",
+            &[&destination.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// The indexer's payments destination (zero if unset).
+    pub fn payments_destination(&self, indexer: Address) -> Result<Address> {
+        let output = self.cast_call(
+            self.contracts.subgraph_service,
+            "paymentsDestination(address)(address)",
+            &[&indexer.to_string()],
+        )?;
+        crate::ids::parse_address(&output)
+    }
+
+    /// The registration as indexed by the network subgraph (`url`, `geoHash`),
+    /// with the payments destination read on-chain.
+    pub async fn indexer_registration(&self, indexer: Address) -> Result<Registration> {
+        let entity = self.query_indexer(indexer).await?;
+        anyhow::ensure!(
+            !entity.is_null(),
+            "indexer {indexer} not in network subgraph"
+        );
+        Ok(Registration {
+            url: entity["url"].as_str().unwrap_or_default().to_string(),
+            geohash: entity["geoHash"].as_str().unwrap_or_default().to_string(),
+            payments_destination: self.payments_destination(indexer)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geohash_known_values() {
+        // Wikipedia example
+        assert_eq!(
+            geohash::encode(57.64911, 10.40744, 11).unwrap(),
+            "u4pruydqqvj"
+        );
+        // The local agent's coordinates ("1 1")
+        assert_eq!(geohash::encode(1.0, 1.0, 9).unwrap(), "s00twy01m");
+    }
+
+    #[test]
+    fn geohash_round_trips() {
+        for (lat, lon) in [(0.0, 0.0), (-33.8688, 151.2093), (40.7128, -74.006)] {
+            let hash = geohash::encode(lat, lon, 9).unwrap();
+            let ((lat_min, lat_max), (lon_min, lon_max)) = geohash::bounds(&hash).unwrap();
+            assert!((lat_min..=lat_max).contains(&lat), "{hash}");
+            assert!((lon_min..=lon_max).contains(&lon), "{hash}");
+            let (dlat, dlon) = geohash::decode(&hash).unwrap();
+            assert!((dlat - lat).abs() < 1e-3 && (dlon - lon).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn geohash_validation() {
+        assert!(geohash::validate("s00twy01m").is_ok());
+        assert!(geohash::validate("").is_err());
+        assert!(geohash::validate("s00a").is_err()); // 'a' is not in the alphabet
+        assert!(geohash::validate("S00").is_err());
+        assert!(geohash::validate("0123456789bcd").is_err());
+        assert!(geohash::encode(91.0, 0.0, 9).is_err());
+        assert!(geohash::encode(0.0, 0.0, 0).is_err());
+    }
+
+    #[test]
+    fn registration_validates_and_encodes() {
+        let reg = Registration::new("http://indexer-service:7601", 1.0, 1.0)
+            .unwrap()
+            .payments_destination(Address::repeat_byte(0x11));
+        reg.validate().unwrap();
+        let (url, geo, dest): (String, String, Address) =
+            SolValue::abi_decode_params(&reg.encode()).unwrap();
+        assert_eq!(url, reg.url);
+        assert_eq!(geo, "s00twy01m");
+        assert_eq!(dest, Address::repeat_byte(0x11));
+
+        let mut bad = reg.clone();
+        bad.url = "indexer-service:7601".into();
+        assert!(bad.validate().is_err());
+        bad.url = String::new();
+        assert!(bad.validate().is_err());
+    }
+}
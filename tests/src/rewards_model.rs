@@ -0,0 +1,375 @@
+//! Off-chain model of RewardsManager's indexing rewards accounting.
+//!
+//! RewardsManager issues `issuancePerBlock` GRT per block, split over
+//! deployments by curation signal and over allocations by allocated tokens,
+//! through three fixed-point (1e18) accumulators:
+//!
+//!   - `accRewardsPerSignal`: issuance per signalled GRT, global
+//!   - `accRewardsForSubgraph`: GRT issued to a deployment, from its pool's
+//!     share of the global signal
+//!   - `accRewardsPerAllocatedToken`: a deployment's GRT per allocated GRT
+//!
+//! Every accumulator is lazy: views extend the last stored snapshot to the
+//! current block, and signal/allocation updates store a new one. The model
+//! takes the same snapshot (`RewardsSnapshot`, read with `rewards_snapshot_at`)
+//! and reproduces the views' integer math, so a prediction for the block a
+//! `collect`/`closeAllocation` lands in is exact, not approximate:
+//!
+//!   rewards = (accPerAllocatedToken(block) − allocation snapshot) × tokens / 1e18
+//!           + accRewardsPending
+//!
+//! Conditions that stop or redirect rewards:
+//!   - denied deployment: accumulators freeze, collection is deferred
+//!   - pool signal below `minimumSubgraphSignal`, no global signal, nothing
+//!     allocated: the deployment's share is not accumulated
+//!   - ineligible indexer (REO): the allocation's rewards are reclaimed
+//!
+//! `issuancePerBlock` is RewardsManager's own rate, or the IssuanceAllocator's
+//! allocation to it when one is configured (`getAllocatedIssuancePerBlock`).
+
+use alloy_primitives::U256;
+use anyhow::{Context, Result};
+
+use crate::allocation::OnChainAllocation;
+use crate::cast::cast_parse_uint;
+use crate::{DeploymentId, Grt, TestNetwork};
+
+/// RewardsManager's fixed-point scale.
+pub const FIXED_POINT: u128 = 1_000_000_000_000_000_000;
+
+/// `a * b / c` in 256 bits, rounded down (Solidity's `mul`/`div` order).
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    if c == 0 {
+        return 0;
+    }
+    (U256::from(a) * U256::from(b) / U256::from(c)).saturating_to()
+}
+
+/// Global accumulator state (`updateAccRewardsPerSignal` inputs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlobalRewardsState {
+    pub issuance_per_block: Grt,
+    pub acc_rewards_per_signal: u128,
+    pub acc_rewards_per_signal_last_block_updated: u64,
+    /// L2Curation's GRT balance.
+    pub total_signal: Grt,
+    pub minimum_signal: Grt,
+}
+
+impl GlobalRewardsState {
+    /// `getAccRewardsPerSignal()` as of `block`.
+    pub fn acc_rewards_per_signal_at(&self, block: u64) -> u128 {
+        let blocks = block.saturating_sub(self.acc_rewards_per_signal_last_block_updated);
+        let issued = self.issuance_per_block.wei() * u128::from(blocks);
+        self.acc_rewards_per_signal + mul_div(issued, FIXED_POINT, self.total_signal.wei())
+    }
+}
+
+/// One deployment's entry in `subgraphs(bytes32)` plus the totals its views
+/// read from Curation and SubgraphService.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubgraphRewardsState {
+    pub acc_rewards_for_subgraph: Grt,
+    pub acc_rewards_for_subgraph_snapshot: Grt,
+    pub acc_rewards_per_signal_snapshot: u128,
+    pub acc_rewards_per_allocated_token: u128,
+    /// Curation pool tokens.
+    pub signal: Grt,
+    /// Tokens allocated by all indexers.
+    pub allocated_tokens: Grt,
+    pub denied: bool,
+}
+
+/// Everything the rewards views depend on, read at the end of `block`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RewardsSnapshot {
+    pub block: u64,
+    pub global: GlobalRewardsState,
+    pub subgraph: SubgraphRewardsState,
+}
+
+/// Predicted outcome of collecting an allocation's rewards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PredictedRewards {
+    /// Paid to the indexer (and its delegators).
+    pub claimed: Grt,
+    /// Sent to a reclaim address instead.
+    pub reclaimed: Grt,
+    /// Kept for a later collection (denied deployment).
+    pub deferred: Grt,
+}
+
+impl RewardsSnapshot {
+    /// Whether the deployment accumulates new rewards at all.
+    pub fn accrues(&self) -> bool {
+        !self.subgraph.denied
+            && self.subgraph.signal >= self.global.minimum_signal
+            && !self.global.total_signal.is_zero()
+    }
+
+    /// `getAccRewardsForSubgraph(deployment)` as of `block`.
+    pub fn acc_rewards_for_subgraph_at(&self, block: u64) -> Grt {
+        let stored = self.subgraph.acc_rewards_for_subgraph;
+        if !self.accrues() {
+            return stored;
+        }
+        let per_signal = self
+            .global
+            .acc_rewards_per_signal_at(block)
+            .saturating_sub(self.subgraph.acc_rewards_per_signal_snapshot);
+        stored + Grt::from_wei(mul_div(per_signal, self.subgraph.signal.wei(), FIXED_POINT))
+    }
+
+    /// `getAccRewardsPerAllocatedToken(deployment)` as of `block`.
+    pub fn acc_rewards_per_allocated_token_at(&self, block: u64) -> u128 {
+        let stored = self.subgraph.acc_rewards_per_allocated_token;
+        if self.subgraph.allocated_tokens.is_zero() {
+            return stored;
+        }
+        let new_rewards = self
+            .acc_rewards_for_subgraph_at(block)
+            .saturating_sub(self.subgraph.acc_rewards_for_subgraph_snapshot);
+        stored
+            + mul_div(
+                new_rewards.wei(),
+                FIXED_POINT,
+                self.subgraph.allocated_tokens.wei(),
+            )
+    }
+
+    /// `getRewards(subgraphService, allocation)` as of `block`.
+    pub fn allocation_rewards_at(&self, allocation: &OnChainAllocation, block: u64) -> Grt {
+        if !allocation.is_open() {
+            return Grt::ZERO;
+        }
+        rewards_for_tokens(
+            allocation.tokens,
+            allocation.acc_rewards_per_allocated_token,
+            self.acc_rewards_per_allocated_token_at(block),
+        ) + allocation.acc_rewards_pending
+    }
+
+    /// Outcome of collecting `allocation`'s rewards in `block` for an indexer
+    /// with the given REO eligibility.
+    pub fn predict_collect(
+        &self,
+        allocation: &OnChainAllocation,
+        block: u64,
+        eligible: bool,
+    ) -> PredictedRewards {
+        let rewards = self.allocation_rewards_at(allocation, block);
+        if self.subgraph.denied {
+            PredictedRewards {
+                deferred: rewards,
+                ..Default::default()
+            }
+        } else if !eligible {
+            PredictedRewards {
+                reclaimed: rewards,
+                ..Default::default()
+            }
+        } else {
+            PredictedRewards {
+                claimed: rewards,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// `_calcRewards`: rewards for `tokens` between two per-allocated-token
+/// accumulator values.
+pub fn rewards_for_tokens(tokens: Grt, start: u128, end: u128) -> Grt {
+    Grt::from_wei(mul_div(
+        end.saturating_sub(start),
+        tokens.wei(),
+        FIXED_POINT,
+    ))
+}
+
+impl TestNetwork {
+    /// Read the rewards state of `deployment` at the end of `block`.
+    pub fn rewards_snapshot_at(
+        &self,
+        deployment: DeploymentId,
+        block: u64,
+    ) -> Result<RewardsSnapshot> {
+        let rm = self.contracts.rewards_manager;
+        let id = deployment.bytes32().to_string();
+        let call = |to, sig: &str, args: &[&str]| self.cast_call_at(block, to, sig, args);
+        let uint = |output: String, what: &str| -> Result<u128> {
+            cast_parse_uint(&output)
+                .parse()
+                .with_context(|| format!("parsing {what} {output:?}"))
+        };
+
+        let total_signal = call(
+            self.contracts.grt_token,
+            "balanceOf(address)(uint256)",
+            &[&self.contracts.curation.to_string()],
+        )?;
+        let global = GlobalRewardsState {
+            issuance_per_block: self.rewards_issuance_per_block_at(block)?,
+            acc_rewards_per_signal: uint(
+                call(rm, "accRewardsPerSignal()(uint256)", &[])?,
+                "accRewardsPerSignal",
+            )?,
+            acc_rewards_per_signal_last_block_updated: uint(
+                call(rm, "accRewardsPerSignalLastBlockUpdated()(uint256)", &[])?,
+                "accRewardsPerSignalLastBlockUpdated",
+            )?
+            .try_into()
+            .context("accRewardsPerSignalLastBlockUpdated out of range")?,
+            total_signal: Grt::from_wei(uint(total_signal, "curation balance")?),
+            minimum_signal: Grt::from_wei(uint(
+                call(rm, "minimumSubgraphSignal()(uint256)", &[])?,
+                "minimumSubgraphSignal",
+            )?),
+        };
+
+        let stored = call(
+            rm,
+            "subgraphs(bytes32)(uint256,uint256,uint256,uint256)",
+            &[&id],
+        )?;
+        let stored: Vec<u128> = stored
+            .lines()
+            .map(|line| uint(line.to_string(), "subgraphs"))
+            .collect::<Result<_>>()?;
+        let [
+            for_subgraph,
+            for_subgraph_snapshot,
+            per_signal_snapshot,
+            per_allocated_token,
+        ] = stored[..]
+        else {
+            anyhow::bail!("subgraphs({id}) returned {} values", stored.len());
+        };
+        let signal = call(
+            self.contracts.curation,
+            "getCurationPoolTokens(bytes32)(uint256)",
+            &[&id],
+        )?;
+        let allocated = call(
+            self.contracts.subgraph_service,
+            "getSubgraphAllocatedTokens(bytes32)(uint256)",
+            &[&id],
+        )?;
+        let denied = call(rm, "isDenied(bytes32)(bool)", &[&id])?;
+        let subgraph = SubgraphRewardsState {
+            acc_rewards_for_subgraph: Grt::from_wei(for_subgraph),
+            acc_rewards_for_subgraph_snapshot: Grt::from_wei(for_subgraph_snapshot),
+            acc_rewards_per_signal_snapshot: per_signal_snapshot,
+            acc_rewards_per_allocated_token: per_allocated_token,
+            signal: Grt::from_wei(uint(signal, "pool tokens")?),
+            allocated_tokens: Grt::from_wei(uint(allocated, "allocated tokens")?),
+            denied: denied.trim() == "true",
+        };
+
+        Ok(RewardsSnapshot {
+            block,
+            global,
+            subgraph,
+        })
+    }
+
+    /// `rewards_snapshot_at` the latest block.
+    pub fn rewards_snapshot(&self, deployment: DeploymentId) -> Result<RewardsSnapshot> {
+        let block = self.get_block_number_sync()?;
+        self.rewards_snapshot_at(deployment, block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> RewardsSnapshot {
+        RewardsSnapshot {
+            block: 100,
+            global: GlobalRewardsState {
+                issuance_per_block: Grt::from_grt(100),
+                acc_rewards_per_signal: 0,
+                acc_rewards_per_signal_last_block_updated: 100,
+                total_signal: Grt::from_grt(3_000),
+                minimum_signal: Grt::ZERO,
+            },
+            subgraph: SubgraphRewardsState {
+                acc_rewards_for_subgraph: Grt::ZERO,
+                acc_rewards_for_subgraph_snapshot: Grt::ZERO,
+                acc_rewards_per_signal_snapshot: 0,
+                acc_rewards_per_allocated_token: 0,
+                signal: Grt::from_grt(1_000),
+                allocated_tokens: Grt::from_grt(10),
+                denied: false,
+            },
+        }
+    }
+
+    fn allocation(tokens: Grt) -> OnChainAllocation {
+        OnChainAllocation {
+            id: Default::default(),
+            indexer: Default::default(),
+            deployment: DeploymentId::new(Default::default()),
+            tokens,
+            created_at: 1,
+            closed_at: 0,
+            last_poi_presented_at: 0,
+            acc_rewards_per_allocated_token: 0,
+            acc_rewards_pending: Grt::ZERO,
+            created_at_epoch: 1,
+        }
+    }
+
+    #[test]
+    fn issuance_splits_by_signal_then_tokens() {
+        let s = snapshot();
+        // 3 blocks × 100 GRT, a third of the signal → 100 GRT (minus rounding)
+        let for_subgraph = s.acc_rewards_for_subgraph_at(103);
+        assert!(Grt::from_grt(100).wei() - for_subgraph.wei() < 1_000);
+        // A quarter of the allocated tokens gets a quarter of it
+        let rewards =
+            s.allocation_rewards_at(&allocation(Grt::from_wei(2_500_000_000_000_000_000)), 103);
+        assert!(for_subgraph.wei() / 4 - rewards.wei() < 1_000);
+        assert_eq!(
+            s.allocation_rewards_at(&allocation(Grt::ONE), 100),
+            Grt::ZERO
+        );
+    }
+
+    #[test]
+    fn conditions_stop_accrual() {
+        let mut denied = snapshot();
+        denied.subgraph.denied = true;
+        assert_eq!(denied.acc_rewards_for_subgraph_at(200), Grt::ZERO);
+
+        let mut below_minimum = snapshot();
+        below_minimum.global.minimum_signal = Grt::from_grt(2_000);
+        assert!(!below_minimum.accrues());
+
+        let mut unallocated = snapshot();
+        unallocated.subgraph.allocated_tokens = Grt::ZERO;
+        assert_eq!(unallocated.acc_rewards_per_allocated_token_at(200), 0);
+
+        let mut no_signal = snapshot();
+        no_signal.global.total_signal = Grt::ZERO;
+        assert_eq!(no_signal.global.acc_rewards_per_signal_at(200), 0);
+    }
+
+    #[test]
+    fn collection_outcome_follows_conditions() {
+        let alloc = allocation(Grt::ONE);
+        let s = snapshot();
+        let expected = s.allocation_rewards_at(&alloc, 110);
+        assert!(!expected.is_zero());
+        assert_eq!(s.predict_collect(&alloc, 110, true).claimed, expected);
+        assert_eq!(s.predict_collect(&alloc, 110, false).reclaimed, expected);
+
+        let mut denied = s;
+        denied.subgraph.acc_rewards_per_allocated_token = 5 * FIXED_POINT;
+        denied.subgraph.denied = true;
+        let frozen = denied.predict_collect(&alloc, 110, true);
+        assert_eq!(frozen.claimed, Grt::ZERO);
+        assert_eq!(frozen.deferred, Grt::from_grt(5));
+    }
+}
@@ -10,11 +10,87 @@
 //!   - `provision_add` → `graph indexer provisions add` (BaselineTestPlan 3.2)
 //!   - `provision_thaw` → `graph indexer provisions thaw` (BaselineTestPlan 3.3)
 //!   - `provision_deprovision` → `graph indexer provisions remove` (BaselineTestPlan 3.4)
+//!   - `set_provision_parameters` + `accept_provision_parameters` → two-step
+//!     change of `maxVerifierCut`/`thawingPeriod` (staged by the indexer,
+//!     accepted through SubgraphService, which checks its allowed ranges)
+//!   - `set_operator_for` / `is_authorized` → per-data-service operators
 
+use alloy_primitives::{U256, hex};
+use alloy_sol_types::{SolType, sol, sol_data};
 use anyhow::{Context, Result};
 
-use crate::TestNetwork;
-use crate::cast::cast_parse_uint;
+use crate::{Address, Grt, TestNetwork};
+
+sol! {
+    /// `IHorizonStakingTypes.Provision` as returned by `getProvision`.
+    struct ProvisionState {
+        uint256 tokens;
+        uint256 tokensThawing;
+        uint256 sharesThawing;
+        uint32 maxVerifierCut;
+        uint64 thawingPeriod;
+        uint64 createdAt;
+        uint32 maxVerifierCutPending;
+        uint64 thawingPeriodPending;
+        uint256 lastParametersStagedAt;
+        uint256 thawingNonce;
+    }
+}
+
+/// On-chain provision of a service provider with a data service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Provision {
+    pub tokens: Grt,
+    pub tokens_thawing: Grt,
+    /// Shares of the thawing tokens, split across thaw requests.
+    pub shares_thawing: u128,
+    /// Max slashable fraction the verifier may take, in PPM.
+    pub max_verifier_cut: u32,
+    /// Seconds thawed tokens stay locked.
+    pub thawing_period: u64,
+    pub created_at: u64,
+    /// Staged by `setProvisionParameters`, active after acceptance.
+    pub max_verifier_cut_pending: u32,
+    pub thawing_period_pending: u64,
+    pub last_parameters_staged_at: u64,
+    /// Thaw requests from an older nonce were invalidated by a slash.
+    pub thawing_nonce: u64,
+}
+
+impl Provision {
+    /// Decode ABI-encoded `getProvision` output.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let s = <ProvisionState as SolType>::abi_decode(data).context("decoding Provision")?;
+        Ok(Self {
+            tokens: Grt::from_wei(s.tokens.to()),
+            tokens_thawing: Grt::from_wei(s.tokensThawing.to()),
+            shares_thawing: s.sharesThawing.to(),
+            max_verifier_cut: s.maxVerifierCut,
+            thawing_period: s.thawingPeriod,
+            created_at: s.createdAt,
+            max_verifier_cut_pending: s.maxVerifierCutPending,
+            thawing_period_pending: s.thawingPeriodPending,
+            last_parameters_staged_at: s.lastParametersStagedAt.to(),
+            thawing_nonce: s.thawingNonce.to(),
+        })
+    }
+
+    pub fn exists(&self) -> bool {
+        self.created_at != 0
+    }
+
+    /// Whether staged parameters differ from the active ones.
+    pub fn has_pending_parameters(&self) -> bool {
+        self.max_verifier_cut_pending != self.max_verifier_cut
+            || self.thawing_period_pending != self.thawing_period
+    }
+}
+
+/// Decode a `(uint256 min, uint256 max)` range.
+fn decode_range(data: &[u8]) -> Result<(U256, U256)> {
+    <(sol_data::Uint<256>, sol_data::Uint<256>) as SolType>::abi_decode(data)
+        .context("decoding range")
+}
 
 impl TestNetwork {
     // --- Stake Management (BaselineTestPlan Cycle 2) ---
@@ -24,17 +100,17 @@ impl TestNetwork {
     ///
     /// Account0 approves and stakes GRT to the indexer. In production,
     /// the indexer does this through Explorer using their own GRT.
-    /// `amount_wei` is in wei (e.g., "1000000000000000000000" for 1000 GRT).
-    pub fn stake_tokens(&self, amount_wei: &str) -> Result<()> {
+    pub fn stake_tokens(&self, amount: Grt) -> Result<()> {
+        let amount = amount.to_wei_string();
         self.cast_send(
-            &self.contracts.grt_token,
+            self.contracts.grt_token,
             "approve(address,uint256)",
-            &[&self.contracts.horizon_staking, amount_wei],
+            &[&self.contracts.horizon_staking.to_string(), &amount],
         )?;
         self.cast_send(
-            &self.contracts.horizon_staking,
+            self.contracts.horizon_staking,
             "stakeTo(address,uint256)",
-            &[&self.indexer_address, amount_wei],
+            &[&self.indexer_address.to_string(), &amount],
         )?;
         Ok(())
     }
@@ -44,25 +120,23 @@ impl TestNetwork {
     ///
     /// Only works on idle stake (not provisioned or allocated).
     /// Called as the indexer (RECEIVER_SECRET).
-    pub fn unstake_tokens(&self, amount_wei: &str) -> Result<()> {
+    pub fn unstake_tokens(&self, amount: Grt) -> Result<()> {
         self.cast_send_as_indexer(
-            &self.contracts.horizon_staking,
+            self.contracts.horizon_staking,
             "unstake(uint256)",
-            &[amount_wei],
+            &[&amount.to_wei_string()],
         )?;
         Ok(())
     }
 
     /// Get idle (unprovisioned, unallocated) stake for the indexer.
-    pub fn idle_stake(&self) -> Result<u128> {
+    pub fn idle_stake(&self) -> Result<Grt> {
         let output = self.cast_call(
-            &self.contracts.horizon_staking,
+            self.contracts.horizon_staking,
             "getIdleStake(address)(uint256)",
-            &[&self.indexer_address],
+            &[&self.indexer_address.to_string()],
         )?;
-        cast_parse_uint(&output)
-            .parse()
-            .context("parsing idle stake")
+        Grt::parse_wei(&output).context("parsing idle stake")
     }
 
     // --- Provision Management (BaselineTestPlan Cycle 3) ---
@@ -72,14 +146,14 @@ impl TestNetwork {
     ///
     /// Moves tokens from idle stake into the provision for SubgraphService.
     /// Called as the indexer (RECEIVER_SECRET).
-    pub fn provision_add(&self, amount_wei: &str) -> Result<()> {
+    pub fn provision_add(&self, amount: Grt) -> Result<()> {
         self.cast_send_as_indexer(
-            &self.contracts.horizon_staking,
+            self.contracts.horizon_staking,
             "addToProvision(address,address,uint256)",
             &[
-                &self.indexer_address,
-                &self.contracts.subgraph_service,
-                amount_wei,
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &amount.to_wei_string(),
             ],
         )?;
         Ok(())
@@ -91,14 +165,14 @@ impl TestNetwork {
     /// Starts the thawing process. Tokens remain locked until the thawing
     /// period expires, then `provision_deprovision()` completes the removal.
     /// Called as the indexer (RECEIVER_SECRET).
-    pub fn provision_thaw(&self, amount_wei: &str) -> Result<()> {
+    pub fn provision_thaw(&self, amount: Grt) -> Result<()> {
         self.cast_send_as_indexer(
-            &self.contracts.horizon_staking,
+            self.contracts.horizon_staking,
             "thaw(address,address,uint256)",
             &[
-                &self.indexer_address,
-                &self.contracts.subgraph_service,
-                amount_wei,
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &amount.to_wei_string(),
             ],
         )?;
         Ok(())
@@ -112,11 +186,11 @@ impl TestNetwork {
     /// Called as the indexer (RECEIVER_SECRET).
     pub fn provision_deprovision(&self, n_thaw_requests: u64) -> Result<()> {
         self.cast_send_as_indexer(
-            &self.contracts.horizon_staking,
+            self.contracts.horizon_staking,
             "deprovision(address,address,uint256)",
             &[
-                &self.indexer_address,
-                &self.contracts.subgraph_service,
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
                 &n_thaw_requests.to_string(),
             ],
         )?;
@@ -126,7 +200,7 @@ impl TestNetwork {
     /// Get the thawing period (seconds) for the indexer's SubgraphService provision.
     /// Queries the network subgraph for the provision's thawingPeriod field.
     pub async fn provision_thawing_period(&self) -> Result<u64> {
-        let provisions = self.query_provisions(&self.indexer_address).await?;
+        let provisions = self.query_provisions(self.indexer_address).await?;
         let provisions = provisions
             .as_array()
             .context("provisions should be an array")?;
@@ -138,4 +212,187 @@ impl TestNetwork {
             .or_else(|| first["thawingPeriod"].as_str().and_then(|s| s.parse().ok()))
             .context("thawingPeriod not found in provision")
     }
+
+    // --- Provision Parameters ---
+
+    /// The indexer's SubgraphService provision as stored by HorizonStaking,
+    /// including staged parameters.
+    pub fn provision(&self) -> Result<Provision> {
+        let output = self.cast_call(
+            self.contracts.horizon_staking,
+            "getProvision(address,address)",
+            &[
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+            ],
+        )?;
+        let data = hex::decode(output.trim()).context("decoding getProvision output")?;
+        Provision::decode(&data)
+    }
+
+    /// Stage new provision parameters via `HorizonStaking.setProvisionParameters`.
+    /// They only take effect once SubgraphService accepts them
+    /// (`accept_provision_parameters`). Called as the indexer (RECEIVER_SECRET).
+    pub fn set_provision_parameters(
+        &self,
+        max_verifier_cut: u32,
+        thawing_period: u64,
+    ) -> Result<()> {
+        self.cast_send_as_indexer(
+            self.contracts.horizon_staking,
+            "setProvisionParameters(address,address,uint32,uint64)",
+            &[
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &max_verifier_cut.to_string(),
+                &thawing_period.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Accept staged parameters via `SubgraphService.acceptProvisionPendingParameters`,
+    /// which reverts if they are outside the service's verifier cut or
+    /// thawing period range. Called as the indexer (RECEIVER_SECRET).
+    pub fn accept_provision_parameters(&self) -> Result<()> {
+        self.cast_send_as_indexer(
+            self.contracts.subgraph_service,
+            "acceptProvisionPendingParameters(address,bytes)",
+            &[&self.indexer_address.to_string(), "0x"],
+        )?;
+        Ok(())
+    }
+
+    /// `accept_provision_parameters`, returning `Ok(false)` on revert.
+    pub fn try_accept_provision_parameters(&self) -> Result<bool> {
+        self.cast_send_may_revert(
+            &self.receiver_secret,
+            self.contracts.subgraph_service,
+            "acceptProvisionPendingParameters(address,bytes)",
+            &[&self.indexer_address.to_string(), "0x"],
+        )
+    }
+
+    // --- Operators ---
+
+    /// Allow or revoke `operator` acting for the indexer on SubgraphService.
+    /// Emulates `graph indexer` operator setup.
+    pub fn set_operator(&self, operator: Address, allowed: bool) -> Result<()> {
+        self.set_operator_for(self.contracts.subgraph_service, operator, allowed)
+    }
+
+    /// Allow or revoke `operator` for the indexer's provision with `verifier`
+    /// via `HorizonStaking.setOperator`. Operators are per data service.
+    pub fn set_operator_for(
+        &self,
+        verifier: Address,
+        operator: Address,
+        allowed: bool,
+    ) -> Result<()> {
+        self.cast_send_as_indexer(
+            self.contracts.horizon_staking,
+            "setOperator(address,address,bool)",
+            &[
+                &verifier.to_string(),
+                &operator.to_string(),
+                if allowed { "true" } else { "false" },
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Whether `operator` may act for the indexer's SubgraphService provision.
+    pub fn is_operator(&self, operator: Address) -> Result<bool> {
+        self.is_authorized(self.contracts.subgraph_service, operator)
+    }
+
+    /// `HorizonStaking.isAuthorized(indexer, verifier, operator)`. The indexer
+    /// itself is always authorized.
+    pub fn is_authorized(&self, verifier: Address, operator: Address) -> Result<bool> {
+        let output = self.cast_call(
+            self.contracts.horizon_staking,
+            "isAuthorized(address,address,address)(bool)",
+            &[
+                &self.indexer_address.to_string(),
+                &verifier.to_string(),
+                &operator.to_string(),
+            ],
+        )?;
+        Ok(output.trim() == "true")
+    }
+
+    // --- SubgraphService Provision Settings ---
+
+    /// `(min, max)` provision tokens SubgraphService accepts.
+    pub fn provision_tokens_range(&self) -> Result<(Grt, Grt)> {
+        let (min, max) = self.subgraph_service_range("getProvisionTokensRange()")?;
+        Ok((Grt::from_wei(min.to()), Grt::from_wei(max.saturating_to())))
+    }
+
+    /// `(min, max)` verifier cut (PPM) SubgraphService accepts.
+    pub fn verifier_cut_range(&self) -> Result<(u32, u32)> {
+        let (min, max) = self.subgraph_service_range("getVerifierCutRange()")?;
+        Ok((min.to(), max.to()))
+    }
+
+    /// `(min, max)` thawing period (seconds) SubgraphService accepts.
+    pub fn thawing_period_range(&self) -> Result<(u64, u64)> {
+        let (min, max) = self.subgraph_service_range("getThawingPeriodRange()")?;
+        Ok((min.to(), max.saturating_to()))
+    }
+
+    fn subgraph_service_range(&self, sig: &str) -> Result<(U256, U256)> {
+        let output = self.cast_call(self.contracts.subgraph_service, sig, &[])?;
+        let data = hex::decode(output.trim()).with_context(|| format!("decoding {sig} output"))?;
+        decode_range(&data)
+    }
+
+    /// How many times its own provision an indexer may use in delegation.
+    pub fn delegation_ratio(&self) -> Result<u32> {
+        let output = self.cast_call(
+            self.contracts.subgraph_service,
+            "getDelegationRatio()(uint32)",
+            &[],
+        )?;
+        crate::cast::cast_parse_uint(&output)
+            .parse()
+            .context("parsing delegation ratio")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::SolValue;
+
+    #[test]
+    fn decodes_provision_and_ranges() {
+        let data = (
+            U256::from(5_000u64),
+            U256::from(100u64),
+            U256::from(100u64),
+            500_000u32,
+            3_600u64,
+            1_700_000_000u64,
+            250_000u32,
+            3_600u64,
+            U256::from(1_700_000_100u64),
+            U256::from(2u64),
+        )
+            .abi_encode_params();
+        let provision = Provision::decode(&data).unwrap();
+        assert!(provision.exists());
+        assert_eq!(provision.tokens, Grt::from_wei(5_000));
+        assert_eq!(provision.tokens_thawing, Grt::from_wei(100));
+        assert_eq!(provision.max_verifier_cut, 500_000);
+        assert_eq!(provision.thawing_period, 3_600);
+        assert_eq!(provision.max_verifier_cut_pending, 250_000);
+        assert_eq!(provision.last_parameters_staged_at, 1_700_000_100);
+        assert_eq!(provision.shares_thawing, 100);
+        assert_eq!(provision.thawing_nonce, 2);
+        assert!(provision.has_pending_parameters());
+
+        let range = (U256::from(1u64), U256::MAX).abi_encode_params();
+        assert_eq!(decode_range(&range).unwrap(), (U256::from(1u64), U256::MAX));
+    }
 }
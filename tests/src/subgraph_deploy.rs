@@ -0,0 +1,317 @@
+//! Fresh subgraph deployments built from a fixture.
+//!
+//! The local network indexes three deployments (network, TAP, block-oracle),
+//! all of which other tests depend on. Scenarios that need a deployment of
+//! their own — denial of a second deployment, allocations nobody else has —
+//! deploy a throwaway one:
+//!
+//!   1. `ipfs_add` the fixture schema, ABI and prebuilt mapping, then the
+//!      manifest generated by `FixtureSubgraph::manifest`
+//!   2. `subgraph_create` + `subgraph_deploy` on graph-node's admin API
+//!   3. `wait_for_deployment_synced` via the index-node status API
+//!
+//! The fixture (`fixtures/subgraph/`) watches one contract for a
+//! `Fixture(uint256)` event nothing emits, so the mapping never runs and the
+//! deployment syncs as fast as graph-node scans blocks. The manifest's
+//! description is the fixture's label: each label yields a distinct
+//! deployment ID, ready for `publish_subgraph` and `start_service`.
+//!
+//! The indexer-agent may pause deployments it has no rule for, so waiting
+//! for sync resumes the deployment first.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+use crate::polling::PollResult;
+use crate::{Address, DeploymentId, TestNetwork};
+
+/// Prebuilt from `fixtures/subgraph/mapping.wat`.
+const MAPPING: &[u8] = include_bytes!("../fixtures/subgraph/mapping.wasm");
+const SCHEMA: &str = include_str!("../fixtures/subgraph/schema.graphql");
+const ABI: &str = include_str!("../fixtures/subgraph/Fixture.abi.json");
+
+/// graph-node's chain name for the local chain (`ETHEREUM_RPC`).
+const NETWORK: &str = "hardhat";
+
+/// Multipart boundary for IPFS uploads (never occurs in fixture files).
+const BOUNDARY: &str = "local-network-tests-fixture-boundary";
+
+/// Parameters of a fixture deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureSubgraph {
+    /// Manifest description and graph-node name suffix; unique per scenario.
+    pub label: String,
+    /// Contract the data source watches.
+    pub address: Address,
+    pub start_block: u64,
+}
+
+impl FixtureSubgraph {
+    /// A fixture watching the zero address from genesis.
+    pub fn new(label: impl Into<String>) -> Self {
+        FixtureSubgraph {
+            label: label.into(),
+            address: Address::ZERO,
+            start_block: 0,
+        }
+    }
+
+    /// graph-node subgraph name: `fixture/<label>`, with characters graph-node
+    /// rejects replaced by `-`.
+    pub fn name(&self) -> String {
+        let label: String = self
+            .label
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!("fixture/{label}")
+    }
+
+    /// The manifest referencing already uploaded schema, ABI and mapping files.
+    /// JSON is valid YAML, which is what graph-node parses.
+    pub fn manifest(&self, schema_cid: &str, abi_cid: &str, mapping_cid: &str) -> Value {
+        let link = |cid: &str| json!({ "/": format!("/ipfs/{cid}") });
+        json!({
+            "specVersion": "0.0.5",
+            "description": self.label,
+            "schema": { "file": link(schema_cid) },
+            "dataSources": [{
+                "kind": "ethereum",
+                "name": "Fixture",
+                "network": NETWORK,
+                "source": {
+                    "address": self.address.to_string(),
+                    "abi": "Fixture",
+                    "startBlock": self.start_block,
+                },
+                "mapping": {
+                    "kind": "ethereum/events",
+                    "apiVersion": "0.0.7",
+                    "language": "wasm/assemblyscript",
+                    "entities": ["Fixture"],
+                    "abis": [{ "name": "Fixture", "file": link(abi_cid) }],
+                    "eventHandlers": [{
+                        "event": "Fixture(uint256)",
+                        "handler": "handleFixture",
+                    }],
+                    "file": link(mapping_cid),
+                },
+            }],
+        })
+    }
+}
+
+/// A deployment's entry in graph-node's `indexingStatuses`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentStatus {
+    pub synced: bool,
+    /// `healthy`, `unhealthy` or `failed`.
+    pub health: String,
+    #[serde(deserialize_with = "fatal_error_message")]
+    pub fatal_error: Option<String>,
+}
+
+/// Deserialize `fatalError { message }` as the message.
+fn fatal_error_message<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    struct SubgraphError {
+        message: String,
+    }
+    Ok(Option::<SubgraphError>::deserialize(deserializer)?.map(|e| e.message))
+}
+
+impl TestNetwork {
+    /// Upload the fixture files and `fixture`'s manifest to IPFS. Returns the
+    /// manifest's CID, i.e. the deployment ID.
+    pub async fn upload_fixture_subgraph(&self, fixture: &FixtureSubgraph) -> Result<DeploymentId> {
+        let schema = self.ipfs_add("schema.graphql", SCHEMA.as_bytes()).await?;
+        let abi = self.ipfs_add("Fixture.abi.json", ABI.as_bytes()).await?;
+        let mapping = self.ipfs_add("mapping.wasm", MAPPING).await?;
+        let manifest = fixture.manifest(&schema, &abi, &mapping);
+        let manifest = serde_json::to_vec_pretty(&manifest)?;
+        self.ipfs_add("subgraph.yaml", &manifest).await?.parse()
+    }
+
+    /// Upload and deploy `fixture` to graph-node under `fixture.name()`.
+    /// Returns the deployment ID without waiting for it to sync.
+    pub async fn deploy_fixture_subgraph(&self, fixture: &FixtureSubgraph) -> Result<DeploymentId> {
+        let deployment = self.upload_fixture_subgraph(fixture).await?;
+        self.deploy_subgraph(&fixture.name(), deployment).await?;
+        Ok(deployment)
+    }
+
+    /// Create `name` on graph-node if needed and deploy `deployment` to it.
+    pub async fn deploy_subgraph(&self, name: &str, deployment: DeploymentId) -> Result<()> {
+        match self
+            .graph_node_admin("subgraph_create", json!({ "name": name }))
+            .await
+        {
+            Ok(_) => {}
+            Err(e) if format!("{e:#}").contains("already exists") => {}
+            Err(e) => return Err(e),
+        }
+        self.graph_node_admin(
+            "subgraph_deploy",
+            json!({ "name": name, "ipfs_hash": deployment.to_string() }),
+        )
+        .await
+        .with_context(|| format!("deploying {deployment} as {name}"))?;
+        Ok(())
+    }
+
+    /// Resume a deployment graph-node has paused.
+    pub async fn resume_deployment(&self, deployment: DeploymentId) -> Result<()> {
+        self.graph_node_admin(
+            "subgraph_resume",
+            json!({ "deployment": deployment.to_string() }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// graph-node's indexing status of `deployment` (`None` if not deployed).
+    pub async fn deployment_status(
+        &self,
+        deployment: DeploymentId,
+    ) -> Result<Option<DeploymentStatus>> {
+        let query = format!(
+            r#"{{ indexingStatuses(subgraphs: ["{deployment}"]) {{
+                synced health fatalError {{ message }}
+            }} }}"#
+        );
+        let resp = self.graph_node_status_query(&query).await?;
+        let statuses: Vec<DeploymentStatus> =
+            serde_json::from_value(resp["data"]["indexingStatuses"].clone())
+                .context("parsing indexingStatuses")?;
+        Ok(statuses.into_iter().next())
+    }
+
+    /// Resume `deployment` and wait until graph-node reports it synced.
+    /// Fails early if the deployment fails.
+    pub async fn wait_for_deployment_synced(
+        &self,
+        deployment: DeploymentId,
+        timeout: Duration,
+    ) -> Result<DeploymentStatus> {
+        // Fails if the deployment isn't paused, which is fine
+        let _ = self.resume_deployment(deployment).await;
+        let result = self
+            .poll_until(timeout, Duration::from_secs(2), || async {
+                let status = self.deployment_status(deployment).await?;
+                Ok(status.filter(|s| s.synced || s.health == "failed"))
+            })
+            .await;
+        match result {
+            PollResult::Ready(status) if status.health == "failed" => anyhow::bail!(
+                "deployment {deployment} failed: {}",
+                status.fatal_error.unwrap_or_default()
+            ),
+            PollResult::Ready(status) => Ok(status),
+            PollResult::TimedOut => {
+                anyhow::bail!("deployment {deployment} not synced within {timeout:?}")
+            }
+        }
+    }
+
+    /// Add a file to IPFS (CIDv0, pinned). Returns its CID.
+    pub async fn ipfs_add(&self, file_name: &str, content: &[u8]) -> Result<String> {
+        let mut body = format!(
+            "--{BOUNDARY}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(content);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+        let url = format!("{}/api/v0/add?pin=true&cid-version=0", self.ipfs_url);
+        let resp = reqwest::Client::new()
+            .post(&url)
+            .header(
+                "content-type",
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("POST {url}"))?;
+        let status = resp.status();
+        let text = resp.text().await.context("reading IPFS response")?;
+        if !status.is_success() {
+            anyhow::bail!("IPFS add of {file_name} failed ({status}): {text}");
+        }
+        let json: Value = serde_json::from_str(&text)
+            .with_context(|| format!("parsing IPFS add response: {text}"))?;
+        json["Hash"]
+            .as_str()
+            .map(str::to_string)
+            .with_context(|| format!("no Hash in IPFS add response: {text}"))
+    }
+
+    /// Call a graph-node admin JSON-RPC method. Returns its `result`.
+    async fn graph_node_admin(&self, method: &str, params: Value) -> Result<Value> {
+        let resp = reqwest::Client::new()
+            .post(&self.graph_node_admin_url)
+            .json(&json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 }))
+            .send()
+            .await
+            .with_context(|| format!("graph-node {method}"))?;
+        let json: Value = resp
+            .json()
+            .await
+            .with_context(|| format!("parsing graph-node {method} response"))?;
+        if let Some(error) = json.get("error") {
+            anyhow::bail!("graph-node {method} failed: {error}");
+        }
+        Ok(json["result"].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_links_uploaded_files() {
+        let fixture = FixtureSubgraph {
+            start_block: 42,
+            ..FixtureSubgraph::new("denial second/deployment")
+        };
+        let manifest = fixture.manifest("QmSchema", "QmAbi", "QmMapping");
+        assert_eq!(manifest["description"], "denial second/deployment");
+        assert_eq!(manifest["schema"]["file"]["/"], "/ipfs/QmSchema");
+        let data_source = &manifest["dataSources"][0];
+        assert_eq!(data_source["network"], NETWORK);
+        assert_eq!(data_source["source"]["startBlock"], 42);
+        assert_eq!(data_source["mapping"]["file"]["/"], "/ipfs/QmMapping");
+        assert_eq!(
+            data_source["mapping"]["abis"][0]["file"]["/"],
+            "/ipfs/QmAbi"
+        );
+        assert_eq!(fixture.name(), "fixture/denial-second-deployment");
+    }
+
+    #[test]
+    fn mapping_exports_handler_and_runtime() {
+        assert!(MAPPING.starts_with(b"\0asm"));
+        for export in ["memory", "allocate", "id_of_type", "handleFixture"] {
+            assert!(
+                MAPPING
+                    .windows(export.len())
+                    .any(|w| w == export.as_bytes()),
+                "mapping.wasm lacks {export}"
+            );
+        }
+    }
+}
@@ -0,0 +1,610 @@
+//! Direct indexer-service queries paid with locally signed TAP V2 receipts.
+//!
+//! The gateway normally builds and signs a receipt for every query. Here the
+//! crate does it itself so receipt validation can be exercised without
+//! touching gateway config:
+//!
+//!   - receipt: GraphTally `Receipt` (collection = allocation ID, payer,
+//!     data service = SubgraphService, service provider = indexer)
+//!   - signature: EIP-712 in the GraphTallyCollector domain, by the gateway's
+//!     authorized signer (ACCOUNT1) on behalf of the payer (ACCOUNT0)
+//!   - transport: `Tap-Receipt` header, base64 protobuf (`tap_aggregator`
+//!     `v2.SignedReceipt`), which is how indexer-service detects V2 receipts
+//!
+//! indexer-service replies with `{"graphQLResponse": "<json>", "attestation"}`
+//! on success, and an error status with a message when a receipt check fails.
+//!
+//! Receipts indexer-service accepted (from the gateway or from here) are read
+//! back from its `tap_horizon_receipts` table, to compare paid fees with the
+//! indexer's cost model.
+//!
+//! Payer side, for payers other than the gateway's:
+//!   - `authorize_tap_signer_as` → `GraphTallyCollector.authorizeSigner` with
+//!     the signer's proof; `tap_signer_authorized` → `isAuthorized`
+//!   - `escrow_deposit_as` / `escrow_thaw_as` → `PaymentsEscrow.deposit`/`thaw`
+//!     towards the local indexer; `escrow_account` reads balance and thaw
+
+use alloy_primitives::{Signature, U256, keccak256};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{Eip712Domain, SolStruct, eip712_domain, sol};
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use prost::Message;
+use serde_json::Value;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::attestation::Attestation;
+use crate::cast::cast_parse_uint;
+use crate::{Address, B256, DeploymentId, Grt, TestNetwork};
+
+/// Request header carrying the signed receipt.
+pub const TAP_RECEIPT_HEADER: &str = "Tap-Receipt";
+
+sol! {
+    /// GraphTally (TAP V2) receipt, as verified by GraphTallyCollector.
+    struct Receipt {
+        bytes32 collection_id;
+        address payer;
+        address data_service;
+        address service_provider;
+        uint64 timestamp_ns;
+        uint64 nonce;
+        uint128 value;
+    }
+}
+
+/// An unsigned TAP V2 receipt. Fields are public so tests can tamper with them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TapReceipt {
+    /// The allocation ID, left-padded to 32 bytes.
+    pub collection_id: B256,
+    pub payer: Address,
+    pub data_service: Address,
+    pub service_provider: Address,
+    pub timestamp_ns: u64,
+    pub nonce: u64,
+    pub value: Grt,
+}
+
+impl TapReceipt {
+    /// Move the timestamp back by `age` (e.g. to make it expired).
+    pub fn aged(mut self, age: Duration) -> Self {
+        self.timestamp_ns = self.timestamp_ns.saturating_sub(age.as_nanos() as u64);
+        self
+    }
+
+    fn message(&self) -> Receipt {
+        Receipt {
+            collection_id: self.collection_id,
+            payer: self.payer,
+            data_service: self.data_service,
+            service_provider: self.service_provider,
+            timestamp_ns: self.timestamp_ns,
+            nonce: self.nonce,
+            value: self.value.wei(),
+        }
+    }
+
+    /// EIP-712 digest of the receipt.
+    pub fn signing_hash(&self, domain: &Eip712Domain) -> B256 {
+        self.message().eip712_signing_hash(domain)
+    }
+
+    /// Sign with a hex private key.
+    pub fn sign(&self, domain: &Eip712Domain, secret: &str) -> Result<SignedTapReceipt> {
+        let signer: PrivateKeySigner = secret.parse().context("parsing receipt signer key")?;
+        let signature = signer
+            .sign_hash_sync(&self.signing_hash(domain))
+            .context("signing receipt")?;
+        Ok(SignedTapReceipt {
+            receipt: *self,
+            signature,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedTapReceipt {
+    pub receipt: TapReceipt,
+    pub signature: Signature,
+}
+
+impl SignedTapReceipt {
+    /// Recover the signing key's address.
+    pub fn recover_signer(&self, domain: &Eip712Domain) -> Result<Address> {
+        self.signature
+            .recover_address_from_prehash(&self.receipt.signing_hash(domain))
+            .context("recovering receipt signer")
+    }
+
+    /// The `Tap-Receipt` header value: base64 protobuf `v2.SignedReceipt`.
+    pub fn header_value(&self) -> String {
+        let r = &self.receipt;
+        let value = r.value.wei();
+        let proto = proto::SignedReceipt {
+            message: Some(proto::ReceiptMessage {
+                collection_id: r.collection_id.to_vec(),
+                payer: r.payer.to_vec(),
+                data_service: r.data_service.to_vec(),
+                service_provider: r.service_provider.to_vec(),
+                timestamp_ns: r.timestamp_ns,
+                nonce: r.nonce,
+                value: Some(proto::Uint128 {
+                    high: (value >> 64) as u64,
+                    low: value as u64,
+                }),
+            }),
+            signature: self.signature.as_bytes().to_vec(),
+        };
+        BASE64_STANDARD.encode(proto.encode_to_vec())
+    }
+}
+
+/// Wire format of the receipt header (tap_aggregator `v2.proto`, `uint128.proto`).
+mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Uint128 {
+        #[prost(uint64, tag = "1")]
+        pub high: u64,
+        #[prost(uint64, tag = "2")]
+        pub low: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ReceiptMessage {
+        #[prost(bytes = "vec", tag = "1")]
+        pub collection_id: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub payer: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub data_service: Vec<u8>,
+        #[prost(bytes = "vec", tag = "4")]
+        pub service_provider: Vec<u8>,
+        #[prost(uint64, tag = "5")]
+        pub timestamp_ns: u64,
+        #[prost(uint64, tag = "6")]
+        pub nonce: u64,
+        #[prost(message, optional, tag = "7")]
+        pub value: Option<Uint128>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SignedReceipt {
+        #[prost(message, optional, tag = "1")]
+        pub message: Option<ReceiptMessage>,
+        #[prost(bytes = "vec", tag = "2")]
+        pub signature: Vec<u8>,
+    }
+}
+
+/// The GraphTallyCollector EIP-712 domain.
+pub fn tap_domain(chain_id: u64, graph_tally_collector: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: "GraphTallyCollector",
+        version: "1",
+        chain_id: chain_id,
+        verifying_contract: graph_tally_collector,
+    }
+}
+
+/// Proof that `signer` agrees to sign for `authorizer`, as checked by
+/// `Authorizable.authorizeSigner`: an EIP-191 signature over
+/// `keccak256(chainId, collector, "authorizeSignerProof", deadline, authorizer)`.
+pub fn signer_proof(
+    signer: &PrivateKeySigner,
+    chain_id: u64,
+    collector: Address,
+    deadline: u64,
+    authorizer: Address,
+) -> Result<Signature> {
+    let mut message = Vec::new();
+    message.extend_from_slice(&U256::from(chain_id).to_be_bytes::<32>());
+    message.extend_from_slice(collector.as_slice());
+    message.extend_from_slice(b"authorizeSignerProof");
+    message.extend_from_slice(&U256::from(deadline).to_be_bytes::<32>());
+    message.extend_from_slice(authorizer.as_slice());
+    signer
+        .sign_message_sync(keccak256(message).as_slice())
+        .context("signing authorization proof")
+}
+
+/// A payer's `PaymentsEscrow` account towards one collector and receiver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscrowAccount {
+    /// Total deposited, including thawing tokens.
+    pub balance: Grt,
+    pub tokens_thawing: Grt,
+    pub thaw_end_timestamp: u64,
+}
+
+impl EscrowAccount {
+    /// Tokens backing receipts: the balance minus thawing tokens.
+    pub fn available(&self) -> Grt {
+        self.balance.saturating_sub(self.tokens_thawing)
+    }
+}
+
+/// A parsed indexer-service query response.
+#[derive(Debug, Clone)]
+pub struct IndexerServiceResponse {
+    pub status: u16,
+    /// The exact request body sent (the attested request).
+    pub request: String,
+    /// The GraphQL response string (the attested response).
+    pub graphql_response: Option<String>,
+    pub attestation: Option<Attestation>,
+    /// Error message for rejected queries (e.g. a failed receipt check).
+    pub error: Option<String>,
+}
+
+impl IndexerServiceResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status) && self.graphql_response.is_some()
+    }
+}
+
+/// A receipt as stored by indexer-service after validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredReceipt {
+    pub id: u64,
+    pub signer: Address,
+    pub collection_id: B256,
+    pub payer: Address,
+    pub timestamp_ns: u64,
+    pub nonce: u64,
+    pub value: Grt,
+}
+
+impl StoredReceipt {
+    /// The allocation the receipt pays (the collection ID's low 20 bytes).
+    pub fn allocation(&self) -> Address {
+        Address::from_word(self.collection_id)
+    }
+
+    /// Parse a `psql -At -F,` row of `RECEIPT_COLUMNS`.
+    fn parse_row(row: &str) -> Result<Self> {
+        let cols: Vec<&str> = row.split(',').map(str::trim).collect();
+        anyhow::ensure!(cols.len() == 7, "unexpected receipt row: {row}");
+        let hex = |s: &str| format!("0x{}", s.trim_start_matches("0x"));
+        Ok(Self {
+            id: cols[0].parse().context("receipt id")?,
+            signer: crate::ids::parse_address(&hex(cols[1]))?,
+            collection_id: hex(cols[2]).parse().context("receipt collection_id")?,
+            payer: crate::ids::parse_address(&hex(cols[3]))?,
+            timestamp_ns: cols[4].parse().context("receipt timestamp_ns")?,
+            nonce: cols[5].parse().context("receipt nonce")?,
+            value: Grt::parse_wei(cols[6]).context("receipt value")?,
+        })
+    }
+}
+
+const RECEIPT_COLUMNS: &str =
+    "id, signer_address, collection_id, payer, timestamp_ns, nonce, value";
+
+/// Run a query against indexer-service's database (`indexer_components_1` in
+/// the `postgres` container). Returns unaligned rows, comma-separated.
+fn indexer_db_query(sql: &str) -> Result<Vec<String>> {
+    let output = Command::new("docker")
+        .args(["exec", "postgres", "psql", "-U", "postgres", "-d"])
+        .args(["indexer_components_1", "-At", "-F,", "-c", sql])
+        .output()
+        .context("running psql via docker exec")?;
+    if !output.status.success() {
+        anyhow::bail!("psql failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+impl TestNetwork {
+    /// Highest stored TAP V2 receipt ID (0 if none). Use as a watermark for
+    /// `tap_receipts_since`.
+    pub fn latest_tap_receipt_id(&self) -> Result<u64> {
+        let rows = indexer_db_query("SELECT COALESCE(MAX(id), 0) FROM tap_horizon_receipts")?;
+        rows.first()
+            .context("no result for MAX(id)")?
+            .trim()
+            .parse()
+            .context("parsing receipt id")
+    }
+
+    /// TAP V2 receipts stored by indexer-service after `id`, oldest first.
+    ///
+    /// tap-agent may delete receipts once they are covered by a RAV, so read
+    /// them soon after the queries that produced them.
+    pub fn tap_receipts_since(&self, id: u64) -> Result<Vec<StoredReceipt>> {
+        let sql = format!(
+            "SELECT {RECEIPT_COLUMNS} FROM tap_horizon_receipts WHERE id > {id} ORDER BY id"
+        );
+        indexer_db_query(&sql)?
+            .iter()
+            .map(|row| StoredReceipt::parse_row(row))
+            .collect()
+    }
+
+    /// The TAP V2 EIP-712 domain of the local network's GraphTallyCollector.
+    pub fn tap_domain(&self) -> Eip712Domain {
+        tap_domain(self.chain_id, self.contracts.graph_tally_collector)
+    }
+
+    /// The gateway's payer (ACCOUNT0), whose escrow pays for queries.
+    pub fn tap_payer(&self) -> Result<Address> {
+        let payer: PrivateKeySigner = self
+            .account0_secret
+            .parse()
+            .context("parsing ACCOUNT0_SECRET")?;
+        Ok(payer.address())
+    }
+
+    /// A receipt like the gateway would send: payer ACCOUNT0 paying the local
+    /// indexer through SubgraphService, timestamped now, random nonce.
+    pub fn tap_receipt(&self, allocation: Address, value: Grt) -> Result<TapReceipt> {
+        let timestamp_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("system clock before UNIX epoch")?
+            .as_nanos() as u64;
+        Ok(TapReceipt {
+            collection_id: allocation.into_word(),
+            payer: self.tap_payer()?,
+            data_service: self.contracts.subgraph_service,
+            service_provider: self.indexer_address,
+            timestamp_ns,
+            nonce: rand::random(),
+            value,
+        })
+    }
+
+    /// Sign a receipt with the gateway's authorized signer (ACCOUNT1).
+    pub fn sign_tap_receipt(&self, receipt: &TapReceipt) -> Result<SignedTapReceipt> {
+        receipt.sign(&self.tap_domain(), &self.account1_secret)
+    }
+
+    /// Whether `signer` may sign receipts for `payer` (GraphTallyCollector).
+    pub fn tap_signer_authorized(&self, payer: Address, signer: Address) -> Result<bool> {
+        let output = self.cast_call(
+            self.contracts.graph_tally_collector,
+            "isAuthorized(address,address)(bool)",
+            &[&payer.to_string(), &signer.to_string()],
+        )?;
+        Ok(output.trim() == "true")
+    }
+
+    /// Authorize `signer` to sign receipts for the payer `payer_secret`.
+    pub fn authorize_tap_signer_as(
+        &self,
+        payer_secret: &str,
+        signer: &PrivateKeySigner,
+    ) -> Result<()> {
+        let payer: PrivateKeySigner = payer_secret.parse().context("parsing payer key")?;
+        let collector = self.contracts.graph_tally_collector;
+        let deadline = self.get_block_timestamp()? + 3600;
+        let proof = signer_proof(signer, self.chain_id, collector, deadline, payer.address())?;
+        self.cast_send_as(
+            payer_secret,
+            collector,
+            "authorizeSigner(address,uint256,bytes)",
+            &[
+                &signer.address().to_string(),
+                &deadline.to_string(),
+                &alloy_primitives::hex::encode_prefixed(proof.as_bytes()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// `payer`'s escrow account for the local indexer through
+    /// GraphTallyCollector (`PaymentsEscrow.escrowAccounts`).
+    pub fn escrow_account(&self, payer: Address) -> Result<EscrowAccount> {
+        let output = self.cast_call(
+            self.contracts.payments_escrow,
+            "escrowAccounts(address,address,address)(uint256,uint256,uint256)",
+            &[
+                &payer.to_string(),
+                &self.contracts.graph_tally_collector.to_string(),
+                &self.indexer_address.to_string(),
+            ],
+        )?;
+        let fields: Vec<&str> = output.lines().map(cast_parse_uint).collect();
+        anyhow::ensure!(
+            fields.len() == 3,
+            "unexpected escrowAccounts output: {output}"
+        );
+        Ok(EscrowAccount {
+            balance: Grt::parse_wei(fields[0]).context("parsing escrow balance")?,
+            tokens_thawing: Grt::parse_wei(fields[1]).context("parsing tokensThawing")?,
+            thaw_end_timestamp: fields[2].parse().context("parsing thawEndTimestamp")?,
+        })
+    }
+
+    /// Deposit `tokens` into the payer's escrow for the local indexer
+    /// (approve + `PaymentsEscrow.deposit`). The payer must hold the GRT.
+    pub fn escrow_deposit_as(&self, payer_secret: &str, tokens: Grt) -> Result<()> {
+        let escrow = self.contracts.payments_escrow;
+        self.cast_send_as(
+            payer_secret,
+            self.contracts.grt_token,
+            "approve(address,uint256)",
+            &[&escrow.to_string(), &tokens.to_wei_string()],
+        )?;
+        self.cast_send_as(
+            payer_secret,
+            escrow,
+            "deposit(address,address,uint256)",
+            &[
+                &self.contracts.graph_tally_collector.to_string(),
+                &self.indexer_address.to_string(),
+                &tokens.to_wei_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Start thawing `tokens` of the payer's escrow for the local indexer,
+    /// replacing any thaw in progress. Thawing tokens no longer back receipts.
+    pub fn escrow_thaw_as(&self, payer_secret: &str, tokens: Grt) -> Result<()> {
+        self.cast_send_as(
+            payer_secret,
+            self.contracts.payments_escrow,
+            "thaw(address,address,uint256)",
+            &[
+                &self.contracts.graph_tally_collector.to_string(),
+                &self.indexer_address.to_string(),
+                &tokens.to_wei_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Query a deployment on indexer-service directly, paying with `receipt`.
+    pub async fn query_indexer_service(
+        &self,
+        deployment: DeploymentId,
+        receipt: &SignedTapReceipt,
+        query: &str,
+    ) -> Result<IndexerServiceResponse> {
+        let url = format!("{}/subgraphs/id/{deployment}", self.indexer_service_url);
+        let request = serde_json::json!({ "query": query }).to_string();
+        let resp = reqwest::Client::new()
+            .post(&url)
+            .header("content-type", "application/json")
+            .header(TAP_RECEIPT_HEADER, receipt.header_value())
+            .body(request.clone())
+            .send()
+            .await
+            .context("sending indexer-service query")?;
+        let status = resp.status().as_u16();
+        let body = resp
+            .text()
+            .await
+            .context("reading indexer-service response")?;
+        let json: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+
+        let graphql_response = json["graphQLResponse"].as_str().map(str::to_string);
+        let attestation = match graphql_response {
+            Some(_) => Attestation::from_indexer_response(&json)?,
+            None => None,
+        };
+        let error = if graphql_response.is_some() && (200..300).contains(&status) {
+            None
+        } else {
+            Some(
+                json["message"]
+                    .as_str()
+                    .or(json["error"].as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("HTTP {status}: {}", body.trim())),
+            )
+        };
+        Ok(IndexerServiceResponse {
+            status,
+            request,
+            graphql_response,
+            attestation,
+            error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt() -> TapReceipt {
+        TapReceipt {
+            collection_id: Address::repeat_byte(0xaa).into_word(),
+            payer: Address::repeat_byte(0x01),
+            data_service: Address::repeat_byte(0x02),
+            service_provider: Address::repeat_byte(0x03),
+            timestamp_ns: 1_700_000_000_000_000_000,
+            nonce: 7,
+            value: Grt::from_wei(u64::MAX as u128 + 5),
+        }
+    }
+
+    #[test]
+    fn signs_and_recovers() {
+        let signer = PrivateKeySigner::random();
+        let secret = signer.to_bytes().to_string();
+        let domain = tap_domain(1337, Address::repeat_byte(0x42));
+        let signed = receipt().sign(&domain, &secret).unwrap();
+        assert_eq!(signed.recover_signer(&domain).unwrap(), signer.address());
+
+        let other = tap_domain(1337, Address::repeat_byte(0x43));
+        assert_ne!(signed.recover_signer(&other).unwrap(), signer.address());
+    }
+
+    #[test]
+    fn signer_proof_binds_authorizer() {
+        let signer = PrivateKeySigner::random();
+        let collector = Address::repeat_byte(0x42);
+        let authorizer = Address::repeat_byte(0x01);
+        let proof = signer_proof(&signer, 1337, collector, 1_000, authorizer).unwrap();
+
+        // abi.encodePacked(uint256, address, string, uint256, address)
+        let mut message = B256::left_padding_from(&1337u64.to_be_bytes()).to_vec();
+        message.extend_from_slice(collector.as_slice());
+        message.extend_from_slice(b"authorizeSignerProof");
+        message.extend_from_slice(B256::left_padding_from(&1_000u64.to_be_bytes()).as_slice());
+        message.extend_from_slice(authorizer.as_slice());
+        let recovered = proof
+            .recover_address_from_msg(keccak256(&message).as_slice())
+            .unwrap();
+        assert_eq!(recovered, signer.address());
+
+        let other = signer_proof(&signer, 1337, collector, 1_000, Address::ZERO).unwrap();
+        assert_ne!(other, proof);
+    }
+
+    #[test]
+    fn header_round_trips_protobuf() {
+        let signer = PrivateKeySigner::random();
+        let domain = tap_domain(1337, Address::repeat_byte(0x42));
+        let signed = receipt()
+            .sign(&domain, &signer.to_bytes().to_string())
+            .unwrap();
+        let bytes = BASE64_STANDARD.decode(signed.header_value()).unwrap();
+        let decoded = proto::SignedReceipt::decode(bytes.as_slice()).unwrap();
+        let message = decoded.message.unwrap();
+        assert_eq!(message.collection_id.len(), 32);
+        assert_eq!(
+            &message.collection_id[12..],
+            Address::repeat_byte(0xaa).as_slice()
+        );
+        assert_eq!(message.payer, Address::repeat_byte(0x01).to_vec());
+        assert_eq!(message.nonce, 7);
+        let value = message.value.unwrap();
+        assert_eq!(
+            ((value.high as u128) << 64) | value.low as u128,
+            u64::MAX as u128 + 5
+        );
+        assert_eq!(decoded.signature.len(), 65);
+    }
+
+    #[test]
+    fn aged_moves_timestamp_back() {
+        let r = receipt().aged(Duration::from_secs(3600));
+        assert_eq!(r.timestamp_ns, receipt().timestamp_ns - 3_600_000_000_000);
+    }
+
+    #[test]
+    fn parses_stored_receipt_row() {
+        let row = format!(
+            "12,{},{},{},1700000000000000000,42,20000000000000",
+            "f39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            "000000000000000000000000aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "70997970c51812dc3a010c7d01b50e0d17dc79c8",
+        );
+        let r = StoredReceipt::parse_row(&row).unwrap();
+        assert_eq!(r.id, 12);
+        assert_eq!(r.allocation(), Address::repeat_byte(0xaa));
+        assert_eq!(r.nonce, 42);
+        assert_eq!(r.value, Grt::from_wei(20_000_000_000_000));
+        assert!(StoredReceipt::parse_row("1,2,3").is_err());
+    }
+}
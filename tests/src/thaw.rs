@@ -0,0 +1,252 @@
+//! Thaw request inspection for provisions and delegations.
+//!
+//! `thaw` and `undelegate` each create a thaw request in a per-owner linked
+//! list on HorizonStaking. `deprovision`/`withdrawDelegated` fulfil requests
+//! from the head of the list and stop at the first one still thawing, so the
+//! number of requests worth passing is the number of *leading* requests
+//! whose `thawingUntil` has passed.
+//!
+//!   - `thaw_requests` → walk `getThawRequestList` + `getThawRequest`
+//!   - `ready_thaw_requests` → how many requests a fulfilment call would process now
+//!   - `provision_deprovision_ready` → `deprovision` exactly the ready requests
+//!   - `query_thaw_requests` → `ThawRequest` entities from the network subgraph
+//!
+//! Chain time can be moved to a request's expiry with `advance_time_to`.
+
+use alloy_primitives::{U256, hex};
+use alloy_sol_types::{SolType, sol};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::{Address, B256, Grt, TestNetwork};
+
+sol! {
+    /// `LinkedList.List` as returned by `getThawRequestList`.
+    struct ThawRequestListState {
+        bytes32 head;
+        bytes32 tail;
+        uint256 nonce;
+        uint256 count;
+    }
+
+    /// `IHorizonStakingTypes.ThawRequest` as returned by `getThawRequest`.
+    struct ThawRequestState {
+        uint256 shares;
+        uint64 thawingUntil;
+        bytes32 next;
+        uint256 thawingNonce;
+    }
+}
+
+/// `IHorizonStakingTypes.ThawRequestType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThawRequestType {
+    Provision = 0,
+    Delegation = 1,
+}
+
+/// One pending thaw request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThawRequest {
+    pub id: B256,
+    pub shares: u128,
+    /// Current value of `shares` in the pool's thawing tokens.
+    pub tokens: Grt,
+    pub thawing_until: u64,
+    /// Requests from an older nonce than the pool's were invalidated by a slash.
+    pub thawing_nonce: u64,
+}
+
+impl ThawRequest {
+    pub fn is_thawed(&self, now: u64) -> bool {
+        self.thawing_until <= now
+    }
+}
+
+/// Number of leading requests (in list order) that are thawed at `now`: what
+/// a fulfilment call processes before it stops.
+pub fn ready_thaw_requests(requests: &[ThawRequest], now: u64) -> usize {
+    requests.iter().take_while(|r| r.is_thawed(now)).count()
+}
+
+/// Value of `shares` out of `shares_thawing` backed by `tokens_thawing`.
+fn thawing_tokens(shares: u128, tokens_thawing: Grt, shares_thawing: u128) -> Grt {
+    if shares_thawing == 0 {
+        return Grt::ZERO;
+    }
+    let tokens = U256::from(shares) * U256::from(tokens_thawing.wei()) / U256::from(shares_thawing);
+    Grt::from_wei(tokens.to())
+}
+
+/// `ThawRequest` entity from the network subgraph.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphThawRequest {
+    pub id: B256,
+    #[serde(rename = "type")]
+    pub request_type: String,
+    #[serde(deserialize_with = "crate::graphql::big_int")]
+    pub shares: u128,
+    #[serde(deserialize_with = "crate::graphql::big_int")]
+    pub thawing_until: u64,
+    pub fulfilled: bool,
+}
+
+impl TestNetwork {
+    /// Pending thaw requests of `owner` against the indexer's SubgraphService
+    /// provision (`Provision`: owner is the indexer; `Delegation`: the delegator),
+    /// in fulfilment order.
+    pub fn thaw_requests(
+        &self,
+        request_type: ThawRequestType,
+        owner: Address,
+    ) -> Result<Vec<ThawRequest>> {
+        let kind = (request_type as u8).to_string();
+        let output = self.cast_call(
+            self.contracts.horizon_staking,
+            "getThawRequestList(uint8,address,address,address)",
+            &[
+                &kind,
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &owner.to_string(),
+            ],
+        )?;
+        let data = hex::decode(output.trim()).context("decoding getThawRequestList output")?;
+        let list = <ThawRequestListState as SolType>::abi_decode(&data)
+            .context("decoding thaw request list")?;
+
+        let (tokens_thawing, shares_thawing) = match request_type {
+            ThawRequestType::Provision => {
+                let provision = self.provision()?;
+                (provision.tokens_thawing, provision.shares_thawing)
+            }
+            ThawRequestType::Delegation => {
+                let pool = self.delegation_pool(self.indexer_address)?;
+                (pool.tokens_thawing, pool.shares_thawing)
+            }
+        };
+
+        let count: usize = list.count.to();
+        let mut requests = Vec::with_capacity(count);
+        let mut id = list.head;
+        while !id.is_zero() && requests.len() < count {
+            let output = self.cast_call(
+                self.contracts.horizon_staking,
+                "getThawRequest(uint8,bytes32)",
+                &[&kind, &id.to_string()],
+            )?;
+            let data = hex::decode(output.trim()).context("decoding getThawRequest output")?;
+            let state = <ThawRequestState as SolType>::abi_decode(&data)
+                .with_context(|| format!("decoding thaw request {id}"))?;
+            let shares: u128 = state.shares.to();
+            requests.push(ThawRequest {
+                id,
+                shares,
+                tokens: thawing_tokens(shares, tokens_thawing, shares_thawing),
+                thawing_until: state.thawingUntil,
+                thawing_nonce: state.thawingNonce.to(),
+            });
+            id = state.next;
+        }
+        Ok(requests)
+    }
+
+    /// The indexer's own provision thaw requests.
+    pub fn provision_thaw_requests(&self) -> Result<Vec<ThawRequest>> {
+        self.thaw_requests(ThawRequestType::Provision, self.indexer_address)
+    }
+
+    /// Tokens a fulfilment call would release now (`getThawedTokens`).
+    pub fn thawed_tokens(&self, request_type: ThawRequestType, owner: Address) -> Result<Grt> {
+        let output = self.cast_call(
+            self.contracts.horizon_staking,
+            "getThawedTokens(uint8,address,address,address)(uint256)",
+            &[
+                &(request_type as u8).to_string(),
+                &self.indexer_address.to_string(),
+                &self.contracts.subgraph_service.to_string(),
+                &owner.to_string(),
+            ],
+        )?;
+        Grt::parse_wei(crate::cast::cast_parse_uint(&output)).context("parsing thawed tokens")
+    }
+
+    /// Deprovision exactly the provision thaw requests that are thawed at
+    /// the current chain time. Returns how many were fulfilled (0 skips the
+    /// call, since `deprovision(.., 0)` would mean "all").
+    pub fn provision_deprovision_ready(&self) -> Result<usize> {
+        let now = self.get_block_timestamp()?;
+        let ready = ready_thaw_requests(&self.provision_thaw_requests()?, now);
+        if ready > 0 {
+            self.provision_deprovision(ready as u64)?;
+        }
+        Ok(ready)
+    }
+
+    /// Unfulfilled thaw requests for `indexer` as indexed by the network
+    /// subgraph, oldest first.
+    pub async fn query_thaw_requests(&self, indexer: Address) -> Result<Vec<SubgraphThawRequest>> {
+        let query = format!(
+            r#"{{ thawRequests(
+                where: {{ indexer: "{indexer:#x}", fulfilled: false }},
+                orderBy: thawingUntil, orderDirection: asc
+            ) {{
+                id type shares thawingUntil fulfilled
+            }} }}"#
+        );
+        let resp = self.subgraph_query(&query).await?;
+        serde_json::from_value(resp["data"]["thawRequests"].clone()).context("parsing thawRequests")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::SolValue;
+
+    fn request(thawing_until: u64) -> ThawRequest {
+        ThawRequest {
+            id: B256::repeat_byte(thawing_until as u8),
+            shares: 1,
+            tokens: Grt::ONE,
+            thawing_until,
+            thawing_nonce: 0,
+        }
+    }
+
+    #[test]
+    fn ready_counts_leading_thawed_requests() {
+        let requests = [request(100), request(200), request(150)];
+        assert_eq!(ready_thaw_requests(&requests, 99), 0);
+        assert_eq!(ready_thaw_requests(&requests, 100), 1);
+        // The third is thawed but sits behind a thawing one
+        assert_eq!(ready_thaw_requests(&requests, 199), 1);
+        assert_eq!(ready_thaw_requests(&requests, 200), 3);
+        assert_eq!(ready_thaw_requests(&[], 0), 0);
+    }
+
+    #[test]
+    fn thawing_tokens_are_pro_rata() {
+        assert_eq!(
+            thawing_tokens(250, Grt::from_grt(1_000), 1_000),
+            Grt::from_grt(250)
+        );
+        // Slashed pool: shares are worth less than their original tokens
+        assert_eq!(
+            thawing_tokens(500, Grt::from_wei(900), 1_000),
+            Grt::from_wei(450)
+        );
+        assert_eq!(thawing_tokens(1, Grt::ONE, 0), Grt::ZERO);
+    }
+
+    #[test]
+    fn decodes_thaw_request() {
+        let next = B256::repeat_byte(0xab);
+        let data = (U256::from(7u64), 1_700_000_000u64, next, U256::from(1u64)).abi_encode_params();
+        let state = <ThawRequestState as SolType>::abi_decode(&data).unwrap();
+        assert_eq!(state.shares, U256::from(7u64));
+        assert_eq!(state.thawingUntil, 1_700_000_000);
+        assert_eq!(state.next, next);
+    }
+}
@@ -0,0 +1,168 @@
+//! Action Queue Tests (BaselineTestPlan Cycle 4.3)
+//!
+//! Emulates `graph indexer actions queue/get/approve/cancel/delete/execute`
+//! through the indexer management API.
+//!
+//! Mapping to BaselineTestPlan:
+//!   - `action_queue_crud` → Cycle 4.3 (queue, approve, cancel, delete)
+//!   - `batched_reallocation_via_actions` → Cycle 4.3 (create via actions queue,
+//!     multi-allocation batch execution)
+//!   - `failed_action_reports_reason` → Cycle 4.3 (failure reasons surfaced)
+
+use anyhow::{Context, Result};
+use local_network_tests::fixtures::ALLOCATION_AMOUNT;
+use local_network_tests::management::{
+    ActionFilter, ActionInput, ActionStatus, ActionType, IndexerAllocation,
+};
+use local_network_tests::{B256, DeploymentId, TestNetwork};
+use serial_test::serial;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+const EXECUTE_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Queue → approve → cancel → delete without executing anything.
+#[tokio::test]
+#[serial]
+async fn action_queue_crud() -> Result<()> {
+    let net = net()?;
+    let deployment = DeploymentId::new(B256::repeat_byte(0x42));
+
+    eprintln!("=== Action Queue CRUD ===");
+
+    let queued = net
+        .queue_actions(&[ActionInput::allocate(deployment, ALLOCATION_AMOUNT).reason("crud")])
+        .await?;
+    let action = queued.first().context("queueActions returned nothing")?;
+    eprintln!("  Queued action {} ({:?})", action.id, action.action_type);
+    assert_eq!(action.status, ActionStatus::Queued);
+    assert_eq!(action.action_type, ActionType::Allocate);
+    assert_eq!(action.deployment_id, Some(deployment));
+    assert_eq!(action.amount, Some(ALLOCATION_AMOUNT));
+
+    let listed = net
+        .actions(&ActionFilter {
+            status: Some(ActionStatus::Queued),
+            ..Default::default()
+        })
+        .await?;
+    assert!(listed.iter().any(|a| a.id == action.id), "should be listed");
+
+    let approved = net.approve_actions(&[action.id]).await?;
+    assert_eq!(approved[0].status, ActionStatus::Approved);
+
+    // Cancel before the agent's loop picks it up
+    let canceled = net.cancel_actions(&[action.id]).await?;
+    assert_eq!(canceled[0].status, ActionStatus::Canceled);
+
+    assert_eq!(net.delete_actions(&[action.id]).await?, 1);
+    assert_eq!(net.action(action.id).await?, None);
+
+    Ok(())
+}
+
+/// BaselineTestPlan 4.3: close two allocations and open replacements in one
+/// approved batch, then watch every action to success.
+#[tokio::test]
+#[serial]
+async fn batched_reallocation_via_actions() -> Result<()> {
+    let net = net()?;
+
+    let allocs = net.get_allocations().await?;
+    let active: Vec<&IndexerAllocation> = allocs.iter().filter(|a| a.is_active()).take(2).collect();
+    anyhow::ensure!(active.len() == 2, "need two active allocations");
+    eprintln!("=== Batched Reallocation via Actions ===");
+
+    let block_number = net.subgraph_block_number().await?;
+    let mut inputs = Vec::new();
+    for alloc in &active {
+        eprintln!("  {} on {}", alloc.id, alloc.subgraph_deployment);
+        inputs.push(
+            ActionInput::unallocate(alloc.subgraph_deployment, alloc.id).forced_at(block_number),
+        );
+        inputs.push(ActionInput::allocate(
+            alloc.subgraph_deployment,
+            ALLOCATION_AMOUNT,
+        ));
+    }
+    let queued = net.queue_actions(&inputs).await?;
+    let ids: Vec<u64> = queued.iter().map(|a| a.id).collect();
+    eprintln!("  Queued actions {ids:?}");
+    assert_eq!(queued.len(), 4);
+
+    net.approve_actions(&ids).await?;
+    let results = net.execute_approved_actions().await?;
+    eprintln!("  Executed {} actions in one batch", results.len());
+
+    for id in &ids {
+        let watch = net.watch_action(*id, EXECUTE_TIMEOUT).await?;
+        eprintln!(
+            "  Action {id} {:?}: {:?} tx={:?} failure={:?}",
+            watch.action.action_type,
+            watch.transitions,
+            watch.action.transaction,
+            watch.action.failure_reason
+        );
+        assert_eq!(
+            watch.action.status,
+            ActionStatus::Success,
+            "action {id} failed: {:?}",
+            watch.action.failure_reason
+        );
+    }
+
+    let after = net.get_allocations().await?;
+    for old in &active {
+        assert!(
+            after.iter().any(|a| a.is_active()
+                && a.subgraph_deployment == old.subgraph_deployment
+                && a.id != old.id),
+            "{} should have a new active allocation",
+            old.subgraph_deployment
+        );
+    }
+
+    Ok(())
+}
+
+/// An allocate action for a deployment that was never published fails, and
+/// the reason is reported (either at queue time or on the action itself).
+#[tokio::test]
+#[serial]
+async fn failed_action_reports_reason() -> Result<()> {
+    let net = net()?;
+    let deployment = DeploymentId::new(B256::repeat_byte(0x43));
+
+    eprintln!("=== Failed Action Reason ===");
+
+    let queued = match net
+        .queue_actions(&[ActionInput::allocate(deployment, ALLOCATION_AMOUNT)])
+        .await
+    {
+        Ok(queued) => queued,
+        Err(e) => {
+            eprintln!("  Rejected at queue time: {e:#}");
+            return Ok(());
+        }
+    };
+    let id = queued[0].id;
+    net.approve_actions(&[id]).await?;
+    net.execute_approved_actions().await?;
+
+    let watch = net.watch_action(id, EXECUTE_TIMEOUT).await?;
+    eprintln!(
+        "  Action {id}: {:?}, failure: {:?}",
+        watch.transitions, watch.action.failure_reason
+    );
+    assert_eq!(watch.action.status, ActionStatus::Failed);
+    assert!(
+        watch.action.failure_reason.is_some(),
+        "failed action should carry a failure reason"
+    );
+    net.delete_actions(&[id]).await?;
+
+    Ok(())
+}
@@ -6,14 +6,17 @@
 //! Mapping to BaselineTestPlan:
 //!   - `close_and_recreate_allocation` → Cycle 4.2 (create) + 5.2 (close + rewards)
 //!   - `close_allocation_collects_rewards` → Cycle 5.2 (agent-mediated close with reward assertion)
+//!   - `reallocate_allocation` → Cycle 4.5 (close + re-open in one transaction)
 //!   - `gateway_query_serving` → Cycle 5.1 (query serving through gateway)
 //!
-//! The management API mutations (`createAllocation`, `closeAllocation`) emulate
-//! what `graph indexer allocations create/close` does. The close path internally
+//! The management API mutations (`createAllocation`, `closeAllocation`,
+//! `reallocateAllocation`) emulate what `graph indexer allocations
+//! create/close/reallocate` does. The close path internally
 //! triggers a multicall: collect(IndexingRewards) + stopService.
 
 use anyhow::{Context, Result};
 use local_network_tests::TestNetwork;
+use local_network_tests::fixtures::ALLOCATION_AMOUNT;
 use serial_test::serial;
 
 fn net() -> Result<TestNetwork> {
@@ -30,16 +33,12 @@ async fn close_and_recreate_allocation() -> Result<()> {
 
     // Pick an existing active allocation to close
     let allocs = net.get_allocations().await?;
-    let allocs = allocs.as_array().context("expected allocation array")?;
     let active = allocs
         .iter()
-        .find(|a| a["closedAtEpoch"].is_null())
+        .find(|a| a.is_active())
         .context("no active allocation found to close")?;
-    let alloc_id = active["id"].as_str().context("allocation missing id")?;
-    let deployment = active["subgraphDeployment"]
-        .as_str()
-        .context("allocation missing deployment")?
-        .to_string();
+    let alloc_id = active.id;
+    let deployment = active.subgraph_deployment;
 
     // Advance epochs so allocation is old enough to close
     eprintln!("--- Advancing 2 epochs ---");
@@ -49,22 +48,17 @@ async fn close_and_recreate_allocation() -> Result<()> {
     // Close the existing allocation (emulates: graph indexer allocations close)
     eprintln!("--- Closing allocation {alloc_id} ---");
     let close_result = net.close_allocation(alloc_id).await?;
-    let rewards = close_result["indexingRewards"].as_str().unwrap_or("0");
-    eprintln!("  indexingRewards: {rewards}");
+    eprintln!("  indexingRewards: {} GRT", close_result.indexing_rewards);
 
     assert_eq!(
-        close_result["allocation"].as_str().unwrap_or(""),
-        alloc_id,
+        close_result.allocation, alloc_id,
         "Closed allocation ID should match"
     );
 
     // Create a new allocation for the same deployment (emulates: graph indexer allocations create)
     eprintln!("--- Creating new allocation for {deployment} ---");
-    let amount = "0.01"; // GRT (management API takes GRT, not wei)
-    let create_result = net.create_allocation(&deployment, amount).await?;
-    let new_alloc_id = create_result["allocation"]
-        .as_str()
-        .context("createAllocation should return allocation ID")?;
+    let create_result = net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
+    let new_alloc_id = create_result.allocation;
     eprintln!("  Created allocation: {new_alloc_id}");
 
     assert!(
@@ -72,8 +66,7 @@ async fn close_and_recreate_allocation() -> Result<()> {
         "Allocation ID should be non-empty"
     );
     assert_eq!(
-        create_result["deployment"].as_str().unwrap_or(""),
-        deployment,
+        create_result.deployment, deployment,
         "Deployment should match"
     );
 
@@ -83,18 +76,16 @@ async fn close_and_recreate_allocation() -> Result<()> {
 
     eprintln!("--- Closing new allocation {new_alloc_id} ---");
     let close_result = net.close_allocation(new_alloc_id).await?;
-    let rewards = close_result["indexingRewards"].as_str().unwrap_or("0");
-    eprintln!("  indexingRewards: {rewards}");
+    eprintln!("  indexingRewards: {} GRT", close_result.indexing_rewards);
 
     assert_eq!(
-        close_result["allocation"].as_str().unwrap_or(""),
-        new_alloc_id,
+        close_result.allocation, new_alloc_id,
         "Closed allocation ID should match"
     );
 
     // Re-create the allocation to restore network state
     eprintln!("--- Restoring allocation for {deployment} ---");
-    net.create_allocation(&deployment, "0.01").await?;
+    net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
 
     Ok(())
 }
@@ -111,19 +102,12 @@ async fn close_allocation_collects_rewards() -> Result<()> {
 
     // Find an active allocation
     let allocs = net.get_allocations().await?;
-    let allocs = allocs.as_array().context("expected allocation array")?;
     let active = allocs
         .iter()
-        .find(|a| a["closedAtEpoch"].is_null())
+        .find(|a| a.is_active())
         .context("no active allocation found")?;
-    let alloc_id = active["id"]
-        .as_str()
-        .context("allocation missing id")?
-        .to_string();
-    let deployment = active["subgraphDeployment"]
-        .as_str()
-        .context("allocation missing deployment")?
-        .to_string();
+    let alloc_id = active.id;
+    let deployment = active.subgraph_deployment;
 
     eprintln!("=== Close-collects-rewards test (BaselineTestPlan 5.2) ===");
     eprintln!("  Allocation: {alloc_id}");
@@ -131,13 +115,10 @@ async fn close_allocation_collects_rewards() -> Result<()> {
 
     // Close and recreate so we have a fresh allocation with known epoch boundaries
     net.advance_epochs(2).await?;
-    net.close_allocation(&alloc_id).await?;
+    net.close_allocation(alloc_id).await?;
 
-    let result = net.create_allocation(&deployment, "0.01").await?;
-    let fresh_alloc = result["allocation"]
-        .as_str()
-        .context("expected allocation ID")?
-        .to_string();
+    let result = net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
+    let fresh_alloc = result.allocation;
     eprintln!("  Fresh allocation: {fresh_alloc}");
 
     // Advance epochs so rewards accumulate
@@ -145,28 +126,27 @@ async fn close_allocation_collects_rewards() -> Result<()> {
 
     // Ensure indexer is eligible (eligibility may expire during epoch advancement)
     if net.contracts.reo.is_some() {
-        net.reo_renew_indexer(&net.indexer_address)?;
+        net.reo_renew_indexer(net.indexer_address)?;
         assert!(
-            net.reo_is_eligible(&net.indexer_address)?,
+            net.reo_is_eligible(net.indexer_address)?,
             "Indexer must be eligible before close"
         );
     }
 
     // Close via agent — this triggers collect(IndexingRewards) + stopService multicall
     eprintln!("  Closing allocation via agent...");
-    let close_result = net.close_allocation(&fresh_alloc).await?;
-    let rewards_str = close_result["indexingRewards"].as_str().unwrap_or("0");
-    let rewards: f64 = rewards_str.parse().unwrap_or(0.0);
-    eprintln!("  indexingRewards: {rewards_str} ({rewards:.2} GRT)");
+    let close_result = net.close_allocation(fresh_alloc).await?;
+    let rewards = close_result.indexing_rewards;
+    eprintln!("  indexingRewards: {rewards} GRT");
 
     assert!(
-        rewards > 0.0,
+        !rewards.is_zero(),
         "Agent-mediated close should collect non-zero rewards. \
-         Got indexingRewards={rewards_str}"
+         Got indexingRewards={rewards}"
     );
 
     // Verify closed allocation in subgraph
-    let alloc_data = net.query_allocation(&fresh_alloc).await?;
+    let alloc_data = net.query_allocation(fresh_alloc).await?;
     assert_eq!(
         alloc_data["status"].as_str().unwrap_or(""),
         "Closed",
@@ -175,12 +155,72 @@ async fn close_allocation_collects_rewards() -> Result<()> {
 
     // Restore allocation
     net.advance_epochs(2).await?;
-    net.create_allocation(&deployment, "0.01").await?;
+    net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
     eprintln!("  Restored allocation for {deployment}");
 
     Ok(())
 }
 
+/// BaselineTestPlan 4.5: Reallocate an allocation with a different amount.
+///
+/// Emulates `graph indexer allocations reallocate`. The old allocation is
+/// closed (collecting its rewards) and a new one opened on the same deployment.
+#[tokio::test]
+#[serial]
+async fn reallocate_allocation() -> Result<()> {
+    let net = net()?;
+
+    let allocs = net.get_allocations().await?;
+    let active = allocs
+        .iter()
+        .find(|a| a.is_active())
+        .context("no active allocation found to reallocate")?;
+    let alloc_id = active.id;
+    let deployment = active.subgraph_deployment;
+    let new_amount = ALLOCATION_AMOUNT + ALLOCATION_AMOUNT;
+
+    eprintln!("=== Reallocate test (BaselineTestPlan 4.5) ===");
+    eprintln!("  Allocation: {alloc_id} ({} GRT)", active.allocated_tokens);
+    eprintln!("  Deployment: {deployment}");
+
+    net.advance_epochs(2).await?;
+    if net.contracts.reo.is_some() {
+        net.reo_renew_indexer(net.indexer_address)?;
+    }
+
+    let result = net.reallocate_allocation(alloc_id, new_amount).await?;
+    eprintln!("  Closed:  {}", result.closed_allocation);
+    eprintln!(
+        "  Created: {} ({} GRT)",
+        result.created_allocation, result.created_allocation_stake
+    );
+    eprintln!(
+        "  indexingRewardsCollected: {} GRT",
+        result.indexing_rewards_collected
+    );
+
+    assert_eq!(result.closed_allocation, alloc_id);
+    assert_ne!(result.created_allocation, alloc_id);
+    assert_eq!(result.created_allocation_stake, new_amount);
+
+    let old = net.subgraph_service_allocation(alloc_id)?;
+    assert!(!old.is_open(), "old allocation should be closed on-chain");
+    let new = net.subgraph_service_allocation(result.created_allocation)?;
+    assert!(new.is_open(), "new allocation should be open on-chain");
+    assert_eq!(new.deployment, deployment);
+    assert_eq!(new.tokens, new_amount);
+
+    let after = net.get_allocations().await?;
+    assert!(
+        after
+            .iter()
+            .any(|a| a.is_active() && a.id == result.created_allocation),
+        "agent should report the new allocation as active"
+    );
+
+    Ok(())
+}
+
 /// BaselineTestPlan 5.1: Send test queries through gateway.
 ///
 /// Emulates the `query_test.sh` script from the test plan.
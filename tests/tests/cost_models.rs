@@ -0,0 +1,219 @@
+//! Cost Model Tests (BaselineTestPlan Cycle 5.1, query pricing)
+//!
+//! Emulates `graph indexer cost get/set/delete` through the indexer
+//! management API, then checks the price end to end: indexer-service serves
+//! the model, the gateway prices queries with it, and the TAP receipts stored
+//! by indexer-service carry the fee.
+//!
+//!   - `cost_model_crud` → set/get/list/delete a deployment model
+//!   - `invalid_cost_model_observable` → malformed Agora (observational)
+//!   - `gateway_applies_cost_model` → receipt values follow a flat model, and
+//!     an indexer priced above the gateway budget is not selected
+//!
+//! Only flat `default => <price>;` models are used: that is the part of Agora
+//! the gateway prices with.
+
+use anyhow::{Context, Result};
+use local_network_tests::management::{CostModel, agora_flat};
+use local_network_tests::polling::PollResult;
+use local_network_tests::{Address, B256, DeploymentId, Grt, TestNetwork};
+use serial_test::serial;
+use std::collections::HashSet;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+const QUERY: &str = r#"{ _meta { block { number } } }"#;
+
+/// The gateway's per-query budget (`query_fees_target`, 40e-6 GRT at an
+/// exchange rate of 1).
+const GATEWAY_BUDGET: Grt = Grt::from_wei(40_000_000_000_000);
+
+/// Flat price under the budget (20e-6 GRT).
+const PRICE: Grt = Grt::from_wei(20_000_000_000_000);
+
+/// The gateway refreshes indexer cost models periodically.
+const APPLY_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Put back the deployment's own model, or remove the one the test set.
+async fn restore_cost_model(
+    net: &TestNetwork,
+    deployment: DeploymentId,
+    original: Option<CostModel>,
+) -> Result<()> {
+    let global = net.global_cost_model().await?;
+    match original {
+        Some(model) if model.model != global.and_then(|g| g.model) => {
+            net.set_cost_model(&CostModel {
+                deployment: deployment.to_string(),
+                ..model
+            })
+            .await?;
+        }
+        _ => {
+            net.delete_cost_models(&[deployment]).await?;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn cost_model_crud() -> Result<()> {
+    let net = net()?;
+    let deployment = DeploymentId::new(B256::repeat_byte(0x42));
+    let id = deployment.to_string();
+
+    eprintln!("=== Cost Model CRUD ({id}) ===");
+
+    let model = agora_flat(PRICE);
+    let set = net
+        .set_cost_model(&CostModel::deployment(deployment, &model))
+        .await?;
+    assert_eq!(set.deployment, id);
+    assert_eq!(set.flat_price(), Some(PRICE));
+
+    let fetched = net.cost_model(deployment).await?;
+    assert_eq!(fetched.as_ref(), Some(&set));
+    let listed = net.cost_models(Some(&[deployment])).await?;
+    assert!(listed.contains(&set), "model should be listed: {listed:?}");
+    eprintln!(
+        "  {} cost models configured",
+        net.cost_models(None).await?.len()
+    );
+
+    // Replace
+    let doubled = agora_flat(PRICE + PRICE);
+    let updated = net
+        .set_cost_model(&CostModel::deployment(deployment, &doubled))
+        .await?;
+    assert_eq!(updated.flat_price(), Some(PRICE + PRICE));
+
+    assert_eq!(net.delete_cost_models(&[deployment]).await?, 1);
+
+    // Without its own model the deployment falls back to the global one
+    let global = net.global_cost_model().await?;
+    let after = net.cost_model(deployment).await?;
+    eprintln!(
+        "  Global model: {:?}",
+        global.as_ref().and_then(|m| m.model.as_deref())
+    );
+    assert_eq!(
+        after.and_then(|m| m.model),
+        global.and_then(|m| m.model),
+        "deleted model should fall back to global"
+    );
+
+    Ok(())
+}
+
+/// Documents whether the agent validates Agora when a model is set; gateway
+/// pricing of a malformed model is undefined, so it is deleted either way.
+#[tokio::test]
+#[serial]
+async fn invalid_cost_model_observable() -> Result<()> {
+    let net = net()?;
+    let deployment = DeploymentId::new(B256::repeat_byte(0x43));
+
+    match net
+        .set_cost_model(&CostModel::deployment(deployment, "default => ;"))
+        .await
+    {
+        Ok(model) => {
+            eprintln!("  NOTE: malformed model accepted by the agent: {model:?}");
+            assert_eq!(model.flat_price(), None);
+        }
+        Err(e) => eprintln!("  Rejected: {e:#}"),
+    }
+    net.delete_cost_models(&[deployment]).await?;
+
+    Ok(())
+}
+
+/// Receipts stored by indexer-service after a flat model is applied pay at
+/// least the model price and no more than the gateway budget; a price above
+/// the budget makes the gateway stop selecting the indexer.
+#[tokio::test]
+#[serial]
+async fn gateway_applies_cost_model() -> Result<()> {
+    let net = net()?;
+    let deployment = net.gateway_deployment().await?;
+    let original = net.cost_model(deployment).await?;
+    let allocations: HashSet<Address> = net
+        .get_allocations()
+        .await?
+        .into_iter()
+        .filter(|a| a.is_active() && a.subgraph_deployment == deployment)
+        .map(|a| a.id)
+        .collect();
+
+    eprintln!("=== Gateway Applies Cost Model ({deployment}) ===");
+    eprintln!(
+        "  Original model: {:?}",
+        original.as_ref().and_then(|m| m.model.as_deref())
+    );
+
+    // --- Flat price under the budget ---
+    net.set_cost_model(&CostModel::deployment(deployment, &agora_flat(PRICE)))
+        .await?;
+    let result = net
+        .poll_until(APPLY_TIMEOUT, Duration::from_secs(10), || async {
+            let watermark = net.latest_tap_receipt_id()?;
+            for _ in 0..5 {
+                net.gateway_query(QUERY).await?;
+            }
+            let values: Vec<Grt> = net
+                .tap_receipts_since(watermark)?
+                .into_iter()
+                .filter(|r| allocations.contains(&r.allocation()))
+                .map(|r| r.value)
+                .collect();
+            eprintln!("  Receipt values: {values:?}");
+            Ok((!values.is_empty() && values.iter().all(|v| *v >= PRICE)).then_some(values))
+        })
+        .await;
+    let values = match result {
+        PollResult::Ready(values) => values,
+        PollResult::TimedOut => {
+            restore_cost_model(&net, deployment, original).await?;
+            anyhow::bail!("receipts did not reach {PRICE} GRT within {APPLY_TIMEOUT:?}");
+        }
+    };
+    eprintln!(
+        "  Model price {PRICE} GRT applied to {} receipts",
+        values.len()
+    );
+    for value in &values {
+        assert!(
+            *value <= GATEWAY_BUDGET,
+            "receipt value {value} exceeds the gateway budget {GATEWAY_BUDGET}"
+        );
+    }
+
+    // --- Price above the budget: indexer not selected ---
+    let expensive = agora_flat(Grt::ONE);
+    net.set_cost_model(&CostModel::deployment(deployment, &expensive))
+        .await?;
+    let result = net
+        .poll_until(APPLY_TIMEOUT, Duration::from_secs(10), || async {
+            let resp = net.gateway_query(QUERY).await?;
+            Ok((!resp.is_success()).then_some(resp))
+        })
+        .await;
+    restore_cost_model(&net, deployment, original).await?;
+    let resp = match result {
+        PollResult::Ready(resp) => resp,
+        PollResult::TimedOut => {
+            anyhow::bail!("gateway still served queries priced at 1 GRT after {APPLY_TIMEOUT:?}")
+        }
+    };
+    eprintln!(
+        "  Over-budget query: HTTP {}, {:?}",
+        resp.status,
+        resp.gateway_error.context("expected a gateway error")?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Curation Tests (L2Curation and GNS signal)
+//!
+//! A dedicated curator (hardhat account #4, funded with GRT by account0)
+//! mints and burns signal directly on a deployment and through the test
+//! subgraph's GNS entry; the network subgraph must follow the on-chain pool.
+//!
+//!   - `curation_pools_match_subgraph` → every signalled deployment vs `SubgraphDeployment`
+//!   - `mint_and_burn_deployment_signal` → tax on mint, pool restored after burn
+//!   - `gns_name_signal_round_trip` → `mintSignal`/`burnSignal` on the test subgraph
+//!
+//! Each mutating test burns everything it minted.
+
+use anyhow::{Context, Result};
+use local_network_tests::curation::tokens_after_tax;
+use local_network_tests::fixtures::{ACCOUNT4, INDEX_TIMEOUT};
+use local_network_tests::{Address, DeploymentId, Grt, TestNetwork};
+use serial_test::serial;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Curator, funded with GRT per test.
+const CURATOR_KEY: &str = ACCOUNT4.key;
+const CURATOR: Address = ACCOUNT4.address;
+
+const SIGNAL: Grt = Grt::from_grt(100);
+
+/// Pool share rounding when burning signal back into tokens.
+const ROUNDING_WEI: u128 = 1_000;
+
+async fn signalled_deployment(net: &TestNetwork) -> Result<DeploymentId> {
+    let deployments = net.query_deployments_with_signal().await?;
+    let first = deployments
+        .as_array()
+        .and_then(|d| d.first())
+        .context("no deployment with signal found")?;
+    serde_json::from_value(first["id"].clone()).context("parsing deployment id")
+}
+
+#[tokio::test]
+async fn curation_pools_match_subgraph() -> Result<()> {
+    let net = net()?;
+    let deployments = net.query_deployments_with_signal().await?;
+    let deployments = deployments
+        .as_array()
+        .context("expected deployment array")?;
+    eprintln!("  Global signal: {} GRT", net.total_curation_tokens()?);
+
+    for d in deployments {
+        let id: DeploymentId =
+            serde_json::from_value(d["id"].clone()).context("parsing deployment id")?;
+        let pool = net.curation_pool(id)?;
+        let indexed = net.wait_for_deployment_signal(id, INDEX_TIMEOUT).await?;
+        eprintln!("  {id}: {} GRT, {} signal", pool.tokens, pool.signal);
+        assert_eq!(indexed.signal_amount, pool.signal);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn mint_and_burn_deployment_signal() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Mint → Burn Deployment Signal ===");
+
+    let deployment = signalled_deployment(&net).await?;
+    let tax = net.curation_tax()?;
+    let pool_before = net.curation_pool(deployment)?;
+
+    net.transfer_grt(CURATOR, SIGNAL)?;
+    let signal = net.mint_signal_as(CURATOR_KEY, deployment, SIGNAL)?;
+    let pool = net.curation_pool(deployment)?;
+    let deposited = pool.tokens - pool_before.tokens;
+    eprintln!("  Minted {signal} signal for {SIGNAL} GRT ({deposited} GRT after {tax} PPM tax)");
+    assert_eq!(deposited, tokens_after_tax(SIGNAL, tax));
+    assert_eq!(pool.signal, pool_before.signal + signal);
+    assert_eq!(net.curator_signal(CURATOR, deployment)?, signal);
+
+    let indexed = net
+        .wait_for_deployment_signal(deployment, INDEX_TIMEOUT)
+        .await?;
+    assert_eq!(indexed.signalled_tokens, pool.tokens);
+
+    let returned = net.burn_signal_as(CURATOR_KEY, deployment, signal)?;
+    let pool_after = net.curation_pool(deployment)?;
+    eprintln!("  Burned for {returned} GRT");
+    assert_eq!(net.curator_signal(CURATOR, deployment)?, 0);
+    assert!(deposited.wei().abs_diff(returned.wei()) <= ROUNDING_WEI);
+    assert!(pool_after.tokens.wei().abs_diff(pool_before.tokens.wei()) <= ROUNDING_WEI);
+
+    net.wait_for_deployment_signal(deployment, INDEX_TIMEOUT)
+        .await?;
+
+    Ok(())
+}
+
+/// Name signal on the test subgraph is deposited into its current version's
+/// curation pool.
+#[tokio::test]
+#[serial]
+async fn gns_name_signal_round_trip() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== GNS Name Signal ===");
+
+    let subgraph = net.subgraph_id;
+    let tax = net.curation_tax()?;
+    let global_before = net.total_curation_tokens()?;
+    let name_signal_before = net.gns_subgraph_signal(subgraph)?;
+
+    net.transfer_grt(CURATOR, SIGNAL)?;
+    let name_signal = net.gns_mint_signal_as(CURATOR_KEY, subgraph, SIGNAL)?;
+    let curated = net.total_curation_tokens()? - global_before;
+    eprintln!("  Minted {name_signal} name signal on {subgraph}, {curated} GRT curated");
+    assert!(name_signal > 0);
+    assert_eq!(
+        net.gns_subgraph_signal(subgraph)?,
+        name_signal_before + name_signal
+    );
+    assert_eq!(curated, tokens_after_tax(SIGNAL, tax));
+
+    let returned = net.gns_burn_signal_as(CURATOR_KEY, subgraph, name_signal)?;
+    eprintln!("  Burned for {returned} GRT");
+    assert_eq!(net.gns_curator_signal(subgraph, CURATOR)?, 0);
+    assert!(curated.wei().abs_diff(returned.wei()) <= ROUNDING_WEI);
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+//! Delegation Tests (Explorer delegate/undelegate, Horizon delegation pools)
+//!
+//! A dedicated delegator (hardhat account #8, funded with GRT by account0)
+//! delegates to the indexer's SubgraphService provision. On-chain pool and
+//! delegator state is checked against the share math and the network subgraph.
+//!
+//!   - `delegation_pool_matches_subgraph` → on-chain pool vs provision entity
+//!   - `delegate_undelegate_withdraw` → delegate, thaw, withdraw back to the wallet
+//!   - `redelegate_thawed_tokens` → thawed delegation re-delegated without leaving staking
+//!   - `delegators_share_indexing_rewards` → IndexingRewards fee cut splits collected rewards
+//!
+//! Each mutating test undelegates and withdraws what it delegated.
+
+use anyhow::{Context, Result};
+use local_network_tests::allocation::AllocationKey;
+use local_network_tests::delegation::PaymentType;
+use local_network_tests::fixtures::{ACCOUNT8, ALLOCATION_AMOUNT, INDEX_TIMEOUT};
+use local_network_tests::polling::PollResult;
+use local_network_tests::{Address, Grt, TestNetwork};
+use serial_test::serial;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Delegator, funded with GRT per test.
+const DELEGATOR_KEY: &str = ACCOUNT8.key;
+const DELEGATOR: Address = ACCOUNT8.address;
+
+/// Above HorizonStaking's 1 GRT minimum delegation.
+const DELEGATION: Grt = Grt::from_grt(100);
+
+/// Fund the delegator and delegate, returning the shares received.
+fn fund_and_delegate(net: &TestNetwork, tokens: Grt) -> Result<u128> {
+    net.transfer_grt(DELEGATOR, tokens)?;
+    let before = net.delegation_shares(net.indexer_address, DELEGATOR)?;
+    net.delegate_as(DELEGATOR_KEY, tokens)?;
+    let after = net.delegation_shares(net.indexer_address, DELEGATOR)?;
+    Ok(after - before)
+}
+
+/// Undelegate everything the delegator holds, wait out the thawing period
+/// and withdraw to the delegator's wallet.
+async fn undelegate_all(net: &TestNetwork) -> Result<()> {
+    let shares = net.delegation_shares(net.indexer_address, DELEGATOR)?;
+    if shares > 0 {
+        net.undelegate_as(DELEGATOR_KEY, shares)?;
+    }
+    net.advance_time(net.provision_thawing_period().await? + 60)
+        .await?;
+    net.withdraw_delegated_as(DELEGATOR_KEY, 0)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn delegation_pool_matches_subgraph() -> Result<()> {
+    let net = net()?;
+    let result = net
+        .poll_until(INDEX_TIMEOUT, Duration::from_secs(2), || async {
+            let onchain = net.delegation_pool(net.indexer_address)?;
+            let indexed = net
+                .query_delegation_pool(net.indexer_address)
+                .await?
+                .context("no SubgraphService provision in the network subgraph")?;
+            eprintln!("  On-chain: {onchain:?}");
+            eprintln!("  Subgraph: {indexed:?}");
+            Ok((indexed.delegated_tokens == onchain.tokens
+                && indexed.delegator_shares == onchain.shares
+                && indexed.delegated_thawing_tokens == onchain.tokens_thawing)
+                .then_some(()))
+        })
+        .await;
+    assert!(
+        matches!(result, PollResult::Ready(())),
+        "subgraph delegation pool should match on-chain state"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn delegate_undelegate_withdraw() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Delegate → Undelegate → Withdraw ===");
+
+    // --- Delegate ---
+    let pool_before = net.delegation_pool(net.indexer_address)?;
+    let expected_shares = pool_before.shares_for(DELEGATION);
+    let shares = fund_and_delegate(&net, DELEGATION)?;
+    let pool = net.delegation_pool(net.indexer_address)?;
+    eprintln!("  Delegated {DELEGATION} GRT for {shares} shares");
+    assert_eq!(shares, expected_shares);
+    assert_eq!(pool.tokens, pool_before.tokens + DELEGATION);
+    assert_eq!(pool.shares, pool_before.shares + shares);
+
+    let result = net
+        .poll_until(INDEX_TIMEOUT, Duration::from_secs(2), || async {
+            net.mine_blocks(1).await?;
+            let delegator = net.query_delegator(DELEGATOR).await?;
+            Ok(delegator.filter(|d| {
+                d.stake_with(net.indexer_address)
+                    .is_some_and(|s| s.share_amount >= shares)
+            }))
+        })
+        .await;
+    match result {
+        PollResult::Ready(delegator) => eprintln!(
+            "  Subgraph delegator: {} GRT staked",
+            delegator.total_staked_tokens
+        ),
+        PollResult::TimedOut => anyhow::bail!("delegation not indexed within {INDEX_TIMEOUT:?}"),
+    }
+
+    // --- Undelegate: tokens start thawing ---
+    let held = net.delegation_shares(net.indexer_address, DELEGATOR)?;
+    let expected_tokens = pool.tokens_for(held);
+    net.undelegate_as(DELEGATOR_KEY, held)?;
+    let thawing = net.delegation_pool(net.indexer_address)?;
+    eprintln!(
+        "  Undelegated {held} shares, {} GRT thawing",
+        thawing.tokens_thawing
+    );
+    assert_eq!(net.delegation_shares(net.indexer_address, DELEGATOR)?, 0);
+    assert_eq!(
+        thawing.tokens_thawing,
+        pool.tokens_thawing + expected_tokens
+    );
+
+    // --- Withdraw after the thawing period ---
+    let balance_before = net.grt_balance_of(DELEGATOR)?;
+    let thawing_period = net.provision_thawing_period().await?;
+    net.advance_time(thawing_period + 60).await?;
+    net.withdraw_delegated_as(DELEGATOR_KEY, 0)?;
+    let withdrawn = net.grt_balance_of(DELEGATOR)? - balance_before;
+    eprintln!("  Withdrew {withdrawn} GRT after {thawing_period}s");
+    assert_eq!(withdrawn, expected_tokens);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn redelegate_thawed_tokens() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Redelegate ===");
+
+    let shares = fund_and_delegate(&net, DELEGATION)?;
+    net.undelegate_as(DELEGATOR_KEY, shares)?;
+    net.advance_time(net.provision_thawing_period().await? + 60)
+        .await?;
+
+    let balance_before = net.grt_balance_of(DELEGATOR)?;
+    net.redelegate_as(DELEGATOR_KEY, net.indexer_address, 0)?;
+    let redelegated = net.delegation_shares(net.indexer_address, DELEGATOR)?;
+    eprintln!("  Redelegated into {redelegated} shares");
+    assert!(redelegated > 0, "thawed tokens should be delegated again");
+    assert_eq!(
+        net.grt_balance_of(DELEGATOR)?,
+        balance_before,
+        "redelegated tokens should not pass through the wallet"
+    );
+
+    undelegate_all(&net).await
+}
+
+/// With a 50% IndexingRewards fee cut, the delegation pool receives half of
+/// the rewards collected for a fresh allocation; the rest is restaked to the
+/// indexer's provision.
+#[tokio::test]
+#[serial]
+async fn delegators_share_indexing_rewards() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Delegators Share Indexing Rewards ===");
+
+    let original_cut = net.delegation_fee_cut(net.indexer_address, PaymentType::IndexingRewards)?;
+    let fee_cut = 500_000;
+    net.set_delegation_fee_cut(PaymentType::IndexingRewards, fee_cut)?;
+    assert_eq!(
+        net.delegation_fee_cut(net.indexer_address, PaymentType::IndexingRewards)?,
+        fee_cut
+    );
+    fund_and_delegate(&net, DELEGATION)?;
+
+    let deployment = net
+        .get_allocations()
+        .await?
+        .into_iter()
+        .find(|a| a.is_active())
+        .context("no active allocation found")?
+        .subgraph_deployment;
+    let key = AllocationKey::random();
+    net.start_service(deployment, ALLOCATION_AMOUNT, &key)?;
+    net.advance_epochs(2).await?;
+    if net.contracts.reo.is_some() {
+        net.reo_renew_indexer(net.indexer_address)?;
+    }
+
+    let pool_before = net.delegation_pool(net.indexer_address)?;
+    let stake_before = net.staked_tokens()?;
+    net.collect_indexing_rewards(key.id())?;
+    let delegators = net.delegation_pool(net.indexer_address)?.tokens - pool_before.tokens;
+    let indexer = net.staked_tokens()? - stake_before;
+    let total = delegators + indexer;
+    eprintln!("  Rewards: {total} GRT (delegators {delegators}, indexer {indexer})");
+
+    net.stop_service(key.id())?;
+    net.set_delegation_fee_cut(PaymentType::IndexingRewards, original_cut)?;
+    undelegate_all(&net).await?;
+
+    assert!(!total.is_zero(), "collect should distribute rewards");
+    let expected = total.mul_ppm(fee_cut);
+    assert!(
+        delegators.wei().abs_diff(expected.wei()) <= 1,
+        "delegators should receive {fee_cut} PPM of {total}: expected {expected}, got {delegators}"
+    );
+
+    Ok(())
+}
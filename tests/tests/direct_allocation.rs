@@ -0,0 +1,165 @@
+//! Agent-Free Allocation Tests (BaselineTestPlan Cycle 4.2, contract level)
+//!
+//! Opens and closes allocations with direct `SubgraphService.startService` /
+//! `stopService` calls, using allocation keys and EIP-712 allocation-ID
+//! proofs generated by the test crate instead of indexer-agent.
+//!
+//!   - `allocation_proof_matches_contract` → proof digest matches `encodeAllocationProof`
+//!   - `start_and_stop_service_as_indexer` → open/close, allocation ID reuse rejected
+//!   - `invalid_allocation_proof_is_rejected` → proof for another indexer / wrong key
+//!   - `operator_manages_allocation` → `setOperator`, then open/close as the operator
+
+use anyhow::{Context, Result};
+use local_network_tests::allocation::AllocationKey;
+use local_network_tests::fixtures::{ACCOUNT7, ALLOCATION_AMOUNT};
+use local_network_tests::{Address, DeploymentId, TestNetwork};
+use serial_test::serial;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Operator for the indexer.
+const OPERATOR_KEY: &str = ACCOUNT7.key;
+const OPERATOR_ADDRESS: Address = ACCOUNT7.address;
+
+/// A deployment the indexer already allocates to (so the agent's rules keep
+/// an extra allocation there).
+async fn allocated_deployment(net: &TestNetwork) -> Result<DeploymentId> {
+    let allocs = net.get_allocations().await?;
+    let active = allocs
+        .iter()
+        .find(|a| a.is_active())
+        .context("no active allocation found")?;
+    Ok(active.subgraph_deployment)
+}
+
+#[tokio::test]
+async fn allocation_proof_matches_contract() -> Result<()> {
+    let net = net()?;
+    let key = AllocationKey::random();
+
+    let ours = key.proof_hash(&net.allocation_proof_domain(), net.indexer_address);
+    let contract = net.encode_allocation_proof(key.id())?;
+    eprintln!("  Allocation ID: {}", key.id());
+    eprintln!("  Proof digest:  {ours}");
+    assert_eq!(ours, contract, "EIP-712 digest should match the contract");
+
+    Ok(())
+}
+
+/// BaselineTestPlan 4.2 without the agent: open an allocation with a
+/// crate-generated key, then close it with `stopService`.
+#[tokio::test]
+#[serial]
+async fn start_and_stop_service_as_indexer() -> Result<()> {
+    let net = net()?;
+    let deployment = allocated_deployment(&net).await?;
+    let key = AllocationKey::random();
+
+    eprintln!("=== Direct startService/stopService ===");
+    eprintln!("  Deployment: {deployment}");
+
+    let opened = net.start_service(deployment, ALLOCATION_AMOUNT, &key)?;
+    eprintln!(
+        "  Opened {} ({} GRT, epoch {})",
+        opened.id, opened.tokens, opened.created_at_epoch
+    );
+    assert!(opened.is_open());
+    assert_eq!(opened.indexer, net.indexer_address);
+    assert_eq!(opened.deployment, deployment);
+    assert_eq!(opened.tokens, ALLOCATION_AMOUNT);
+
+    let closed = net.stop_service(key.id())?;
+    eprintln!("  Closed at {}", closed.closed_at);
+    assert!(!closed.is_open());
+    assert!(closed.exists());
+
+    // Allocation IDs cannot be reused
+    let proof = key.proof(&net.allocation_proof_domain(), net.indexer_address)?;
+    let reused = net.start_service_with_proof(
+        &net.receiver_secret,
+        deployment,
+        ALLOCATION_AMOUNT,
+        key.id(),
+        &proof,
+    )?;
+    assert!(!reused, "reusing a closed allocation ID should revert");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn invalid_allocation_proof_is_rejected() -> Result<()> {
+    let net = net()?;
+    let deployment = allocated_deployment(&net).await?;
+    let domain = net.allocation_proof_domain();
+    let key = AllocationKey::random();
+
+    // Signed for a different indexer
+    let wrong_indexer = key.proof(&domain, Address::repeat_byte(0x5a))?;
+    let accepted = net.start_service_with_proof(
+        &net.receiver_secret,
+        deployment,
+        ALLOCATION_AMOUNT,
+        key.id(),
+        &wrong_indexer,
+    )?;
+    eprintln!("  Proof for another indexer accepted: {accepted}");
+    assert!(!accepted);
+
+    // Signed by a key other than the allocation ID's
+    let other = AllocationKey::random().proof(&domain, net.indexer_address)?;
+    let accepted = net.start_service_with_proof(
+        &net.receiver_secret,
+        deployment,
+        ALLOCATION_AMOUNT,
+        key.id(),
+        &other,
+    )?;
+    eprintln!("  Proof from another key accepted: {accepted}");
+    assert!(!accepted);
+    assert!(!net.subgraph_service_allocation(key.id())?.exists());
+
+    Ok(())
+}
+
+/// An operator authorized via `HorizonStaking.setOperator` can manage
+/// allocations for the indexer. Revokes the operator afterwards.
+#[tokio::test]
+#[serial]
+async fn operator_manages_allocation() -> Result<()> {
+    let net = net()?;
+    let deployment = allocated_deployment(&net).await?;
+    let key = AllocationKey::random();
+    let proof = key.proof(&net.allocation_proof_domain(), net.indexer_address)?;
+
+    eprintln!("=== Operator-managed allocation ===");
+
+    net.set_operator(OPERATOR_ADDRESS, false)?;
+    let accepted = net.start_service_with_proof(
+        OPERATOR_KEY,
+        deployment,
+        ALLOCATION_AMOUNT,
+        key.id(),
+        &proof,
+    )?;
+    assert!(!accepted, "unauthorized operator should be rejected");
+
+    net.set_operator(OPERATOR_ADDRESS, true)?;
+    assert!(net.is_operator(OPERATOR_ADDRESS)?);
+    let opened = net.start_service_as(OPERATOR_KEY, deployment, ALLOCATION_AMOUNT, &key)?;
+    eprintln!("  Operator opened {}", opened.id);
+    assert!(opened.is_open());
+    assert_eq!(opened.indexer, net.indexer_address);
+
+    let closed = net.stop_service_as(OPERATOR_KEY, key.id())?;
+    eprintln!("  Operator closed {}", closed.id);
+    assert!(!closed.is_open());
+
+    net.set_operator(OPERATOR_ADDRESS, false)?;
+    assert!(!net.is_operator(OPERATOR_ADDRESS)?);
+
+    Ok(())
+}
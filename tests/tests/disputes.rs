@@ -0,0 +1,253 @@
+//! Dispute Tests (BaselineTestPlan Cycle 5, DisputeManager)
+//!
+//! A dedicated fisherman (hardhat account #3, funded with GRT by account0)
+//! disputes the indexer's work; the arbitrator resolves each dispute.
+//!
+//!   - `dispute_parameters_are_sane` → arbitrator is a known account, cuts within PPM
+//!   - `indexing_dispute_accepted_slashes_provision` → bad POI, accepted: slash + fisherman reward
+//!   - `query_dispute_from_gateway_rejected` → real gateway attestation, rejected: deposit burned
+//!   - `conflicting_attestations_drawn` → two allocations answer the same request differently
+//!   - `cancel_after_dispute_period` → fisherman cancels, deposit returned
+//!
+//! Disputes are opened against throwaway allocations where possible, and the
+//! slashed stake is re-provisioned so later tests see the same provision.
+
+use anyhow::{Context, Result};
+use local_network_tests::allocation::AllocationKey;
+use local_network_tests::disputes::{DisputeStatus, DisputeType, expected_fisherman_reward};
+use local_network_tests::fixtures::{ACCOUNT3, ALLOCATION_AMOUNT, INDEX_TIMEOUT};
+use local_network_tests::grt::PPM;
+use local_network_tests::load::META_QUERY;
+use local_network_tests::polling::PollResult;
+use local_network_tests::{Address, B256, DeploymentId, Grt, TestNetwork};
+use serial_test::serial;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Fisherman, funded with GRT per test.
+const FISHERMAN_KEY: &str = ACCOUNT3.key;
+const FISHERMAN: Address = ACCOUNT3.address;
+
+/// Upper bound on what a test slashes, so the provision stays usable.
+const MAX_SLASH: Grt = Grt::ONE;
+
+/// Deployment of an active agent allocation, for throwaway allocations.
+async fn active_deployment(net: &TestNetwork) -> Result<DeploymentId> {
+    Ok(net
+        .get_allocations()
+        .await?
+        .into_iter()
+        .find(|a| a.is_active())
+        .context("no active allocation found")?
+        .subgraph_deployment)
+}
+
+/// Send the fisherman exactly one dispute deposit.
+fn fund_fisherman(net: &TestNetwork) -> Result<Grt> {
+    let deposit = net.dispute_parameters()?.dispute_deposit;
+    net.transfer_grt(FISHERMAN, deposit)?;
+    Ok(deposit)
+}
+
+#[tokio::test]
+async fn dispute_parameters_are_sane() -> Result<()> {
+    let net = net()?;
+    let params = net.dispute_parameters()?;
+    eprintln!("  {params:?}");
+
+    net.arbitrator_secret()?;
+    assert!(!params.dispute_deposit.is_zero());
+    assert!(params.fisherman_reward_cut <= PPM);
+    assert!(params.max_slashing_cut <= PPM);
+
+    Ok(())
+}
+
+/// An indexing dispute over a made-up POI, accepted: the provision is
+/// slashed and the fisherman gets the reward plus the deposit back.
+#[tokio::test]
+#[serial]
+async fn indexing_dispute_accepted_slashes_provision() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Indexing Dispute → Accept ===");
+
+    let key = AllocationKey::random();
+    net.start_service(active_deployment(&net).await?, ALLOCATION_AMOUNT, &key)?;
+    let provision_before = net.provision()?.tokens;
+
+    let result = accept_indexing_dispute(&net, &key).await;
+
+    // Restore the allocation and the slashed provision tokens
+    net.stop_service(key.id())?;
+    let slashed = provision_before.saturating_sub(net.provision()?.tokens);
+    if !slashed.is_zero() {
+        net.stake_tokens(slashed)?;
+        net.provision_add(slashed)?;
+    }
+    result
+}
+
+/// Dispute `key`'s allocation with a made-up POI and accept it.
+async fn accept_indexing_dispute(net: &TestNetwork, key: &AllocationKey) -> Result<()> {
+    let deposit = fund_fisherman(net)?;
+    let balance_before = net.grt_balance_of(FISHERMAN)?;
+    let block = net.get_block_number_sync()?;
+    let dispute =
+        net.create_indexing_dispute_as(FISHERMAN_KEY, key.id(), B256::repeat_byte(0xba), block)?;
+    eprintln!("  Created {} ({} GRT deposit)", dispute.id, dispute.deposit);
+    assert_eq!(dispute.dispute_type, DisputeType::IndexingDispute);
+    assert_eq!(dispute.indexer, net.indexer_address);
+    assert_eq!(dispute.deposit, deposit);
+    assert_eq!(net.grt_balance_of(FISHERMAN)?, balance_before - deposit);
+
+    let params = net.dispute_parameters()?;
+    let provision = net.provision()?;
+    let slash = params.max_tokens_slash(&dispute).min(MAX_SLASH);
+    let reward = expected_fisherman_reward(
+        slash,
+        params.fisherman_reward_cut,
+        provision.max_verifier_cut,
+        provision.tokens,
+    );
+    let accepted = net.accept_dispute(dispute.id, slash)?;
+    let slashed = provision.tokens - net.provision()?.tokens;
+    let received = net.grt_balance_of(FISHERMAN)? - (balance_before - deposit);
+    eprintln!("  Accepted: slashed {slashed} GRT, fisherman received {received} GRT");
+
+    assert_eq!(accepted.status, DisputeStatus::Accepted);
+    assert_eq!(slashed, slash);
+    assert_eq!(received, reward + deposit);
+
+    let result = net
+        .poll_until(INDEX_TIMEOUT, Duration::from_secs(2), || async {
+            Ok(net
+                .query_dispute(dispute.id)
+                .await?
+                .filter(|d| d.status == "Accepted"))
+        })
+        .await;
+    match result {
+        PollResult::Ready(indexed) => {
+            eprintln!("  Subgraph: {indexed:?}");
+            assert_eq!(indexed.fisherman, FISHERMAN);
+            assert_eq!(indexed.tokens_slashed, slash);
+        }
+        PollResult::TimedOut => anyhow::bail!("dispute not indexed within {INDEX_TIMEOUT:?}"),
+    }
+
+    Ok(())
+}
+
+/// A query dispute over a real gateway response, rejected: the deposit is
+/// burned and the provision is untouched.
+#[tokio::test]
+#[serial]
+async fn query_dispute_from_gateway_rejected() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Query Dispute → Reject ===");
+
+    let deployment = net.gateway_deployment().await?;
+    let resp = net.gateway_query(META_QUERY).await?;
+    let verified = net
+        .verify_gateway_attestation(&resp, deployment)
+        .await
+        .context("need an attested gateway response")?;
+    eprintln!("  Disputing response signed by {}", verified.allocation);
+
+    let deposit = fund_fisherman(&net)?;
+    let balance_before = net.grt_balance_of(FISHERMAN)?;
+    let provision_before = net.provision()?.tokens;
+    let dispute = net.create_query_dispute_as(FISHERMAN_KEY, &verified.attestation)?;
+    assert_eq!(dispute.dispute_type, DisputeType::QueryDispute);
+    assert_eq!(dispute.indexer, verified.indexer);
+
+    let rejected = net.reject_dispute(dispute.id)?;
+    let balance = net.grt_balance_of(FISHERMAN)?;
+    eprintln!("  Rejected: fisherman balance {balance_before} → {balance} GRT");
+
+    assert_eq!(rejected.status, DisputeStatus::Rejected);
+    assert_eq!(
+        balance,
+        balance_before - deposit,
+        "deposit should be burned"
+    );
+    assert_eq!(net.provision()?.tokens, provision_before);
+
+    Ok(())
+}
+
+/// Two allocations attest different responses to the same request. The
+/// conflict creates a linked pair of disputes; drawing one draws both.
+#[tokio::test]
+#[serial]
+async fn conflicting_attestations_drawn() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Conflicting Attestations → Draw ===");
+
+    let deployment = active_deployment(&net).await?;
+    let (key1, key2) = (AllocationKey::random(), AllocationKey::random());
+    net.start_service(deployment, ALLOCATION_AMOUNT, &key1)?;
+    net.start_service(deployment, ALLOCATION_AMOUNT, &key2)?;
+
+    let domain = net.attestation_domain();
+    let request = br#"{"query":"{ _meta { block { number } } }"}"#;
+    let a1 = key1.sign_attestation(&domain, request, br#"{"data":{"n":1}}"#, deployment)?;
+    let a2 = key2.sign_attestation(&domain, request, br#"{"data":{"n":2}}"#, deployment)?;
+
+    let created = net.create_query_dispute_conflict_as(FISHERMAN_KEY, &a1, &a2);
+    let drawn = created.as_ref().ok().map(|(d1, _)| net.draw_dispute(d1.id));
+    net.stop_service(key1.id())?;
+    net.stop_service(key2.id())?;
+
+    let (d1, d2) = created?;
+    eprintln!("  Created {} ↔ {}", d1.id, d2.id);
+    assert_eq!(d1.related_dispute_id, d2.id);
+    assert_eq!(d2.related_dispute_id, d1.id);
+    assert_eq!(d1.dispute_type, DisputeType::QueryDispute);
+
+    let drawn = drawn.context("draw not attempted")??;
+    assert_eq!(drawn.status, DisputeStatus::Drawn);
+    assert_eq!(net.dispute(d2.id)?.status, DisputeStatus::Drawn);
+
+    Ok(())
+}
+
+/// Once the dispute period has passed the fisherman can cancel and gets the
+/// deposit back.
+#[tokio::test]
+#[serial]
+async fn cancel_after_dispute_period() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Cancel Dispute ===");
+
+    let key = AllocationKey::random();
+    net.start_service(active_deployment(&net).await?, ALLOCATION_AMOUNT, &key)?;
+    let result = cancel_indexing_dispute(&net, &key).await;
+    net.stop_service(key.id())?;
+    result
+}
+
+/// Dispute `key`'s allocation and cancel once the dispute period is over.
+async fn cancel_indexing_dispute(net: &TestNetwork, key: &AllocationKey) -> Result<()> {
+    let deposit = fund_fisherman(net)?;
+    let balance_before = net.grt_balance_of(FISHERMAN)?;
+    let block = net.get_block_number_sync()?;
+    let dispute =
+        net.create_indexing_dispute_as(FISHERMAN_KEY, key.id(), B256::repeat_byte(0xca), block)?;
+
+    let now = net.advance_time_to(dispute.cancellable_at).await?;
+    let cancelled = net.cancel_dispute_as(FISHERMAN_KEY, dispute.id)?;
+    eprintln!(
+        "  Cancelled at {now} (cancellable at {})",
+        dispute.cancellable_at
+    );
+
+    assert_eq!(cancelled.status, DisputeStatus::Cancelled);
+    assert_eq!(net.grt_balance_of(FISHERMAN)?, balance_before);
+    assert_eq!(dispute.deposit, deposit);
+
+    Ok(())
+}
@@ -14,50 +14,35 @@
 //! No dependency on the REO node's async processing.
 
 use anyhow::{Context, Result};
-use local_network_tests::TestNetwork;
+use local_network_tests::fixtures::ALLOCATION_AMOUNT;
+use local_network_tests::{Address, DeploymentId, Grt, TestNetwork};
 
 fn net() -> Result<TestNetwork> {
     TestNetwork::from_default_env()
 }
 
-/// Parse a reward string (may be "0", "0.0", "123.456", etc.) to f64.
-fn parse_rewards(s: &str) -> f64 {
-    s.parse::<f64>().unwrap_or(0.0)
-}
-
 /// Helper: close an existing active allocation and return (deployment, alloc_id).
 /// This frees the deployment for a new allocation.
-async fn close_existing_allocation(net: &TestNetwork) -> Result<(String, String)> {
+async fn close_existing_allocation(net: &TestNetwork) -> Result<(DeploymentId, Address)> {
     let allocs = net.get_allocations().await?;
-    let allocs = allocs.as_array().context("expected allocation array")?;
     let active = allocs
         .iter()
-        .find(|a| a["closedAtEpoch"].is_null())
+        .find(|a| a.is_active())
         .context("no active allocation found")?;
-    let alloc_id = active["id"]
-        .as_str()
-        .context("allocation missing id")?
-        .to_string();
-    let deployment = active["subgraphDeployment"]
-        .as_str()
-        .context("allocation missing deployment")?
-        .to_string();
+    let alloc_id = active.id;
+    let deployment = active.subgraph_deployment;
 
     // Advance epochs so allocation is old enough to close
     net.advance_epochs(2).await?;
-    net.close_allocation(&alloc_id).await?;
+    net.close_allocation(alloc_id).await?;
 
     Ok((deployment, alloc_id))
 }
 
 /// Helper: create allocation, advance epochs, and return the allocation ID.
-async fn create_test_allocation(net: &TestNetwork, deployment: &str) -> Result<String> {
-    let amount = "0.01"; // GRT (management API takes GRT, not wei)
-    let result = net.create_allocation(deployment, amount).await?;
-    let alloc_id = result["allocation"]
-        .as_str()
-        .context("expected allocation ID")?
-        .to_string();
+async fn create_test_allocation(net: &TestNetwork, deployment: DeploymentId) -> Result<Address> {
+    let result = net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
+    let alloc_id = result.allocation;
 
     // Advance epochs so it's old enough to close
     net.advance_epochs(2).await?;
@@ -92,37 +77,36 @@ async fn eligibility_lifecycle() -> Result<()> {
     eprintln!();
     eprintln!("=== Set 2: Eligible indexer closes allocation ===");
 
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     assert!(
-        net.reo_is_eligible(&net.indexer_address)?,
+        net.reo_is_eligible(net.indexer_address)?,
         "Indexer should be eligible after renewal"
     );
 
-    let alloc_id = create_test_allocation(&net, &deployment).await?;
+    let alloc_id = create_test_allocation(&net, deployment).await?;
     eprintln!("  Allocation: {alloc_id}");
 
     // Re-renew to ensure still eligible (time advanced during epoch mining)
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     assert!(
-        net.reo_is_eligible(&net.indexer_address)?,
+        net.reo_is_eligible(net.indexer_address)?,
         "Indexer should still be eligible before close"
     );
 
-    let close = net.close_allocation(&alloc_id).await?;
-    let rewards = close["indexingRewards"].as_str().unwrap_or("0");
-    let eligible_rewards = parse_rewards(rewards);
-    eprintln!("  indexingRewards: {rewards} (eligible)");
+    let close = net.close_allocation(alloc_id).await?;
+    let eligible_rewards = close.indexing_rewards;
+    eprintln!("  indexingRewards: {eligible_rewards} GRT (eligible)");
     assert!(
-        eligible_rewards > 0.0,
-        "Set 2: Eligible indexer should receive rewards, got {rewards}"
+        !eligible_rewards.is_zero(),
+        "Set 2: Eligible indexer should receive rewards, got {eligible_rewards}"
     );
 
     // ── Set 3: Ineligible → close → verify rewards denied ──
     eprintln!();
     eprintln!("=== Set 3: Ineligible indexer denied rewards ===");
 
-    net.reo_renew_indexer(&net.indexer_address)?;
-    let alloc_id = create_test_allocation(&net, &deployment).await?;
+    net.reo_renew_indexer(net.indexer_address)?;
+    let alloc_id = create_test_allocation(&net, deployment).await?;
     eprintln!("  Allocation: {alloc_id}");
 
     // Expire eligibility
@@ -131,20 +115,20 @@ async fn eligibility_lifecycle() -> Result<()> {
     net.advance_time(period + 60).await?;
 
     assert!(
-        !net.reo_is_eligible(&net.indexer_address)?,
+        !net.reo_is_eligible(net.indexer_address)?,
         "Set 3: Indexer should be ineligible after period expiry"
     );
 
     // ReoTestPlan 6.3: Record stake before closing while ineligible
     let stake_before_denied = net.staked_tokens()?;
 
-    let close = net.close_allocation(&alloc_id).await?;
-    let rewards = close["indexingRewards"].as_str().unwrap_or("0");
-    let ineligible_rewards = parse_rewards(rewards);
-    eprintln!("  indexingRewards: {rewards} (ineligible)");
-    assert!(
-        ineligible_rewards == 0.0,
-        "Set 3: Ineligible indexer should receive zero rewards, got {rewards}"
+    let close = net.close_allocation(alloc_id).await?;
+    let ineligible_rewards = close.indexing_rewards;
+    eprintln!("  indexingRewards: {ineligible_rewards} GRT (ineligible)");
+    assert_eq!(
+        ineligible_rewards,
+        Grt::ZERO,
+        "Set 3: Ineligible indexer should receive zero rewards, got {ineligible_rewards}"
     );
 
     // ReoTestPlan 6.3: Verify stake did not increase (denied rewards not credited)
@@ -162,15 +146,15 @@ async fn eligibility_lifecycle() -> Result<()> {
     eprintln!();
     eprintln!("=== Set 4: Re-renewed indexer (optimistic recovery) ===");
 
-    net.reo_renew_indexer(&net.indexer_address)?;
-    let alloc_id = create_test_allocation(&net, &deployment).await?;
+    net.reo_renew_indexer(net.indexer_address)?;
+    let alloc_id = create_test_allocation(&net, deployment).await?;
     eprintln!("  Allocation: {alloc_id}");
 
     // Let eligibility expire
     eprintln!("  Expiring eligibility ({period}s)...");
     net.advance_time(period + 60).await?;
     assert!(
-        !net.reo_is_eligible(&net.indexer_address)?,
+        !net.reo_is_eligible(net.indexer_address)?,
         "Should be ineligible"
     );
 
@@ -178,19 +162,18 @@ async fn eligibility_lifecycle() -> Result<()> {
     net.advance_epochs(2).await?;
 
     // Re-renew — the key assertion: eligibility can be restored
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     assert!(
-        net.reo_is_eligible(&net.indexer_address)?,
+        net.reo_is_eligible(net.indexer_address)?,
         "Should be eligible after re-renewal"
     );
 
-    let close = net.close_allocation(&alloc_id).await?;
-    let rewards = close["indexingRewards"].as_str().unwrap_or("0");
-    let recovery_rewards = parse_rewards(rewards);
-    eprintln!("  indexingRewards: {rewards} (re-eligible)");
+    let close = net.close_allocation(alloc_id).await?;
+    let recovery_rewards = close.indexing_rewards;
+    eprintln!("  indexingRewards: {recovery_rewards} GRT (re-eligible)");
     assert!(
-        recovery_rewards > 0.0,
-        "Set 4: Re-eligible indexer should receive rewards, got {rewards}"
+        !recovery_rewards.is_zero(),
+        "Set 4: Re-eligible indexer should receive rewards, got {recovery_rewards}"
     );
     assert!(
         recovery_rewards > eligible_rewards,
@@ -201,7 +184,7 @@ async fn eligibility_lifecycle() -> Result<()> {
     // Restore: re-create the allocation we consumed
     eprintln!();
     eprintln!("=== Cleanup: restoring allocation for {deployment} ===");
-    net.create_allocation(&deployment, "0.01").await?;
+    net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
 
     Ok(())
 }
@@ -0,0 +1,93 @@
+//! Epoch Length Tests (EpochManager)
+//!
+//!   - `epoch_length_override_restores` → shortening the epoch length mid-epoch
+//!     through a `GovernanceOverride` skips no epochs and is undone on drop
+//!   - `advance_to_epoch_without_gaps` → `advance_to_epoch` reaches the target
+//!     with every epoch in the block-oracle subgraph and the length restored
+
+use anyhow::Result;
+use local_network_tests::TestNetwork;
+use local_network_tests::epochs::FAST_EPOCH_LENGTH;
+use local_network_tests::governance::{GovernanceParameter, ParameterValue};
+use serial_test::serial;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+#[tokio::test]
+#[serial]
+async fn epoch_length_override_restores() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Epoch Length Override ===");
+
+    let before = net.epoch_state()?;
+    eprintln!(
+        "  Epoch {} ({} of {} blocks)",
+        before.epoch,
+        before.blocks_into_epoch(),
+        before.length
+    );
+    // Shorter than the blocks already run, when possible
+    let short = (before.blocks_into_epoch() / 2).max(1);
+
+    {
+        let mut overrides = net.governance_override();
+        let after = overrides.set_epoch_length(short).await?;
+        eprintln!(
+            "  Length {short}: epoch {} ({} of {} blocks)",
+            after.epoch,
+            after.blocks_into_epoch(),
+            after.length
+        );
+        assert_eq!(after.length, short);
+        assert!(
+            after.epoch <= before.epoch + 1,
+            "epoch jumped {} → {}",
+            before.epoch,
+            after.epoch
+        );
+        if after.epoch > before.epoch {
+            assert!(
+                net.block_oracle_has_epoch(after.epoch).await?,
+                "block oracle missing epoch {}",
+                after.epoch
+            );
+        }
+    }
+
+    let restored = net.governance_get(GovernanceParameter::EpochLength)?;
+    eprintln!("  Length after drop: {restored}");
+    assert_eq!(restored, ParameterValue::Uint(before.length.into()));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn advance_to_epoch_without_gaps() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Advance To Epoch ===");
+
+    let before = net.epoch_state()?;
+    let target = before.epoch + 3;
+    let epoch = net.advance_to_epoch(target).await?;
+    let after = net.epoch_state()?;
+    eprintln!(
+        "  Epoch {} → {epoch} in {} blocks (length {}, fast {FAST_EPOCH_LENGTH})",
+        before.epoch,
+        after.block - before.block,
+        before.length
+    );
+
+    assert!(epoch >= target, "reached epoch {epoch}, wanted {target}");
+    assert_eq!(after.length, before.length, "epoch length not restored");
+    for epoch in before.epoch + 1..=epoch {
+        assert!(
+            net.block_oracle_has_epoch(epoch).await?,
+            "block oracle missing epoch {epoch}"
+        );
+    }
+
+    Ok(())
+}
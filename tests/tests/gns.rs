@@ -0,0 +1,153 @@
+//! Subgraph Publishing Tests (L2GNS)
+//!
+//! account0 publishes throwaway subgraphs on deployments the indexer already
+//! allocates to, so the gateway can serve them end to end:
+//!
+//!   - `publish_upgrade_and_deprecate` → new subgraph → new version → deprecated,
+//!     on-chain, in the network subgraph and at the gateway
+//!   - `update_metadata` → `updateSubgraphMetadata` reaches `Subgraph.metadataHash`
+//!
+//! Every published subgraph is deprecated before the test ends.
+
+use anyhow::{Context, Result};
+use local_network_tests::fixtures::INDEX_TIMEOUT;
+use local_network_tests::polling::PollResult;
+use local_network_tests::{B256, DeploymentId, SubgraphId, TestNetwork};
+use serial_test::serial;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// The gateway refreshes subgraph resolution from the network subgraph
+/// periodically.
+const GATEWAY_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Distinct deployments with an active agent allocation.
+async fn allocated_deployments(net: &TestNetwork) -> Result<Vec<DeploymentId>> {
+    let mut deployments: Vec<DeploymentId> = net
+        .get_allocations()
+        .await?
+        .into_iter()
+        .filter(|a| a.is_active())
+        .map(|a| a.subgraph_deployment)
+        .collect();
+    deployments.sort();
+    deployments.dedup();
+    Ok(deployments)
+}
+
+#[tokio::test]
+#[serial]
+async fn publish_upgrade_and_deprecate() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Publish → New Version → Deprecate ===");
+
+    let deployments = allocated_deployments(&net).await?;
+    let [first, second, ..] = deployments[..] else {
+        anyhow::bail!(
+            "need two allocated deployments, found {}",
+            deployments.len()
+        );
+    };
+    let publisher = net.subgraph_owner(net.subgraph_id)?;
+
+    let subgraph = net.publish_subgraph(first, B256::ZERO, B256::ZERO)?;
+    eprintln!("  Published {subgraph} on {first}");
+    // Read before deprecating: deprecation burns the NFT
+    let owner = net.subgraph_owner(subgraph);
+    // Deprecate even if a check below fails
+    let result = upgrade_and_check(&net, subgraph, first, second).await;
+    net.deprecate_subgraph(subgraph)?;
+    result?;
+
+    assert_eq!(owner?, publisher);
+    assert!(!net.subgraph_is_published(subgraph)?);
+    assert!(
+        net.subgraph_owner(subgraph).is_err(),
+        "deprecated subgraph's NFT should be burned"
+    );
+    net.wait_for_gateway_subgraph(subgraph, None, GATEWAY_TIMEOUT)
+        .await?;
+    let indexed = net
+        .query_subgraph(subgraph)
+        .await?
+        .context("deprecated subgraph missing from the network subgraph")?;
+    eprintln!("  Deprecated: active={}", indexed.active);
+    assert!(!indexed.active);
+
+    Ok(())
+}
+
+async fn upgrade_and_check(
+    net: &TestNetwork,
+    subgraph: SubgraphId,
+    first: DeploymentId,
+    second: DeploymentId,
+) -> Result<()> {
+    assert!(net.subgraph_is_published(subgraph)?);
+    let indexed = net
+        .wait_for_subgraph_version(subgraph, first, INDEX_TIMEOUT)
+        .await?;
+    assert_eq!(indexed.versions.len(), 1);
+    net.wait_for_gateway_subgraph(subgraph, Some(first), GATEWAY_TIMEOUT)
+        .await?;
+    eprintln!("  Gateway serves {subgraph} from {first}");
+
+    net.publish_new_version(subgraph, second, B256::ZERO)?;
+    let indexed = net
+        .wait_for_subgraph_version(subgraph, second, INDEX_TIMEOUT)
+        .await?;
+    let versions: Vec<_> = indexed.versions.iter().map(|v| v.deployment).collect();
+    assert_eq!(versions, [first, second]);
+    net.wait_for_gateway_subgraph(subgraph, Some(second), GATEWAY_TIMEOUT)
+        .await?;
+    eprintln!("  Gateway serves {subgraph} from {second} after upgrade");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn update_metadata() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Subgraph Metadata ===");
+
+    let deployment = *allocated_deployments(&net)
+        .await?
+        .first()
+        .context("no allocated deployment found")?;
+    let version_metadata = B256::repeat_byte(0x1e);
+    let subgraph = net.publish_subgraph(deployment, version_metadata, B256::ZERO)?;
+    let result = update_and_check(&net, subgraph, version_metadata).await;
+    net.deprecate_subgraph(subgraph)?;
+    result
+}
+
+async fn update_and_check(
+    net: &TestNetwork,
+    subgraph: SubgraphId,
+    version_metadata: B256,
+) -> Result<()> {
+    let metadata = B256::repeat_byte(0x5e);
+    net.update_subgraph_metadata(subgraph, metadata)?;
+
+    let result = net
+        .poll_until(INDEX_TIMEOUT, Duration::from_secs(2), || async {
+            let indexed = net.query_subgraph(subgraph).await?;
+            Ok(indexed.filter(|s| s.metadata_hash == Some(metadata)))
+        })
+        .await;
+    let indexed = match result {
+        PollResult::Ready(indexed) => indexed,
+        PollResult::TimedOut => {
+            anyhow::bail!("metadata update not indexed within {INDEX_TIMEOUT:?}")
+        }
+    };
+    eprintln!("  {subgraph}: metadataHash {:?}", indexed.metadata_hash);
+    let version = indexed.current_version.context("no current version")?;
+    assert_eq!(version.metadata_hash, Some(version_metadata));
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Governance Parameter Tests (RewardsManager / SubgraphService / REO)
+//!
+//!   - `all_parameters_readable` → every tunable parameter reads through
+//!     `governance_get` with the right type
+//!   - `override_restores_on_drop` → parameters changed through a
+//!     `GovernanceOverride` are back to their originals once it is dropped
+//!   - `override_restore_is_explicit` → `restore()` resets immediately, and
+//!     overriding twice keeps the first original
+//!
+//! REO parameters are skipped when no REO is deployed.
+
+use anyhow::Result;
+use local_network_tests::TestNetwork;
+use local_network_tests::governance::{GovernanceParameter, ParameterValue};
+use serial_test::serial;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Parameters changed by the override tests, with a different valid value.
+fn changed_values(net: &TestNetwork) -> Result<Vec<(GovernanceParameter, ParameterValue)>> {
+    let uint = |parameter| -> Result<u128> {
+        net.governance_get(parameter)?
+            .as_uint()
+            .ok_or_else(|| anyhow::anyhow!("{parameter} is not an integer"))
+    };
+    let staleness = uint(GovernanceParameter::MaxPoiStaleness)?;
+    let stake_to_fees = uint(GovernanceParameter::StakeToFeesRatio)?;
+    // PPM, at most 1_000_000
+    let curation_cut = uint(GovernanceParameter::CurationCut)?;
+    let minimum_signal = uint(GovernanceParameter::MinimumSubgraphSignal)?;
+    Ok(vec![
+        (
+            GovernanceParameter::MaxPoiStaleness,
+            ParameterValue::Uint(staleness / 2 + 1),
+        ),
+        (
+            GovernanceParameter::StakeToFeesRatio,
+            ParameterValue::Uint(stake_to_fees + 1),
+        ),
+        (
+            GovernanceParameter::CurationCut,
+            ParameterValue::Uint(if curation_cut < 1_000_000 {
+                curation_cut + 1
+            } else {
+                curation_cut - 1
+            }),
+        ),
+        (
+            GovernanceParameter::MinimumSubgraphSignal,
+            ParameterValue::Uint(minimum_signal + 1),
+        ),
+    ])
+}
+
+#[tokio::test]
+async fn all_parameters_readable() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Governance Parameters ===");
+
+    for parameter in GovernanceParameter::ALL {
+        if net.contracts.reo.is_none()
+            && matches!(
+                parameter,
+                GovernanceParameter::EligibilityPeriod | GovernanceParameter::OracleUpdateTimeout
+            )
+        {
+            eprintln!("  {parameter}: SKIP (no REO deployed)");
+            continue;
+        }
+        let value = net.governance_get(parameter)?;
+        eprintln!("  {parameter}: {value} (set by {:?})", parameter.signer());
+        assert_eq!(
+            value.as_address().is_some(),
+            parameter.is_address(),
+            "{parameter} read as {value:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn override_restores_on_drop() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Governance Override: restore on drop ===");
+
+    let changes = changed_values(&net)?;
+    let mut originals = Vec::new();
+    for (parameter, _) in &changes {
+        originals.push((*parameter, net.governance_get(*parameter)?));
+    }
+
+    {
+        let mut overrides = net.governance_override();
+        for (parameter, value) in &changes {
+            overrides.set(*parameter, *value)?;
+            let now = net.governance_get(*parameter)?;
+            eprintln!(
+                "  {parameter}: {} → {now}",
+                overrides.original(*parameter).unwrap()
+            );
+            assert_eq!(now, *value, "{parameter} not overridden");
+        }
+    }
+
+    for (parameter, original) in originals {
+        let now = net.governance_get(parameter)?;
+        eprintln!("  {parameter} after drop: {now}");
+        assert_eq!(now, original, "{parameter} not restored");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn override_restore_is_explicit() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Governance Override: explicit restore ===");
+
+    let parameter = GovernanceParameter::MaxPoiStaleness;
+    let original = net.governance_get(parameter)?;
+    let staleness = original.as_uint().unwrap_or_default();
+
+    let mut overrides = net.governance_override();
+    overrides.set(parameter, staleness / 2 + 1)?;
+    overrides.set(parameter, staleness / 4 + 1)?;
+    eprintln!(
+        "  {parameter}: {original} → {}",
+        net.governance_get(parameter)?
+    );
+    assert_eq!(
+        overrides.original(parameter),
+        Some(original),
+        "second override should keep the first original"
+    );
+    assert_eq!(
+        overrides.original(GovernanceParameter::StakeToFeesRatio),
+        None
+    );
+
+    overrides.restore()?;
+    let now = net.governance_get(parameter)?;
+    eprintln!("  {parameter} after restore: {now}");
+    assert_eq!(now, original);
+
+    Ok(())
+}
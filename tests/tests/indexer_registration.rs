@@ -0,0 +1,131 @@
+//! Indexer Registration Tests (BaselineTestPlan Cycle 1.2)
+//!
+//! Write-side counterpart of `indexer_registered`: re-register the local
+//! indexer with SubgraphService and check the network subgraph follows.
+//!
+//!   - `registration_matches_agent_config` → url/geoHash as configured by the agent
+//!   - `reregister_updates_url_and_geohash` → re-registration (restores the original)
+//!   - `payments_destination_round_trip` → `setPaymentsDestination`
+//!   - `invalid_registration_rejected` → empty url / geohash revert on-chain
+
+use anyhow::Result;
+use local_network_tests::fixtures::{ACCOUNT5, INDEX_TIMEOUT};
+use local_network_tests::polling::PollResult;
+use local_network_tests::registration::{Registration, geohash};
+use local_network_tests::{Address, TestNetwork};
+use serial_test::serial;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Payments destination to set and restore.
+const DESTINATION: Address = ACCOUNT5.address;
+
+/// Wait until the network subgraph shows `expected` url and geohash.
+async fn wait_for_registration(net: &TestNetwork, expected: &Registration) -> Result<()> {
+    let result = net
+        .poll_until(INDEX_TIMEOUT, Duration::from_secs(2), || async {
+            net.mine_blocks(1).await?;
+            let current = net.indexer_registration(net.indexer_address).await?;
+            Ok((current.url == expected.url && current.geohash == expected.geohash).then_some(()))
+        })
+        .await;
+    match result {
+        PollResult::Ready(()) => Ok(()),
+        PollResult::TimedOut => anyhow::bail!(
+            "network subgraph did not index {} / {} within {INDEX_TIMEOUT:?}",
+            expected.url,
+            expected.geohash
+        ),
+    }
+}
+
+/// The agent registers `INDEXER_AGENT_PUBLIC_INDEXER_URL` and geohashes
+/// `INDEXER_AGENT_INDEXER_GEO_COORDINATES="1 1"`.
+#[tokio::test]
+async fn registration_matches_agent_config() -> Result<()> {
+    let net = net()?;
+    let reg = net.indexer_registration(net.indexer_address).await?;
+    eprintln!("  url: {}", reg.url);
+    eprintln!("  geoHash: {}", reg.geohash);
+    eprintln!("  paymentsDestination: {}", reg.payments_destination);
+
+    reg.validate()?;
+    let (lat, lon) = geohash::decode(&reg.geohash)?;
+    eprintln!("  decoded: {lat:.5}, {lon:.5}");
+    assert!(
+        (lat - 1.0).abs() < 0.01 && (lon - 1.0).abs() < 0.01,
+        "geoHash should encode the agent's coordinates (1, 1)"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn reregister_updates_url_and_geohash() -> Result<()> {
+    let net = net()?;
+    let original = net.indexer_registration(net.indexer_address).await?;
+
+    eprintln!("=== Re-registration ===");
+    eprintln!("  Original: {} ({})", original.url, original.geohash);
+
+    // London; keep the payments destination
+    let moved = Registration::new("http://indexer-service-moved:7601", 51.5072, -0.1276)?
+        .payments_destination(original.payments_destination);
+    net.register_indexer(&moved)?;
+    let indexed = wait_for_registration(&net, &moved).await;
+    eprintln!("  Moved:    {} ({})", moved.url, moved.geohash);
+
+    // Restore before asserting: the gateway routes to the registered URL
+    net.register_indexer(&original)?;
+    indexed?;
+    wait_for_registration(&net, &original).await?;
+    eprintln!("  Restored");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn payments_destination_round_trip() -> Result<()> {
+    let net = net()?;
+    let original = net.payments_destination(net.indexer_address)?;
+    eprintln!("  Original paymentsDestination: {original}");
+
+    net.set_payments_destination(DESTINATION)?;
+    let updated = net.payments_destination(net.indexer_address)?;
+    net.set_payments_destination(original)?;
+
+    assert_eq!(updated, DESTINATION);
+    assert_eq!(net.payments_destination(net.indexer_address)?, original);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn invalid_registration_rejected() -> Result<()> {
+    let net = net()?;
+    let original = net.indexer_registration(net.indexer_address).await?;
+
+    let mut empty_url = original.clone();
+    empty_url.url.clear();
+    assert!(empty_url.validate().is_err());
+    let accepted_url = net.try_register_indexer(&empty_url)?;
+
+    let mut empty_geohash = original.clone();
+    empty_geohash.geohash.clear();
+    let accepted_geohash = net.try_register_indexer(&empty_geohash)?;
+
+    eprintln!("  Empty url accepted: {accepted_url}, empty geohash accepted: {accepted_geohash}");
+    if accepted_url || accepted_geohash {
+        net.register_indexer(&original)?;
+    }
+    assert!(!accepted_url, "empty url should revert");
+    assert!(!accepted_geohash, "empty geohash should revert");
+
+    Ok(())
+}
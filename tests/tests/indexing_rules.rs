@@ -0,0 +1,145 @@
+//! Indexing Rules Tests (BaselineTestPlan Cycle 4.4)
+//!
+//! Emulates `graph indexer rules get/set/delete` through the indexer
+//! management API and checks that the agent's reconciliation loop acts on
+//! deployment rules.
+//!
+//! Mapping to BaselineTestPlan:
+//!   - `indexing_rule_crud` → Cycle 4.4 (set/get/update/delete a deployment rule)
+//!   - `rule_driven_allocation` → Cycle 4.4 (create via deployment rules)
+//!
+//! `start-indexing` sets `decisionBasis always` on the network, block-oracle
+//! and TAP deployments; `rule_driven_allocation` flips one of them to `never`
+//! and back, then restores the original rule.
+
+use anyhow::{Context, Result};
+use local_network_tests::fixtures::ALLOCATION_AMOUNT;
+use local_network_tests::management::{DecisionBasis, IdentifierType, IndexingRuleInput};
+use local_network_tests::{B256, DeploymentId, Grt, TestNetwork};
+use serial_test::serial;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// The agent reconciles every polling interval (120s by default).
+const RECONCILE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Rule CRUD on a deployment nobody publishes, with `never` so the agent
+/// takes no action while the rule exists.
+#[tokio::test]
+#[serial]
+async fn indexing_rule_crud() -> Result<()> {
+    let net = net()?;
+    let deployment = DeploymentId::new(B256::repeat_byte(0x42));
+    let id = deployment.to_string();
+
+    eprintln!("=== Indexing Rule CRUD ({id}) ===");
+
+    let rule = net
+        .set_indexing_rule(
+            &IndexingRuleInput::deployment(deployment)
+                .decision_basis(DecisionBasis::Never)
+                .allocation_amount(ALLOCATION_AMOUNT),
+        )
+        .await?;
+    assert_eq!(rule.identifier, id);
+    assert_eq!(rule.identifier_type, IdentifierType::Deployment);
+    assert_eq!(rule.decision_basis, DecisionBasis::Never);
+    assert_eq!(rule.allocation_amount, Some(ALLOCATION_AMOUNT));
+
+    // Update thresholds; unset fields are kept
+    let updated = net
+        .set_indexing_rule(
+            &IndexingRuleInput::deployment(deployment)
+                .min_signal(Grt::from_grt(100))
+                .min_stake(Grt::from_grt(1000)),
+        )
+        .await?;
+    assert_eq!(updated.min_signal, Some(Grt::from_grt(100)));
+    assert_eq!(updated.min_stake, Some(Grt::from_grt(1000)));
+    assert_eq!(updated.decision_basis, DecisionBasis::Never);
+    assert_eq!(updated.allocation_amount, Some(ALLOCATION_AMOUNT));
+
+    let fetched = net.indexing_rule(&id, false).await?;
+    assert_eq!(fetched.as_ref(), Some(&updated));
+    let all = net.indexing_rules(false).await?;
+    assert!(
+        all.iter().any(|r| r.identifier == id),
+        "rule should be listed"
+    );
+    eprintln!("  {} rules configured", all.len());
+
+    // Merged view fills unset fields from the global rule
+    let global = net
+        .indexing_rule("global", false)
+        .await?
+        .context("global rule should exist")?;
+    let merged = net.indexing_rule(&id, true).await?.context("merged rule")?;
+    assert_eq!(merged.max_signal, updated.max_signal.or(global.max_signal));
+
+    assert!(net.delete_indexing_rules(&[&id]).await?);
+    assert_eq!(net.indexing_rule(&id, false).await?, None);
+
+    Ok(())
+}
+
+/// BaselineTestPlan 4.4: the agent closes and re-creates an allocation when
+/// its deployment rule changes.
+#[tokio::test]
+#[serial]
+async fn rule_driven_allocation() -> Result<()> {
+    let net = net()?;
+
+    let allocs = net.get_allocations().await?;
+    let active = allocs
+        .iter()
+        .find(|a| a.is_active())
+        .context("no active allocation to drive with rules")?;
+    let deployment = active.subgraph_deployment;
+    let id = deployment.to_string();
+    let original = net.indexing_rule(&id, false).await?;
+    eprintln!("=== Rule-Driven Allocation ({id}) ===");
+    eprintln!(
+        "  Original rule: {:?}",
+        original.as_ref().map(|r| r.decision_basis)
+    );
+
+    // decisionBasis never → agent closes the allocation
+    net.set_indexing_rule(
+        &IndexingRuleInput::deployment(deployment).decision_basis(DecisionBasis::Never),
+    )
+    .await?;
+    net.wait_for_rule_reconciliation(deployment, false, RECONCILE_TIMEOUT)
+        .await?;
+    eprintln!("  Allocation closed by agent");
+
+    // decisionBasis always → agent allocates again
+    net.set_indexing_rule(
+        &IndexingRuleInput::deployment(deployment)
+            .decision_basis(DecisionBasis::Always)
+            .allocation_amount(ALLOCATION_AMOUNT),
+    )
+    .await?;
+    let alloc = net
+        .wait_for_rule_reconciliation(deployment, true, RECONCILE_TIMEOUT)
+        .await?
+        .context("reconciliation returned no allocation")?;
+    eprintln!(
+        "  Agent allocated {} ({} GRT)",
+        alloc.id, alloc.allocated_tokens
+    );
+    assert_ne!(alloc.id, active.id, "should be a new allocation");
+
+    // Restore what start-indexing configured
+    let mut restore = IndexingRuleInput::deployment(deployment).decision_basis(
+        original
+            .as_ref()
+            .map_or(DecisionBasis::Always, |r| r.decision_basis),
+    );
+    restore.allocation_amount = original.as_ref().and_then(|r| r.allocation_amount);
+    net.set_indexing_rule(&restore).await?;
+
+    Ok(())
+}
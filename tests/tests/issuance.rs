@@ -0,0 +1,103 @@
+//! Issuance Configuration Tests (RewardsManager / IssuanceAllocator)
+//!
+//!   - `issuance_configuration_is_consistent` → RewardsManager's rate matches its
+//!     allocator target (or its own `issuancePerBlock` without an allocator)
+//!   - `distributions_go_to_allocator_minting_targets` → `distributeIssuance()` only
+//!     mints to targets with an allocator-minting rate
+//!
+//! Without an IssuanceAllocator in issuance.json the allocator checks are
+//! skipped.
+
+use anyhow::{Context, Result};
+use local_network_tests::{Grt, TestNetwork};
+use serial_test::serial;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+#[tokio::test]
+async fn issuance_configuration_is_consistent() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Issuance Configuration ===");
+
+    let config = net.issuance_config()?;
+    eprintln!(
+        "  Allocator: {:?}, issuance {} GRT/block, RewardsManager {} GRT/block",
+        config.allocator, config.issuance_per_block, config.rewards_manager_rate
+    );
+    for target in &config.targets {
+        eprintln!(
+            "  Target {}: allocator-minting {} GRT/block, self-minting {} GRT/block",
+            target.address, target.allocator_minting_rate, target.self_minting_rate
+        );
+    }
+
+    // graph-contracts sets a non-zero rate for reward testing
+    assert!(
+        config.rewards_accrue(),
+        "RewardsManager should get issuance"
+    );
+    if !config.uses_allocator() {
+        assert!(config.targets.is_empty());
+        assert_eq!(config.rewards_manager_rate, config.issuance_per_block);
+        return Ok(());
+    }
+
+    let allocated = config
+        .targets
+        .iter()
+        .fold(Grt::ZERO, |total, t| total + t.total_rate());
+    eprintln!("  Unallocated: {} GRT/block", config.unallocated_rate());
+    assert!(
+        allocated <= config.issuance_per_block,
+        "targets are allocated {allocated} of {} GRT/block",
+        config.issuance_per_block
+    );
+    let rewards_manager = config
+        .target(net.contracts.rewards_manager)
+        .context("RewardsManager is not an allocator target")?;
+    assert_eq!(rewards_manager.total_rate(), config.rewards_manager_rate);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn distributions_go_to_allocator_minting_targets() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Issuance Distribution ===");
+
+    let config = net.issuance_config()?;
+    if !config.uses_allocator() {
+        eprintln!("  SKIP: no IssuanceAllocator deployed");
+        return Ok(());
+    }
+
+    let from_block = net.get_block_number_sync()?;
+    net.mine_blocks(5).await?;
+    net.distribute_issuance()?;
+    let to_block = net.get_block_number_sync()?;
+
+    let distributions = net.issuance_distributions(from_block + 1, to_block)?;
+    for d in &distributions {
+        eprintln!("  Block {}: {} GRT to {}", d.block, d.amount, d.target);
+        let target = config.target(d.target);
+        assert!(
+            target.is_some_and(|t| !t.allocator_minting_rate.is_zero()),
+            "{} received allocator-minted issuance without an allocator-minting rate",
+            d.target
+        );
+    }
+    let minting = config
+        .targets
+        .iter()
+        .any(|t| !t.allocator_minting_rate.is_zero());
+    assert_eq!(
+        !distributions.is_empty(),
+        minting,
+        "distributeIssuance should mint exactly when a target is allocator-minting"
+    );
+
+    Ok(())
+}
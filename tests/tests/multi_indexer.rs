@@ -0,0 +1,127 @@
+//! Multi-Indexer Tests (spawned on-chain indexers)
+//!
+//! A second indexer is created on demand with `spawn_indexer`: funded, staked,
+//! provisioned and registered with SubgraphService, but without an agent or
+//! indexer-service. It allocates directly with `start_service`.
+//!
+//!   - `spawned_indexer_is_provisioned_and_registered` → provision + `Indexer` entity
+//!   - `competing_allocations_split_rewards` → equal allocations by two indexers earn equal rewards
+//!
+//! Spawned indexers close every allocation they open; their stake stays
+//! provisioned (a fresh indexer is spawned per test).
+
+use anyhow::{Context, Result};
+use local_network_tests::allocation::AllocationKey;
+use local_network_tests::fixtures::{ALLOCATION_AMOUNT, INDEX_TIMEOUT};
+use local_network_tests::polling::PollResult;
+use local_network_tests::{Grt, TestNetwork};
+use serial_test::serial;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Spawn an indexer with the minimum SubgraphService provision.
+fn spawn(net: &TestNetwork) -> Result<TestNetwork> {
+    let (minimum, _) = net.provision_tokens_range()?;
+    let indexer = net.spawn_indexer(minimum.max(Grt::ONE))?;
+    eprintln!(
+        "  Spawned indexer {} ({} GRT provisioned)",
+        indexer.indexer_address,
+        indexer.provision()?.tokens
+    );
+    Ok(indexer)
+}
+
+#[tokio::test]
+#[serial]
+async fn spawned_indexer_is_provisioned_and_registered() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Spawn Indexer ===");
+
+    let indexer = spawn(&net)?;
+    assert_ne!(indexer.indexer_address, net.indexer_address);
+
+    let provision = indexer.provision()?;
+    let template = net.provision()?;
+    assert!(provision.exists());
+    assert_eq!(provision.max_verifier_cut, template.max_verifier_cut);
+    assert_eq!(provision.thawing_period, template.thawing_period);
+    assert_eq!(indexer.idle_stake()?, Grt::ZERO);
+
+    let result = net
+        .poll_until(INDEX_TIMEOUT, Duration::from_secs(2), || async {
+            let registration = net.indexer_registration(indexer.indexer_address).await;
+            Ok(registration.ok().filter(|r| !r.url.is_empty()))
+        })
+        .await;
+    match result {
+        PollResult::Ready(registration) => eprintln!("  Registered: {registration:?}"),
+        PollResult::TimedOut => {
+            anyhow::bail!("spawned indexer not indexed within {INDEX_TIMEOUT:?}")
+        }
+    }
+
+    Ok(())
+}
+
+/// Both indexers open an equal allocation on the same deployment at the same
+/// time and collect after the same number of epochs: rewards are split
+/// (almost) evenly.
+#[tokio::test]
+#[serial]
+async fn competing_allocations_split_rewards() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Competing Allocations ===");
+
+    let other = spawn(&net)?;
+    let deployment = net
+        .get_allocations()
+        .await?
+        .into_iter()
+        .find(|a| a.is_active())
+        .context("no active allocation found")?
+        .subgraph_deployment;
+
+    let (key, other_key) = (AllocationKey::random(), AllocationKey::random());
+    net.start_service(deployment, ALLOCATION_AMOUNT, &key)?;
+    let result: Result<_> = async {
+        other.start_service(deployment, ALLOCATION_AMOUNT, &other_key)?;
+        net.advance_epochs(2).await?;
+        if net.contracts.reo.is_some() {
+            net.reo_renew_batch(&[net.indexer_address, other.indexer_address])?;
+        }
+
+        let pending = net.rewards_pending(key.id())?;
+        let other_pending = net.rewards_pending(other_key.id())?;
+        eprintln!("  Pending: local {pending} GRT, spawned {other_pending} GRT");
+
+        let stake_before = other.staked_tokens()?;
+        other.collect_indexing_rewards(other_key.id())?;
+        let collected = other.staked_tokens()? - stake_before;
+        eprintln!("  Spawned indexer collected {collected} GRT");
+        Ok((pending, other_pending, collected))
+    }
+    .await;
+
+    // Close both allocations before surfacing any error
+    let stopped = net.stop_service(key.id());
+    let other_stopped = other.stop_service(other_key.id());
+    let (pending, other_pending, collected) = result?;
+    stopped?;
+    other_stopped?;
+
+    assert!(!pending.is_zero(), "allocations should accrue rewards");
+    // The second allocation opens a block later, so allow a small difference
+    assert!(
+        pending.wei().abs_diff(other_pending.wei()) <= pending.wei() / 20,
+        "equal allocations should accrue about equal rewards: {pending} vs {other_pending}"
+    );
+    assert!(
+        collected >= other_pending,
+        "collect should pay at least the pending rewards"
+    );
+
+    Ok(())
+}
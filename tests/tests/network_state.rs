@@ -8,12 +8,14 @@
 //!   - `provision_exists` → Cycle 1.3 + 3.1 (provision with tokensProvisioned)
 //!   - `active_allocations` → Cycle 4.1 (active allocations exist)
 //!   - `gateway_serves_queries` → Cycle 5.1 (gateway reachable)
+//!   - `gateway_classifies_failures` → Cycle 5.1 (bad API key / unknown subgraph)
 //!   - `indexer_health_metrics` → Cycle 6.1 (all expected fields populated)
 //!   - `epoch_progressing` → Cycle 6.2 (currentEpoch > 0)
 //!   - `reo_contract_state` → IndexerTestGuide prerequisites
 
 use anyhow::Result;
-use local_network_tests::TestNetwork;
+use local_network_tests::gateway::GatewayErrorKind;
+use local_network_tests::{B256, SubgraphId, TestNetwork};
 
 fn net() -> Result<TestNetwork> {
     TestNetwork::from_default_env()
@@ -25,7 +27,7 @@ fn net() -> Result<TestNetwork> {
 #[tokio::test]
 async fn indexer_registered() -> Result<()> {
     let net = net()?;
-    let indexer = net.query_indexer(&net.indexer_address).await?;
+    let indexer = net.query_indexer(net.indexer_address).await?;
 
     assert!(
         !indexer.is_null(),
@@ -54,7 +56,7 @@ async fn indexer_registered() -> Result<()> {
 #[tokio::test]
 async fn provision_exists() -> Result<()> {
     let net = net()?;
-    let provisions = net.query_provisions(&net.indexer_address).await?;
+    let provisions = net.query_provisions(net.indexer_address).await?;
     let provisions = provisions
         .as_array()
         .expect("provisions should be an array");
@@ -78,7 +80,7 @@ async fn provision_exists() -> Result<()> {
 #[tokio::test]
 async fn active_allocations() -> Result<()> {
     let net = net()?;
-    let allocs = net.query_active_allocations(&net.indexer_address).await?;
+    let allocs = net.query_active_allocations(net.indexer_address).await?;
     let allocs = allocs.as_array().expect("allocations should be an array");
 
     assert!(
@@ -101,7 +103,8 @@ async fn active_allocations() -> Result<()> {
 /// BaselineTestPlan 5.1: Gateway serves queries (reachability check).
 ///
 /// Full query success rate is tested in `allocation_lifecycle::gateway_query_serving`.
-/// This test confirms the gateway is reachable and returns valid JSON.
+/// This test confirms the gateway is reachable, accepts our API key and knows
+/// the subgraph. Indexer-side failures are tolerated here.
 #[tokio::test]
 async fn gateway_serves_queries() -> Result<()> {
     let net = net()?;
@@ -110,14 +113,52 @@ async fn gateway_serves_queries() -> Result<()> {
     let resp = net
         .gateway_query(r#"{ _meta { block { number } } }"#)
         .await?;
+    eprintln!(
+        "  Gateway: HTTP {}, error: {:?}",
+        resp.status, resp.gateway_error
+    );
+    assert!(
+        !matches!(
+            resp.error_kind(),
+            Some(GatewayErrorKind::Unauthorized | GatewayErrorKind::SubgraphNotFound)
+        ),
+        "Gateway should accept the configured API key and subgraph: {:?}",
+        resp.gateway_error
+    );
     assert!(
-        resp.status().is_success(),
-        "Gateway should return 200, got {}",
-        resp.status()
+        resp.data.is_some() || resp.gateway_error.is_some(),
+        "Gateway should return GraphQL JSON, got {}",
+        resp.body
     );
 
-    let body: serde_json::Value = resp.json().await?;
-    assert!(body.is_object(), "Gateway should return JSON, got {body}");
+    Ok(())
+}
+
+/// Gateway failure classification: misconfigured requests fail for the
+/// right reason rather than as generic errors.
+#[tokio::test]
+async fn gateway_classifies_failures() -> Result<()> {
+    let net = net()?;
+    let query = r#"{ _meta { block { number } } }"#;
+
+    let mut bad_key = net.clone();
+    bad_key.gateway_api_key = "00000000000000000000000000000000".into();
+    let resp = bad_key.gateway_query(query).await?;
+    eprintln!("  Bad API key: {:?}", resp.gateway_error);
+    assert_eq!(resp.error_kind(), Some(GatewayErrorKind::Unauthorized));
+
+    let mut no_key = net.clone();
+    no_key.gateway_api_key = String::new();
+    let resp = no_key.gateway_query(query).await?;
+    eprintln!("  Missing API key: {:?}", resp.gateway_error);
+    assert_eq!(resp.error_kind(), Some(GatewayErrorKind::Unauthorized));
+
+    // A well-formed subgraph ID that was never published
+    let mut unknown = net.clone();
+    unknown.subgraph_id = SubgraphId::new(B256::repeat_byte(0x11));
+    let resp = unknown.gateway_query(query).await?;
+    eprintln!("  Unknown subgraph: {:?}", resp.gateway_error);
+    assert_eq!(resp.error_kind(), Some(GatewayErrorKind::SubgraphNotFound));
 
     Ok(())
 }
@@ -129,7 +170,7 @@ async fn gateway_serves_queries() -> Result<()> {
 #[tokio::test]
 async fn indexer_health_metrics() -> Result<()> {
     let net = net()?;
-    let indexer = net.query_indexer(&net.indexer_address).await?;
+    let indexer = net.query_indexer(net.indexer_address).await?;
 
     assert!(!indexer.is_null(), "Indexer entity should exist");
 
@@ -209,7 +250,7 @@ async fn reo_contract_state() -> Result<()> {
         "Last oracle update time should be > 0, got {last_update}"
     );
 
-    let eligible = net.reo_is_eligible(&net.indexer_address)?;
+    let eligible = net.reo_is_eligible(net.indexer_address)?;
     eprintln!("  isEligible({}) = {eligible}", net.indexer_address);
 
     Ok(())
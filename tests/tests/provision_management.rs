@@ -7,10 +7,12 @@
 //! Mapping to BaselineTestPlan:
 //!   - `provision_lifecycle` → Cycle 3.2 (add) + 3.3 (thaw) + 3.4 (deprovision)
 //!
+//! Multiple interleaved thaw requests are covered by `thaw_requests.rs`.
+//!
 //! Note: Cycle 3.1 (view provision) is covered by `network_state::provision_exists`.
 
-use anyhow::Result;
-use local_network_tests::TestNetwork;
+use anyhow::{Context, Result};
+use local_network_tests::{Grt, TestNetwork};
 use serial_test::serial;
 
 fn net() -> Result<TestNetwork> {
@@ -22,8 +24,8 @@ fn net() -> Result<TestNetwork> {
 /// Runs as a single test since each step depends on the previous:
 ///   1. Add idle stake to provision (emulates `graph indexer provisions add`)
 ///   2. Thaw from provision (emulates `graph indexer provisions thaw`)
-///   3. Advance past thawing period
-///   4. Deprovision (emulates `graph indexer provisions remove`)
+///   3. Advance chain time to the thaw request's `thawingUntil`
+///   4. Deprovision the thawed requests (emulates `graph indexer provisions remove`)
 ///   5. Verify tokens return to idle stake
 #[tokio::test]
 #[serial]
@@ -31,13 +33,16 @@ async fn provision_lifecycle() -> Result<()> {
     let net = net()?;
     eprintln!("=== BaselineTestPlan 3.2-3.4: Provision Lifecycle ===");
 
-    let amount = "1000000000000000000000"; // 1000 GRT
+    let amount = Grt::from_grt(1000);
 
     // Add idle stake to work with
     net.stake_tokens(amount)?;
     let idle_before = net.idle_stake()?;
     eprintln!("  Idle stake: {idle_before}");
-    assert!(idle_before > 0, "Need idle stake for provision tests");
+    assert!(
+        !idle_before.is_zero(),
+        "Need idle stake for provision tests"
+    );
 
     // -- 3.2: Add to provision --
     // Emulates: graph indexer provisions add 1000
@@ -55,7 +60,7 @@ async fn provision_lifecycle() -> Result<()> {
     // Verify via subgraph (mine blocks to trigger indexing)
     net.mine_blocks(2).await?;
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-    let provisions = net.query_provisions(&net.indexer_address).await?;
+    let provisions = net.query_provisions(net.indexer_address).await?;
     let provisioned = provisions
         .as_array()
         .and_then(|p| p.first())
@@ -72,7 +77,7 @@ async fn provision_lifecycle() -> Result<()> {
     // Verify thawing state via subgraph
     net.mine_blocks(2).await?;
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-    let provisions = net.query_provisions(&net.indexer_address).await?;
+    let provisions = net.query_provisions(net.indexer_address).await?;
     let thawing = provisions
         .as_array()
         .and_then(|p| p.first())
@@ -84,23 +89,30 @@ async fn provision_lifecycle() -> Result<()> {
         "tokensThawing should be non-zero after thaw"
     );
 
-    // Get thawing period
-    let thawing_period = net.provision_thawing_period().await?;
-    eprintln!("  Thawing period: {thawing_period}s");
+    // The thaw request just created is the last in the list
+    let request = *net
+        .provision_thaw_requests()?
+        .last()
+        .context("thaw should create a thaw request")?;
+    eprintln!(
+        "  Thaw request {}: {} GRT until {}",
+        request.id, request.tokens, request.thawing_until
+    );
+    assert_eq!(request.tokens, amount);
 
     // -- 3.4: Deprovision after thawing period --
     // Emulates: graph indexer provisions remove (after waiting for thaw)
     eprintln!();
     eprintln!("--- 3.4: Deprovision ---");
-    if thawing_period > 0 {
-        eprintln!(
-            "  Advancing time by {}s to expire thawing period...",
-            thawing_period + 60
-        );
-        net.advance_time(thawing_period + 60).await?;
-    }
+    let now = net.advance_time_to(request.thawing_until).await?;
+    eprintln!("  Chain time advanced to {now}");
 
-    net.provision_deprovision(1)?;
+    let fulfilled = net.provision_deprovision_ready()?;
+    eprintln!("  Fulfilled {fulfilled} thaw request(s)");
+    assert!(
+        fulfilled >= 1,
+        "the expired thaw request should be fulfilled"
+    );
     let idle_final = net.idle_stake()?;
     eprintln!("  Idle stake after deprovision: {idle_final}");
 
@@ -0,0 +1,229 @@
+//! Provision Parameter Tests (BaselineTestPlan Cycle 3, Horizon provisions)
+//!
+//! Provision parameters change in two steps: the indexer stages them on
+//! HorizonStaking, then SubgraphService accepts them if they are within its
+//! verifier cut and thawing period ranges. SubgraphService's owner settings
+//! (minimum provision, delegation ratio) and per-data-service operators are
+//! covered here too.
+//!
+//!   - `provision_within_subgraph_service_ranges` → on-chain provision vs ranges and subgraph
+//!   - `stage_and_accept_provision_parameters` → staged, then active after acceptance
+//!   - `out_of_range_parameters_rejected` → acceptance reverts below the thawing range
+//!   - `minimum_provision_blocks_allocation` → `setMinimumProvisionTokens` enforced by `startService`
+//!   - `delegation_ratio_round_trip` → `setDelegationRatio`
+//!   - `operators_are_per_data_service` → `setOperator` scoped to one verifier
+//!
+//! Every mutating test restores the original values.
+
+use anyhow::{Context, Result};
+use local_network_tests::allocation::AllocationKey;
+use local_network_tests::fixtures::{ACCOUNT7, ALLOCATION_AMOUNT};
+use local_network_tests::governance::GovernanceParameter;
+use local_network_tests::{Address, Grt, TestNetwork};
+use serial_test::serial;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Operator for the indexer.
+const OPERATOR_ADDRESS: Address = ACCOUNT7.address;
+
+#[tokio::test]
+async fn provision_within_subgraph_service_ranges() -> Result<()> {
+    let net = net()?;
+    let provision = net.provision()?;
+    let (min_tokens, max_tokens) = net.provision_tokens_range()?;
+    let (min_cut, max_cut) = net.verifier_cut_range()?;
+    let (min_thawing, max_thawing) = net.thawing_period_range()?;
+
+    eprintln!("  Provision: {provision:?}");
+    eprintln!("  Tokens range: {min_tokens} - {max_tokens} GRT");
+    eprintln!("  Verifier cut range: {min_cut} - {max_cut} PPM");
+    eprintln!("  Thawing period range: {min_thawing} - {max_thawing}s");
+    eprintln!("  Delegation ratio: {}", net.delegation_ratio()?);
+
+    assert!(provision.exists());
+    assert!((min_tokens..=max_tokens).contains(&provision.tokens));
+    assert!((min_cut..=max_cut).contains(&provision.max_verifier_cut));
+    assert!((min_thawing..=max_thawing).contains(&provision.thawing_period));
+    assert_eq!(
+        net.provision_thawing_period().await?,
+        provision.thawing_period,
+        "subgraph thawingPeriod should match on-chain"
+    );
+
+    Ok(())
+}
+
+/// Staged parameters don't apply until SubgraphService accepts them.
+#[tokio::test]
+#[serial]
+async fn stage_and_accept_provision_parameters() -> Result<()> {
+    let net = net()?;
+    let original = net.provision()?;
+    let (min_thawing, max_thawing) = net.thawing_period_range()?;
+    let (min_cut, max_cut) = net.verifier_cut_range()?;
+
+    // Pick in-range values that differ from the current ones
+    let thawing_period = if original.thawing_period < max_thawing {
+        original.thawing_period + 1
+    } else {
+        min_thawing
+    };
+    let max_verifier_cut = if original.max_verifier_cut > min_cut {
+        original.max_verifier_cut - 1
+    } else {
+        max_cut
+    };
+    eprintln!("=== Provision Parameters ===");
+    eprintln!(
+        "  Active: cut {} PPM, thawing {}s → staging cut {max_verifier_cut} PPM, thawing {thawing_period}s",
+        original.max_verifier_cut, original.thawing_period
+    );
+
+    net.set_provision_parameters(max_verifier_cut, thawing_period)?;
+    let staged = net.provision()?;
+    assert_eq!(staged.max_verifier_cut, original.max_verifier_cut);
+    assert_eq!(staged.thawing_period, original.thawing_period);
+    assert_eq!(staged.max_verifier_cut_pending, max_verifier_cut);
+    assert_eq!(staged.thawing_period_pending, thawing_period);
+
+    net.accept_provision_parameters()?;
+    let accepted = net.provision()?;
+    eprintln!("  Accepted: {accepted:?}");
+
+    net.set_provision_parameters(original.max_verifier_cut, original.thawing_period)?;
+    net.accept_provision_parameters()?;
+    let restored = net.provision()?;
+
+    assert_eq!(accepted.max_verifier_cut, max_verifier_cut);
+    assert_eq!(accepted.thawing_period, thawing_period);
+    assert!(!accepted.has_pending_parameters());
+    assert_eq!(restored.max_verifier_cut, original.max_verifier_cut);
+    assert_eq!(restored.thawing_period, original.thawing_period);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn out_of_range_parameters_rejected() -> Result<()> {
+    let net = net()?;
+    let original = net.provision()?;
+    let (min_thawing, _) = net.thawing_period_range()?;
+    if min_thawing == 0 {
+        eprintln!("  SKIP: thawing period range starts at 0, nothing below it");
+        return Ok(());
+    }
+
+    net.set_provision_parameters(original.max_verifier_cut, min_thawing - 1)?;
+    let accepted = net.try_accept_provision_parameters()?;
+    eprintln!("  Thawing period {}s accepted: {accepted}", min_thawing - 1);
+
+    // Re-stage the original values so nothing is left pending
+    net.set_provision_parameters(original.max_verifier_cut, original.thawing_period)?;
+    if accepted {
+        net.accept_provision_parameters()?;
+    }
+    assert!(
+        !accepted,
+        "thawing period below the range should be rejected"
+    );
+    assert_eq!(net.provision()?.thawing_period, original.thawing_period);
+
+    Ok(())
+}
+
+/// Raising the minimum provision above the indexer's provision makes
+/// `startService` revert; restoring it allows allocations again.
+#[tokio::test]
+#[serial]
+async fn minimum_provision_blocks_allocation() -> Result<()> {
+    let net = net()?;
+    let (original_min, _) = net.provision_tokens_range()?;
+    let provision = net.provision()?;
+    let deployment = net
+        .get_allocations()
+        .await?
+        .into_iter()
+        .find(|a| a.is_active())
+        .context("no active allocation found")?
+        .subgraph_deployment;
+
+    let raised = provision.tokens + Grt::ONE;
+    let mut minimum = net.governance_override();
+    minimum.set(GovernanceParameter::MinimumProvisionTokens, raised)?;
+    let (min, _) = net.provision_tokens_range()?;
+    eprintln!("  Minimum provision raised to {min} GRT");
+
+    let key = AllocationKey::random();
+    let proof = key.proof(&net.allocation_proof_domain(), net.indexer_address)?;
+    let accepted = net.start_service_with_proof(
+        &net.receiver_secret,
+        deployment,
+        ALLOCATION_AMOUNT,
+        key.id(),
+        &proof,
+    )?;
+    minimum.restore()?;
+    if accepted {
+        net.stop_service(key.id())?;
+    }
+
+    assert_eq!(min, raised);
+    assert!(
+        !accepted,
+        "startService should revert with a provision below the minimum"
+    );
+    assert_eq!(net.provision_tokens_range()?.0, original_min);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn delegation_ratio_round_trip() -> Result<()> {
+    let net = net()?;
+    let original = net.delegation_ratio()?;
+    let updated = original + 1;
+
+    let mut ratio = net.governance_override();
+    ratio.set(GovernanceParameter::DelegationRatio, u64::from(updated))?;
+    let read = net.delegation_ratio()?;
+    ratio.restore()?;
+    eprintln!("  Delegation ratio {original} → {read} → restored");
+
+    assert_eq!(read, updated);
+    assert_eq!(net.delegation_ratio()?, original);
+
+    Ok(())
+}
+
+/// An operator authorized for one data service is not authorized for
+/// SubgraphService.
+#[tokio::test]
+#[serial]
+async fn operators_are_per_data_service() -> Result<()> {
+    let net = net()?;
+    let other_verifier = net.contracts.dispute_manager;
+
+    net.set_operator(OPERATOR_ADDRESS, false)?;
+    net.set_operator_for(other_verifier, OPERATOR_ADDRESS, true)?;
+    let for_other = net.is_authorized(other_verifier, OPERATOR_ADDRESS)?;
+    let for_subgraph_service = net.is_operator(OPERATOR_ADDRESS)?;
+    net.set_operator_for(other_verifier, OPERATOR_ADDRESS, false)?;
+    eprintln!(
+        "  Operator for {other_verifier}: {for_other}, for SubgraphService: {for_subgraph_service}"
+    );
+
+    assert!(for_other);
+    assert!(!for_subgraph_service);
+    assert!(!net.is_authorized(other_verifier, OPERATOR_ADDRESS)?);
+    assert!(
+        net.is_authorized(other_verifier, net.indexer_address)?,
+        "the indexer is always authorized for itself"
+    );
+
+    Ok(())
+}
@@ -6,6 +6,8 @@
 //! Mapping to BaselineTestPlan:
 //!   - `gateway_queries_generate_tap_receipts` → Cycle 5.1 (send test queries, verify receipts)
 //!   - `tap_escrow_state_observable` → Cycle 5.3 (verify query fee collection state)
+//!   - `gateway_sustained_load` → Cycle 5.1 (receipt volume under concurrent load)
+//!   - `gateway_attestations_match_active_allocation` → Cycle 5.1 (attestation signer)
 //!
 //! The local network runs the full TAP stack: gateway, tap-aggregator,
 //! tap-escrow-manager, tap-agent, and redpanda (Kafka). Query fees are
@@ -14,6 +16,11 @@
 
 use anyhow::Result;
 use local_network_tests::TestNetwork;
+use local_network_tests::attestation::AttestationMismatch;
+use local_network_tests::gateway::GatewayErrorKind;
+use local_network_tests::load::{LoadConfig, META_QUERY};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 fn net() -> Result<TestNetwork> {
     TestNetwork::from_default_env()
@@ -30,14 +37,81 @@ async fn gateway_queries_generate_tap_receipts() -> Result<()> {
 
     eprintln!("=== TAP Receipt Generation Test ===");
 
-    // Send a batch of queries through the gateway
-    let (ok, fail) = net.send_gateway_queries(20).await?;
-    eprintln!("  Gateway queries: {ok} OK, {fail} failed (out of 20)");
+    // Send a batch of queries through the gateway, keeping failure reasons
+    let mut ok = 0;
+    let mut failures: BTreeMap<GatewayErrorKind, usize> = BTreeMap::new();
+    for _ in 0..20 {
+        let resp = net.gateway_query(META_QUERY).await?;
+        match resp.error_kind() {
+            None if resp.is_success() => ok += 1,
+            None => eprintln!("  GraphQL errors: {:?}", resp.graphql_errors),
+            Some(kind) => *failures.entry(kind).or_default() += 1,
+        }
+    }
+    eprintln!("  Gateway queries: {ok} OK out of 20, failures: {failures:?}");
 
-    // At least some should succeed (attestation signer may be stale for some)
+    // Configuration errors are never acceptable; indexer-side failures
+    // (e.g. a stale attestation signer right after reallocation) are
+    // tolerated as long as some queries get through.
+    for kind in [
+        GatewayErrorKind::Unauthorized,
+        GatewayErrorKind::SubgraphNotFound,
+        GatewayErrorKind::BadQuery,
+    ] {
+        assert!(
+            !failures.contains_key(&kind),
+            "gateway misconfigured: {failures:?}"
+        );
+    }
     assert!(
         ok >= 1,
-        "At least 1 gateway query should succeed, got {ok} OK / {fail} failed"
+        "At least 1 gateway query should succeed, got {ok} OK, failures: {failures:?}"
+    );
+
+    Ok(())
+}
+
+/// BaselineTestPlan 5.1: Attestations are signed by an active allocation.
+///
+/// Every attested gateway response must attest to the subgraph's current
+/// deployment and recover to an allocation that is active on it and owned by
+/// an indexer. A closed allocation as signer means indexer-service is still
+/// using a rotated-out allocation key (signer drift).
+#[tokio::test]
+async fn gateway_attestations_match_active_allocation() -> Result<()> {
+    let net = net()?;
+
+    eprintln!("=== Gateway Attestation Verification Test ===");
+
+    let deployment = net.gateway_deployment().await?;
+    eprintln!("  Expecting attestations for {deployment}");
+
+    let mut verified = 0;
+    for _ in 0..10 {
+        let resp = net.gateway_query(META_QUERY).await?;
+        if !resp.is_success() || resp.attestation.is_none() {
+            eprintln!("  skipped: {:?}", resp.gateway_error);
+            continue;
+        }
+        match net.verify_gateway_attestation(&resp, deployment).await {
+            Ok(v) => {
+                eprintln!(
+                    "  {} signed by allocation {} (indexer {})",
+                    v.attestation.deployment_id(),
+                    v.allocation,
+                    v.indexer
+                );
+                verified += 1;
+            }
+            Err(e) => match e.downcast_ref::<AttestationMismatch>() {
+                Some(mismatch) => panic!("attestation mismatch: {mismatch}"),
+                None => return Err(e),
+            },
+        }
+    }
+    assert!(
+        verified >= 1,
+        "at least one attested response should verify"
     );
 
     Ok(())
@@ -76,12 +150,12 @@ async fn tap_escrow_state_observable() -> Result<()> {
     // Check on-chain escrow balance directly
     // getBalance(payer, collector, receiver) — collector is the SubgraphService
     let escrow_balance = net.cast_call(
-        &net.contracts.payments_escrow,
+        net.contracts.payments_escrow,
         "getBalance(address,address,address)(uint256)",
         &[
             "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266", // payer (gateway/account0)
-            &net.contracts.subgraph_service,              // collector
-            &net.indexer_address,                         // receiver (indexer)
+            &net.contracts.subgraph_service.to_string(),  // collector
+            &net.indexer_address.to_string(),             // receiver (indexer)
         ],
     );
     match escrow_balance {
@@ -94,3 +168,41 @@ async fn tap_escrow_state_observable() -> Result<()> {
     // doesn't error out (services are reachable).
     Ok(())
 }
+
+/// BaselineTestPlan 5.1 under load: sustained gateway traffic.
+///
+/// Drives ~10 queries/s for 10s so the TAP pipeline sees a realistic receipt
+/// volume, then checks the success ratio and that the local indexer served
+/// (signed attestations for) at least part of the traffic.
+#[tokio::test]
+async fn gateway_sustained_load() -> Result<()> {
+    let net = net()?;
+
+    eprintln!("=== Gateway Sustained Load Test ===");
+
+    let config = LoadConfig::rate(10.0, Duration::from_secs(10));
+    let report = net.run_gateway_load(&config).await?;
+    eprint!("  {report}");
+
+    assert!(
+        report.total() >= 50,
+        "expected ~100 queries, sent {}",
+        report.total()
+    );
+    assert!(
+        report.success_ratio() >= 0.8,
+        "at least 80% of queries should succeed:\n{report}"
+    );
+    let served = report
+        .served_by
+        .get(&net.indexer_address)
+        .copied()
+        .unwrap_or(0);
+    assert!(
+        served > 0,
+        "local indexer {} should serve attested queries:\n{report}",
+        net.indexer_address
+    );
+
+    Ok(())
+}
@@ -24,7 +24,10 @@
 //!   - `access_control_unauthorized` → Cycle 7.3
 
 use anyhow::{Context, Result};
-use local_network_tests::TestNetwork;
+use local_network_tests::fixtures::{ACCOUNT4, ACCOUNT9};
+use local_network_tests::governance::GovernanceParameter;
+use local_network_tests::ids::address;
+use local_network_tests::{Address, Grt, TestNetwork};
 use serial_test::serial;
 
 fn net() -> Result<TestNetwork> {
@@ -32,8 +35,7 @@ fn net() -> Result<TestNetwork> {
 }
 
 /// A private key for an account with NO roles on the REO contract.
-/// Hardhat account #9 — has ETH but no governance roles.
-const UNAUTHORIZED_KEY: &str = "0x2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6";
+const UNAUTHORIZED_KEY: &str = ACCOUNT9.key;
 
 // ── Cycle 1: Deployment Verification ──
 
@@ -69,8 +71,8 @@ async fn deployment_parameters() -> Result<()> {
 #[serial]
 async fn rewards_manager_integration() -> Result<()> {
     let net = net()?;
-    let reo = match &net.contracts.reo {
-        Some(addr) => addr.clone(),
+    let reo = match net.contracts.reo {
+        Some(addr) => addr,
         None => {
             eprintln!("REO not deployed, skipping");
             return Ok(());
@@ -84,8 +86,7 @@ async fn rewards_manager_integration() -> Result<()> {
     eprintln!("  Expected REO address: {reo}");
 
     assert_eq!(
-        configured_reo.to_lowercase(),
-        reo.to_lowercase(),
+        configured_reo, reo,
         "RewardsManager should point to the REO contract"
     );
 
@@ -118,8 +119,8 @@ async fn contract_not_paused() -> Result<()> {
 #[serial]
 async fn renew_single_indexer() -> Result<()> {
     let net = net()?;
-    let reo = match &net.contracts.reo {
-        Some(addr) => addr.clone(),
+    let reo = match net.contracts.reo {
+        Some(addr) => addr,
         None => {
             eprintln!("REO not deployed, skipping");
             return Ok(());
@@ -129,16 +130,16 @@ async fn renew_single_indexer() -> Result<()> {
     eprintln!("=== ReoTestPlan 3.2: Renew Single Indexer ===");
 
     let before_oracle = net.reo_last_oracle_update()?;
-    let before_renewal = net.reo_renewal_time(&net.indexer_address)?;
+    let before_renewal = net.reo_renewal_time(net.indexer_address)?;
 
     // Record block before renewal for event verification
     let block_before = net.get_block_number_sync()?;
 
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
 
     let block_after = net.get_block_number_sync()?;
     let after_oracle = net.reo_last_oracle_update()?;
-    let after_renewal = net.reo_renewal_time(&net.indexer_address)?;
+    let after_renewal = net.reo_renewal_time(net.indexer_address)?;
 
     eprintln!("  lastOracleUpdateTime: {before_oracle} → {after_oracle}");
     eprintln!("  renewalTime({}):", net.indexer_address);
@@ -156,12 +157,12 @@ async fn renew_single_indexer() -> Result<()> {
     );
 
     assert!(
-        net.reo_is_eligible(&net.indexer_address)?,
+        net.reo_is_eligible(net.indexer_address)?,
         "Indexer should be eligible after renewal"
     );
 
     // Event verification: check for IndexerEligibilityRenewed event
-    let logs = net.cast_logs_json(&reo, block_before, block_after)?;
+    let logs = net.cast_logs_json(reo, block_before, block_after)?;
     eprintln!(
         "  Events emitted: {} log(s) in blocks {block_before}..{block_after}",
         logs.len()
@@ -177,7 +178,7 @@ async fn renew_single_indexer() -> Result<()> {
             .as_array()
             .and_then(|t| t.first())
             .and_then(|t| t.as_str())
-            .is_some_and(|t| t == renewed_topic)
+            .is_some_and(|t| t == renewed_topic.to_string())
     });
     eprintln!("  IndexerEligibilityRenewed event: {has_renewed_event}");
     assert!(
@@ -201,9 +202,9 @@ async fn batch_renewal() -> Result<()> {
     eprintln!("=== ReoTestPlan 3.3: Batch Renewal ===");
 
     // Use the indexer plus two arbitrary addresses
-    let addr1 = &net.indexer_address;
-    let addr2 = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"; // Hardhat #1
-    let addr3 = "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"; // Hardhat #2
+    let addr1 = net.indexer_address;
+    let addr2 = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"); // Hardhat #1
+    let addr3 = address!("0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"); // Hardhat #2
 
     net.reo_renew_batch(&[addr1, addr2, addr3])?;
 
@@ -233,12 +234,12 @@ async fn zero_address_skipped() -> Result<()> {
 
     eprintln!("=== ReoTestPlan 3.4: Zero Address Skipped ===");
 
-    let zero = "0x0000000000000000000000000000000000000000";
+    let zero = Address::ZERO;
     // Should succeed (zero address is silently skipped)
-    net.reo_renew_batch(&[zero, &net.indexer_address])?;
+    net.reo_renew_batch(&[zero, net.indexer_address])?;
 
     let zero_time = net.reo_renewal_time(zero)?;
-    let indexer_time = net.reo_renewal_time(&net.indexer_address)?;
+    let indexer_time = net.reo_renewal_time(net.indexer_address)?;
     eprintln!("  renewalTime(zero): {zero_time}");
     eprintln!("  renewalTime(indexer): {indexer_time}");
 
@@ -253,8 +254,8 @@ async fn zero_address_skipped() -> Result<()> {
 #[serial]
 async fn unauthorized_renewal_reverts() -> Result<()> {
     let net = net()?;
-    let reo = match &net.contracts.reo {
-        Some(addr) => addr.clone(),
+    let reo = match net.contracts.reo {
+        Some(addr) => addr,
         None => {
             eprintln!("REO not deployed, skipping");
             return Ok(());
@@ -266,7 +267,7 @@ async fn unauthorized_renewal_reverts() -> Result<()> {
     let array = format!("[{}]", net.indexer_address);
     let succeeded = net.cast_send_may_revert(
         UNAUTHORIZED_KEY,
-        &reo,
+        reo,
         "renewIndexerEligibility(address[],bytes)",
         &[&array, "0x"],
     )?;
@@ -299,7 +300,7 @@ async fn enable_validation_eligible_stays() -> Result<()> {
     let original = net.reo_validation_enabled()?;
 
     // Ensure indexer is renewed
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
 
     // Enable validation
     net.reo_set_validation(true)?;
@@ -309,7 +310,7 @@ async fn enable_validation_eligible_stays() -> Result<()> {
     );
 
     // Renewed indexer should still be eligible
-    let eligible = net.reo_is_eligible(&net.indexer_address)?;
+    let eligible = net.reo_is_eligible(net.indexer_address)?;
     eprintln!("  isEligible after enabling validation: {eligible}");
     assert!(
         eligible,
@@ -341,28 +342,29 @@ async fn eligibility_expires_after_period() -> Result<()> {
 
     // Enable validation and set short period
     net.reo_set_validation(true)?;
-    net.reo_set_eligibility_period(60)?;
+    let mut period = net.governance_override();
+    period.set(GovernanceParameter::EligibilityPeriod, 60u64)?;
     eprintln!("  Set eligibilityPeriod to 60s");
 
     // Renew indexer
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     assert!(
-        net.reo_is_eligible(&net.indexer_address)?,
+        net.reo_is_eligible(net.indexer_address)?,
         "Should be eligible immediately after renewal"
     );
 
     // Advance past the 60s period
     net.advance_time(65).await?;
 
-    let eligible = net.reo_is_eligible(&net.indexer_address)?;
+    let eligible = net.reo_is_eligible(net.indexer_address)?;
     eprintln!("  isEligible after 65s: {eligible}");
     assert!(!eligible, "Should be ineligible after period expires");
 
     // Restore original state
-    net.reo_set_eligibility_period(original_period)?;
+    period.restore()?;
     net.reo_set_validation(original_validation)?;
     // Re-renew to restore eligibility
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     eprintln!("  Restored period={original_period}s, validation={original_validation}");
 
     Ok(())
@@ -389,13 +391,13 @@ async fn timeout_failopen() -> Result<()> {
     let original_validation = net.reo_validation_enabled()?;
 
     // Use an address that has never been renewed
-    let never_renewed = "0x15d34AAf54267DB7D7c367839AAf71A00a2C6A65"; // Hardhat #4
+    let never_renewed = ACCOUNT4.address;
 
     // Enable validation so non-renewed addresses are ineligible
     net.reo_set_validation(true)?;
 
     // Renew the main indexer (to set lastOracleUpdateTime)
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
 
     // Verify the never-renewed address is NOT eligible
     let before = net.reo_is_eligible(never_renewed)?;
@@ -403,7 +405,8 @@ async fn timeout_failopen() -> Result<()> {
     assert!(!before, "Never-renewed address should be ineligible");
 
     // Reduce timeout to 60s and wait
-    net.reo_set_oracle_timeout(60)?;
+    let mut timeout = net.governance_override();
+    timeout.set(GovernanceParameter::OracleUpdateTimeout, 60u64)?;
     eprintln!("  Set oracleUpdateTimeout to 60s");
 
     net.advance_time(65).await?;
@@ -417,9 +420,9 @@ async fn timeout_failopen() -> Result<()> {
     );
 
     // Restore
-    net.reo_set_oracle_timeout(original_timeout)?;
+    timeout.restore()?;
     net.reo_set_validation(original_validation)?;
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     eprintln!("  Restored timeout={original_timeout}s, validation={original_validation}");
 
     Ok(())
@@ -446,7 +449,7 @@ async fn oracle_renewal_resets_timeout() -> Result<()> {
     net.advance_time(30).await?;
 
     // Renew — this should update lastOracleUpdateTime
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
 
     let after = net.reo_last_oracle_update()?;
     let ts_after = net.get_block_timestamp()?;
@@ -470,8 +473,8 @@ async fn oracle_renewal_resets_timeout() -> Result<()> {
 #[serial]
 async fn pause_blocks_writes() -> Result<()> {
     let net = net()?;
-    let reo = match &net.contracts.reo {
-        Some(addr) => addr.clone(),
+    let reo = match net.contracts.reo {
+        Some(addr) => addr,
         None => {
             eprintln!("REO not deployed, skipping");
             return Ok(());
@@ -486,7 +489,7 @@ async fn pause_blocks_writes() -> Result<()> {
     eprintln!("  Paused: true");
 
     // View functions should still work
-    let eligible = net.reo_is_eligible(&net.indexer_address)?;
+    let eligible = net.reo_is_eligible(net.indexer_address)?;
     eprintln!("  isEligible (while paused): {eligible}");
     // No assertion on the value — just that it doesn't revert
 
@@ -494,7 +497,7 @@ async fn pause_blocks_writes() -> Result<()> {
     let array = format!("[{}]", net.indexer_address);
     let succeeded = net.cast_send_may_revert(
         &net.account0_secret,
-        &reo,
+        reo,
         "renewIndexerEligibility(address[],bytes)",
         &[&array, "0x"],
     )?;
@@ -510,7 +513,7 @@ async fn pause_blocks_writes() -> Result<()> {
     eprintln!("  Unpaused: true");
 
     // Writes should work again
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     eprintln!("  Renewal after unpause: OK");
 
     Ok(())
@@ -534,9 +537,9 @@ async fn disable_validation_emergency() -> Result<()> {
     net.reo_set_validation(true)?;
 
     // An address that was never renewed should be ineligible
-    let never_renewed = "0x15d34AAf54267DB7D7c367839AAf71A00a2C6A65";
+    let never_renewed = address!("0x15d34AAf54267DB7D7c367839AAf71A00a2C6A65");
     // Renew the main indexer so lastOracleUpdateTime is fresh (prevent fail-open)
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
 
     let before = net.reo_is_eligible(never_renewed)?;
     eprintln!("  isEligible({never_renewed}) with validation on: {before}");
@@ -557,7 +560,7 @@ async fn disable_validation_emergency() -> Result<()> {
 
     // Restore
     net.reo_set_validation(original)?;
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
 
     Ok(())
 }
@@ -567,8 +570,8 @@ async fn disable_validation_emergency() -> Result<()> {
 #[serial]
 async fn access_control_unauthorized() -> Result<()> {
     let net = net()?;
-    let reo = match &net.contracts.reo {
-        Some(addr) => addr.clone(),
+    let reo = match net.contracts.reo {
+        Some(addr) => addr,
         None => {
             eprintln!("REO not deployed, skipping");
             return Ok(());
@@ -580,7 +583,7 @@ async fn access_control_unauthorized() -> Result<()> {
     // Non-operator cannot set eligibility period
     let ok = net.cast_send_may_revert(
         UNAUTHORIZED_KEY,
-        &reo,
+        reo,
         "setEligibilityPeriod(uint256)",
         &["100"],
     )?;
@@ -590,7 +593,7 @@ async fn access_control_unauthorized() -> Result<()> {
     // Non-operator cannot enable validation
     let ok = net.cast_send_may_revert(
         UNAUTHORIZED_KEY,
-        &reo,
+        reo,
         "setEligibilityValidation(bool)",
         &["true"],
     )?;
@@ -601,14 +604,14 @@ async fn access_control_unauthorized() -> Result<()> {
     );
 
     // Non-pause-role cannot pause
-    let ok = net.cast_send_may_revert(UNAUTHORIZED_KEY, &reo, "pause()", &[])?;
+    let ok = net.cast_send_may_revert(UNAUTHORIZED_KEY, reo, "pause()", &[])?;
     eprintln!("  pause (unauthorized): succeeded={ok}");
     assert!(!ok, "pause should revert for non-pause-role");
 
     // Non-operator cannot set oracle timeout
     let ok = net.cast_send_may_revert(
         UNAUTHORIZED_KEY,
-        &reo,
+        reo,
         "setOracleUpdateTimeout(uint256)",
         &["100"],
     )?;
@@ -643,17 +646,18 @@ async fn rewards_view_zero_for_ineligible() -> Result<()> {
 
     // Enable validation and renew so indexer starts eligible
     net.reo_set_validation(true)?;
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     assert!(
-        net.reo_is_eligible(&net.indexer_address)?,
+        net.reo_is_eligible(net.indexer_address)?,
         "Indexer should be eligible after renewal"
     );
 
     // Get an active allocation
-    let allocs = net.query_active_allocations(&net.indexer_address).await?;
+    let allocs = net.query_active_allocations(net.indexer_address).await?;
     let allocs = allocs.as_array().context("expected allocation array")?;
     let active = allocs.first().context("no active allocation found")?;
-    let alloc_id = active["id"].as_str().context("allocation missing id")?;
+    let alloc_id: Address =
+        serde_json::from_value(active["id"].clone()).context("parsing allocation id")?;
     eprintln!("  Active allocation: {alloc_id}");
 
     // Check rewards while eligible — may be non-zero
@@ -661,11 +665,12 @@ async fn rewards_view_zero_for_ineligible() -> Result<()> {
     eprintln!("  Pending rewards (eligible): {rewards_eligible}");
 
     // Make indexer ineligible: set short period and advance time
-    net.reo_set_eligibility_period(60)?;
+    let mut period = net.governance_override();
+    period.set(GovernanceParameter::EligibilityPeriod, 60u64)?;
     net.advance_time(65).await?;
 
     assert!(
-        !net.reo_is_eligible(&net.indexer_address)?,
+        !net.reo_is_eligible(net.indexer_address)?,
         "Indexer should be ineligible after period expiry"
     );
 
@@ -674,14 +679,15 @@ async fn rewards_view_zero_for_ineligible() -> Result<()> {
     eprintln!("  Pending rewards (ineligible): {rewards_ineligible}");
 
     assert_eq!(
-        rewards_ineligible, 0,
+        rewards_ineligible,
+        Grt::ZERO,
         "getRewards() should return 0 for ineligible indexer, got {rewards_ineligible}"
     );
 
     // Restore original state
-    net.reo_set_eligibility_period(original_period)?;
+    period.restore()?;
     net.reo_set_validation(original_validation)?;
-    net.reo_renew_indexer(&net.indexer_address)?;
+    net.reo_renew_indexer(net.indexer_address)?;
     eprintln!("  Restored period={original_period}s, validation={original_validation}");
 
     Ok(())
@@ -12,6 +12,7 @@
 
 use anyhow::{Context, Result};
 use local_network_tests::TestNetwork;
+use local_network_tests::fixtures::ALLOCATION_AMOUNT;
 use serial_test::serial;
 
 fn net() -> Result<TestNetwork> {
@@ -30,19 +31,12 @@ async fn collect_indexing_rewards_increases_stake() -> Result<()> {
 
     // Find an active allocation
     let allocs = net.get_allocations().await?;
-    let allocs = allocs.as_array().context("expected allocation array")?;
     let active = allocs
         .iter()
-        .find(|a| a["closedAtEpoch"].is_null())
+        .find(|a| a.is_active())
         .context("no active allocation found")?;
-    let alloc_id = active["id"]
-        .as_str()
-        .context("allocation missing id")?
-        .to_string();
-    let deployment = active["subgraphDeployment"]
-        .as_str()
-        .context("allocation missing deployment")?
-        .to_string();
+    let alloc_id = active.id;
+    let deployment = active.subgraph_deployment;
 
     eprintln!("=== Reward collection test ===");
     eprintln!("  Allocation: {alloc_id}");
@@ -50,13 +44,10 @@ async fn collect_indexing_rewards_increases_stake() -> Result<()> {
 
     // Close and recreate so we have a fresh allocation with known epoch boundaries
     net.advance_epochs(2).await?;
-    net.close_allocation(&alloc_id).await?;
+    net.close_allocation(alloc_id).await?;
 
-    let result = net.create_allocation(&deployment, "0.01").await?;
-    let fresh_alloc = result["allocation"]
-        .as_str()
-        .context("expected allocation ID")?
-        .to_string();
+    let result = net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
+    let fresh_alloc = result.allocation;
     eprintln!("  Fresh allocation: {fresh_alloc}");
 
     // Advance epochs so rewards accumulate (need > 1 epoch for allocation maturity)
@@ -64,9 +55,9 @@ async fn collect_indexing_rewards_increases_stake() -> Result<()> {
 
     // Ensure indexer is eligible (eligibility may have expired during epoch advancement)
     if net.contracts.reo.is_some() {
-        net.reo_renew_indexer(&net.indexer_address)?;
+        net.reo_renew_indexer(net.indexer_address)?;
         assert!(
-            net.reo_is_eligible(&net.indexer_address)?,
+            net.reo_is_eligible(net.indexer_address)?,
             "Indexer must be eligible to collect rewards"
         );
     }
@@ -77,14 +68,13 @@ async fn collect_indexing_rewards_increases_stake() -> Result<()> {
 
     // Call collect(IndexingRewards) — this is the key operation
     eprintln!("  Calling collect(IndexingRewards)...");
-    net.collect_indexing_rewards(&fresh_alloc)?;
+    net.collect_indexing_rewards(fresh_alloc)?;
 
     // Record stake after collect
     let stake_after = net.staked_tokens()?;
     let reward_delta = stake_after.saturating_sub(stake_before);
-    let reward_grt = reward_delta as f64 / 1e18;
     eprintln!("  Stake after collect: {stake_after}");
-    eprintln!("  Reward delta: {reward_delta} wei ({reward_grt:.2} GRT)");
+    eprintln!("  Reward delta: {reward_delta} GRT");
 
     assert!(
         stake_after > stake_before,
@@ -95,10 +85,10 @@ async fn collect_indexing_rewards_increases_stake() -> Result<()> {
     // Restore: close the fresh allocation (if still open) and recreate.
     // The collect() call or the indexer-agent may have auto-closed it.
     net.advance_epochs(2).await?;
-    if let Err(e) = net.close_allocation(&fresh_alloc).await {
+    if let Err(e) = net.close_allocation(fresh_alloc).await {
         eprintln!("  Close skipped (already closed): {e:#}");
     }
-    net.create_allocation(&deployment, "0.01").await?;
+    net.create_allocation(deployment, ALLOCATION_AMOUNT).await?;
     eprintln!("  Restored allocation for {deployment}");
 
     Ok(())
@@ -0,0 +1,130 @@
+//! Exact Rewards Tests (off-chain RewardsManager model)
+//!
+//! A spawned indexer (no delegators, so its stake receives the full reward)
+//! allocates on an indexed deployment and the `rewards_model` prediction is
+//! compared with the chain to the wei:
+//!
+//!   - `pending_rewards_match_model` → `getRewards` equals the model at the same block
+//!   - `collect_pays_predicted_rewards` → two consecutive collections each add exactly
+//!     the predicted reward to the indexer's stake
+//!
+//! Predictions for a transaction use a snapshot of the block before its
+//! receipt block, so concurrent agent activity on the deployment is
+//! accounted for.
+
+use anyhow::{Context, Result};
+use local_network_tests::allocation::AllocationKey;
+use local_network_tests::cast::cast_receipt_block;
+use local_network_tests::fixtures::ALLOCATION_AMOUNT;
+use local_network_tests::{DeploymentId, Grt, TestNetwork};
+use serial_test::serial;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+/// Spawn an indexer with the minimum SubgraphService provision and open an
+/// allocation on a deployment the local indexer allocates to.
+async fn allocate(net: &TestNetwork) -> Result<(TestNetwork, DeploymentId, AllocationKey)> {
+    let (minimum, _) = net.provision_tokens_range()?;
+    let indexer = net.spawn_indexer(minimum.max(Grt::ONE))?;
+    let deployment = net
+        .get_allocations()
+        .await?
+        .into_iter()
+        .find(|a| a.is_active())
+        .context("no active allocation found")?
+        .subgraph_deployment;
+    let key = AllocationKey::random();
+    indexer.start_service(deployment, ALLOCATION_AMOUNT, &key)?;
+    eprintln!(
+        "  {} allocated {ALLOCATION_AMOUNT} GRT on {deployment}",
+        indexer.indexer_address
+    );
+    Ok((indexer, deployment, key))
+}
+
+/// Advance two epochs and renew REO eligibility of `indexer`.
+async fn accrue(net: &TestNetwork, indexer: &TestNetwork) -> Result<()> {
+    net.advance_epochs(2).await?;
+    if net.contracts.reo.is_some() {
+        net.reo_renew_indexer(indexer.indexer_address)?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn pending_rewards_match_model() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Pending Rewards vs Model ===");
+
+    let (indexer, deployment, key) = allocate(&net).await?;
+    let result = check_pending(&net, &indexer, deployment, &key).await;
+    indexer.stop_service(key.id())?;
+    result
+}
+
+async fn check_pending(
+    net: &TestNetwork,
+    indexer: &TestNetwork,
+    deployment: DeploymentId,
+    key: &AllocationKey,
+) -> Result<()> {
+    accrue(net, indexer).await?;
+
+    let allocation = net.subgraph_service_allocation(key.id())?;
+    let block = net.get_block_number_sync()?;
+    let pending = net.rewards_pending_at(key.id(), block)?;
+    let snapshot = net.rewards_snapshot_at(deployment, block)?;
+    let predicted = snapshot.allocation_rewards_at(&allocation, block);
+    eprintln!("  Block {block}: pending {pending} GRT, model {predicted} GRT");
+    eprintln!("  {snapshot:?}");
+
+    assert!(!pending.is_zero(), "allocation should accrue rewards");
+    assert_eq!(pending, predicted);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn collect_pays_predicted_rewards() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Collected Rewards vs Model ===");
+
+    let (indexer, deployment, key) = allocate(&net).await?;
+    let result = check_collections(&net, &indexer, deployment, &key).await;
+    indexer.stop_service(key.id())?;
+    result
+}
+
+async fn check_collections(
+    net: &TestNetwork,
+    indexer: &TestNetwork,
+    deployment: DeploymentId,
+    key: &AllocationKey,
+) -> Result<()> {
+    for round in 1..=2 {
+        accrue(net, indexer).await?;
+        let eligible = match net.contracts.reo {
+            Some(_) => net.reo_is_eligible(indexer.indexer_address)?,
+            None => true,
+        };
+
+        let allocation = net.subgraph_service_allocation(key.id())?;
+        let stake_before = indexer.staked_tokens()?;
+        let receipt = indexer.collect_indexing_rewards(key.id())?;
+        let collected = indexer.staked_tokens()? - stake_before;
+
+        let block = cast_receipt_block(&receipt)?;
+        let snapshot = net.rewards_snapshot_at(deployment, block - 1)?;
+        let predicted = snapshot.predict_collect(&allocation, block, eligible);
+        eprintln!("  Collect {round} in block {block}: {collected} GRT, model {predicted:?}");
+
+        assert!(!collected.is_zero(), "collect should pay rewards");
+        assert_eq!(collected, predicted.claimed);
+    }
+
+    Ok(())
+}
@@ -9,7 +9,7 @@
 //!   - `unstake_idle_tokens` → Cycle 2.2 (Unstake tokens)
 
 use anyhow::Result;
-use local_network_tests::TestNetwork;
+use local_network_tests::{Grt, TestNetwork};
 use serial_test::serial;
 
 fn net() -> Result<TestNetwork> {
@@ -29,12 +29,12 @@ async fn add_stake() -> Result<()> {
     let before = net.staked_tokens()?;
     eprintln!("  Staked before: {before}");
 
-    let amount = "1000000000000000000000"; // 1000 GRT
+    let amount = Grt::from_grt(1000);
     net.stake_tokens(amount)?;
 
     let after = net.staked_tokens()?;
     let delta = after.saturating_sub(before);
-    eprintln!("  Staked after: {after} (+{delta} wei)");
+    eprintln!("  Staked after: {after} (+{delta} GRT)");
 
     assert!(
         after > before,
@@ -58,12 +58,12 @@ async fn unstake_idle_tokens() -> Result<()> {
     eprintln!("=== BaselineTestPlan 2.2: Unstake Tokens ===");
 
     // Add some stake to create idle (unprovisioned) tokens
-    let amount = "1000000000000000000000"; // 1000 GRT
+    let amount = Grt::from_grt(1000);
     net.stake_tokens(amount)?;
 
     let idle = net.idle_stake()?;
     eprintln!("  Idle stake after adding: {idle}");
-    assert!(idle > 0, "Should have idle stake to unstake");
+    assert!(!idle.is_zero(), "Should have idle stake to unstake");
 
     // Unstake the idle portion
     let before = net.staked_tokens()?;
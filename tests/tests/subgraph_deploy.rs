@@ -0,0 +1,67 @@
+//! Fixture Deployment Tests (IPFS + graph-node)
+//!
+//! Fresh deployments built from `fixtures/subgraph/` are uploaded to the
+//! local IPFS node and deployed to graph-node:
+//!
+//!   - `fixture_deployment_syncs` → upload, deploy, synced and healthy
+//!   - `fixture_ids_follow_label` → same label same CID, new label new CID
+//!
+//! Deployments stay on graph-node; they index an event nothing emits.
+
+use anyhow::Result;
+use local_network_tests::subgraph_deploy::FixtureSubgraph;
+use local_network_tests::{DeploymentId, TestNetwork};
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+const SYNC_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[tokio::test]
+async fn fixture_deployment_syncs() -> Result<()> {
+    let net = net()?;
+    let block = net.get_block_number_sync()?;
+    let fixture = FixtureSubgraph {
+        start_block: block,
+        ..FixtureSubgraph::new(format!("deploy-{block}"))
+    };
+
+    let deployment = net.deploy_fixture_subgraph(&fixture).await?;
+    eprintln!(
+        "  Deployed {} as {deployment} ({})",
+        fixture.name(),
+        deployment.bytes32()
+    );
+    let status = net
+        .wait_for_deployment_synced(deployment, SYNC_TIMEOUT)
+        .await?;
+    eprintln!("  {status:?}");
+    assert_eq!(status.health, "healthy");
+    assert_eq!(status.fatal_error, None);
+    assert_eq!(
+        DeploymentId::new(deployment.bytes32()),
+        deployment,
+        "CID and bytes32 forms should round-trip"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fixture_ids_follow_label() -> Result<()> {
+    let net = net()?;
+    let fixture = FixtureSubgraph::new("ids");
+
+    let first = net.upload_fixture_subgraph(&fixture).await?;
+    let again = net.upload_fixture_subgraph(&fixture).await?;
+    let other = net
+        .upload_fixture_subgraph(&FixtureSubgraph::new("ids-other"))
+        .await?;
+    eprintln!("  {first} / {again} / {other}");
+    assert_eq!(first, again);
+    assert_ne!(first, other);
+
+    Ok(())
+}
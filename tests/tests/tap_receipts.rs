@@ -0,0 +1,255 @@
+//! TAP Receipt Validation Tests (BaselineTestPlan Cycle 5.1)
+//!
+//! Queries indexer-service directly with TAP V2 receipts signed by the test
+//! crate instead of the gateway, so receipt checks can be exercised one at a
+//! time:
+//!
+//!   - `valid_receipt_is_accepted` → receipt + attestation round trip
+//!   - `wrong_signer_is_rejected` → signer not authorized for the payer
+//!   - `expired_receipt_is_rejected` → timestamp outside the tolerance window
+//!   - `unfunded_payer_is_rejected` → authorized payer and signer, escrow
+//!     drained by thawing it
+//!   - `replayed_receipt_is_stored_twice` → same receipt (same nonce) sent
+//!     twice; indexer-service accepts both and leaves deduplication to
+//!     tap-agent
+
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use local_network_tests::fixtures::ACCOUNT7;
+use local_network_tests::polling::PollResult;
+use local_network_tests::tap::{IndexerServiceResponse, TapReceipt};
+use local_network_tests::{Address, DeploymentId, Grt, TestNetwork};
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+const QUERY: &str = r#"{ _meta { block { number } } }"#;
+
+/// Per-query fee, matching the gateway's `query_fees_target` (40e-6 GRT).
+const RECEIPT_VALUE: Grt = Grt::from_wei(40_000_000_000_000);
+
+/// Payer with its own escrow and signer, independent of the gateway's. Its
+/// operator role in other tests is unrelated to paying for queries.
+const PAYER_KEY: &str = ACCOUNT7.key;
+const PAYER: Address = ACCOUNT7.address;
+
+/// Escrow deposited by `PAYER` before draining it.
+const ESCROW_DEPOSIT: Grt = Grt::from_grt(1);
+
+/// indexer-service reads payers, signers and escrow balances from the network
+/// subgraph and refreshes them periodically.
+const ESCROW_SYNC_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// An active allocation of the local indexer and its deployment.
+async fn active_allocation(net: &TestNetwork) -> Result<(Address, DeploymentId)> {
+    let allocs = net.get_allocations().await?;
+    let active = allocs
+        .iter()
+        .find(|a| a.is_active())
+        .context("no active allocation to pay for")?;
+    Ok((active.id, active.subgraph_deployment))
+}
+
+async fn receipt(net: &TestNetwork) -> Result<(TapReceipt, DeploymentId)> {
+    let (allocation, deployment) = active_allocation(net).await?;
+    Ok((net.tap_receipt(allocation, RECEIPT_VALUE)?, deployment))
+}
+
+/// A receipt signed by the gateway's signer is accepted and the response is
+/// attested by the allocation the receipt pays.
+#[tokio::test]
+async fn valid_receipt_is_accepted() -> Result<()> {
+    let net = net()?;
+    let (receipt, deployment) = receipt(&net).await?;
+    let signed = net.sign_tap_receipt(&receipt)?;
+
+    let resp = net
+        .query_indexer_service(deployment, &signed, QUERY)
+        .await?;
+    eprintln!("  HTTP {}, error: {:?}", resp.status, resp.error);
+    assert!(
+        resp.is_success(),
+        "valid receipt rejected: {:?}",
+        resp.error
+    );
+
+    let attestation = resp.attestation.context("paid query should be attested")?;
+    attestation.check_payload(
+        resp.request.as_bytes(),
+        resp.graphql_response
+            .as_deref()
+            .unwrap_or_default()
+            .as_bytes(),
+    )?;
+    let verified = net.verify_attestation(&attestation, deployment).await?;
+    eprintln!("  Attested by allocation {}", verified.allocation);
+    assert_eq!(
+        verified.allocation.into_word(),
+        receipt.collection_id,
+        "attestation should be signed by the paid allocation"
+    );
+    assert_eq!(verified.indexer, net.indexer_address);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wrong_signer_is_rejected() -> Result<()> {
+    let net = net()?;
+    let (receipt, deployment) = receipt(&net).await?;
+    let signer = PrivateKeySigner::random();
+    assert!(
+        !net.tap_signer_authorized(receipt.payer, signer.address())?,
+        "random signer {} is authorized for {}",
+        signer.address(),
+        receipt.payer
+    );
+    let signed = receipt.sign(&net.tap_domain(), &signer.to_bytes().to_string())?;
+
+    let resp = net
+        .query_indexer_service(deployment, &signed, QUERY)
+        .await?;
+    eprintln!("  HTTP {}, error: {:?}", resp.status, resp.error);
+    assert!(
+        !resp.is_success(),
+        "receipt from unknown signer was accepted"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn expired_receipt_is_rejected() -> Result<()> {
+    let net = net()?;
+    let (receipt, deployment) = receipt(&net).await?;
+    let signed = net.sign_tap_receipt(&receipt.aged(Duration::from_secs(3600)))?;
+
+    let resp = net
+        .query_indexer_service(deployment, &signed, QUERY)
+        .await?;
+    eprintln!("  HTTP {}, error: {:?}", resp.status, resp.error);
+    assert!(!resp.is_success(), "hour-old receipt was accepted");
+
+    Ok(())
+}
+
+/// Replaying a receipt (same nonce and signature).
+///
+/// indexer-service only checks each receipt on its own (signer, timestamp,
+/// escrow, allocation) and stores it; the uniqueness check runs in tap-agent
+/// when receipts are aggregated into a RAV. So the replay is served, and both
+/// copies land in `tap_horizon_receipts`.
+#[tokio::test]
+async fn replayed_receipt_is_stored_twice() -> Result<()> {
+    let net = net()?;
+    let (receipt, deployment) = receipt(&net).await?;
+    let signed = net.sign_tap_receipt(&receipt)?;
+    let watermark = net.latest_tap_receipt_id()?;
+
+    let first = net
+        .query_indexer_service(deployment, &signed, QUERY)
+        .await?;
+    let second = net
+        .query_indexer_service(deployment, &signed, QUERY)
+        .await?;
+    eprintln!("  First:  HTTP {}, error: {:?}", first.status, first.error);
+    eprintln!(
+        "  Second: HTTP {}, error: {:?}",
+        second.status, second.error
+    );
+    assert!(first.is_success(), "first use rejected: {:?}", first.error);
+    assert!(
+        second.is_success(),
+        "replay rejected by indexer-service: {:?}",
+        second.error
+    );
+
+    let stored: Vec<_> = net
+        .tap_receipts_since(watermark)?
+        .into_iter()
+        .filter(|r| r.nonce == receipt.nonce && r.collection_id == receipt.collection_id)
+        .collect();
+    eprintln!(
+        "  Stored copies of nonce {}: {}",
+        receipt.nonce,
+        stored.len()
+    );
+    assert_eq!(stored.len(), 2, "both uses should be stored: {stored:?}");
+
+    Ok(())
+}
+
+/// Query `deployment` paying `PAYER`'s receipt, signed by `signer`.
+async fn pay_as(
+    net: &TestNetwork,
+    signer: &PrivateKeySigner,
+    allocation: Address,
+    deployment: DeploymentId,
+) -> Result<IndexerServiceResponse> {
+    let mut receipt = net.tap_receipt(allocation, RECEIPT_VALUE)?;
+    receipt.payer = PAYER;
+    let signed = receipt.sign(&net.tap_domain(), &signer.to_bytes().to_string())?;
+    net.query_indexer_service(deployment, &signed, QUERY).await
+}
+
+/// A payer whose signer stays authorized is served while its escrow backs
+/// the receipts, and rejected once the whole escrow is thawing.
+///
+/// Accepting first shows indexer-service knows the payer and signer, so the
+/// later rejection is down to the escrow alone.
+#[tokio::test]
+async fn unfunded_payer_is_rejected() -> Result<()> {
+    let net = net()?;
+    let (allocation, deployment) = active_allocation(&net).await?;
+
+    let signer = PrivateKeySigner::random();
+    net.authorize_tap_signer_as(PAYER_KEY, &signer)?;
+    assert!(net.tap_signer_authorized(PAYER, signer.address())?);
+    net.transfer_grt(PAYER, ESCROW_DEPOSIT)?;
+    net.escrow_deposit_as(PAYER_KEY, ESCROW_DEPOSIT)?;
+    let funded = net.escrow_account(PAYER)?;
+    eprintln!(
+        "  Payer {PAYER}, signer {}: {} GRT available",
+        signer.address(),
+        funded.available()
+    );
+
+    let accepted = net
+        .poll_until(ESCROW_SYNC_TIMEOUT, Duration::from_secs(5), || async {
+            let resp = pay_as(&net, &signer, allocation, deployment).await?;
+            Ok(resp.is_success().then_some(resp))
+        })
+        .await;
+    if let PollResult::TimedOut = accepted {
+        anyhow::bail!("funded payer not accepted within {ESCROW_SYNC_TIMEOUT:?}");
+    }
+    eprintln!("  Funded: accepted");
+
+    net.escrow_thaw_as(PAYER_KEY, funded.balance)?;
+    let drained = net.escrow_account(PAYER)?;
+    eprintln!(
+        "  Thawing {} of {} GRT",
+        drained.tokens_thawing, drained.balance
+    );
+    assert_eq!(drained.available(), Grt::ZERO);
+    assert!(net.tap_signer_authorized(PAYER, signer.address())?);
+
+    let rejected = net
+        .poll_until(ESCROW_SYNC_TIMEOUT, Duration::from_secs(5), || async {
+            let resp = pay_as(&net, &signer, allocation, deployment).await?;
+            Ok((!resp.is_success()).then_some(resp))
+        })
+        .await;
+    match rejected {
+        PollResult::Ready(resp) => {
+            eprintln!("  Drained: HTTP {}, error: {:?}", resp.status, resp.error)
+        }
+        PollResult::TimedOut => anyhow::bail!(
+            "receipts still accepted {ESCROW_SYNC_TIMEOUT:?} after draining the escrow"
+        ),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Thaw Request Tests (BaselineTestPlan Cycle 3.3-3.4, multiple requests)
+//!
+//! Thaw requests are fulfilled in creation order and fulfilment stops at the
+//! first request still thawing. These tests inspect the request list instead
+//! of waiting a fixed time, and move chain time exactly to each expiry.
+//!
+//!   - `thaw_requests_match_subgraph` → on-chain list vs `ThawRequest` entities
+//!   - `interleaved_thaws_fulfil_in_order` → two overlapping thaws, deprovisioned one at a time
+
+use anyhow::Result;
+use local_network_tests::fixtures::INDEX_TIMEOUT;
+use local_network_tests::polling::PollResult;
+use local_network_tests::thaw::{ThawRequestType, ready_thaw_requests};
+use local_network_tests::{B256, Grt, TestNetwork};
+use serial_test::serial;
+use std::collections::HashSet;
+use std::time::Duration;
+
+fn net() -> Result<TestNetwork> {
+    TestNetwork::from_default_env()
+}
+
+const THAW_AMOUNT: Grt = Grt::from_grt(500);
+
+/// Shortest provision thawing period for interleaving two thaws: B starts
+/// halfway through A's, so it expires this long / 2 seconds after A.
+const MIN_THAWING_PERIOD: u64 = 60;
+
+/// Every pending on-chain provision thaw request is indexed.
+#[tokio::test]
+async fn thaw_requests_match_subgraph() -> Result<()> {
+    let net = net()?;
+    let onchain: HashSet<B256> = net
+        .provision_thaw_requests()?
+        .iter()
+        .map(|r| r.id)
+        .collect();
+    eprintln!("  {} pending provision thaw request(s)", onchain.len());
+
+    let result = net
+        .poll_until(INDEX_TIMEOUT, Duration::from_secs(2), || async {
+            let indexed: HashSet<B256> = net
+                .query_thaw_requests(net.indexer_address)
+                .await?
+                .into_iter()
+                .map(|r| r.id)
+                .collect();
+            Ok(onchain.is_subset(&indexed).then_some(indexed))
+        })
+        .await;
+    match result {
+        PollResult::Ready(indexed) => {
+            eprintln!("  {} unfulfilled thaw request(s) indexed", indexed.len());
+            Ok(())
+        }
+        PollResult::TimedOut => anyhow::bail!("thaw requests not indexed within {INDEX_TIMEOUT:?}"),
+    }
+}
+
+/// Thaw A, then B halfway through A's thawing period. At A's expiry only A
+/// is ready; at B's expiry B is. Each deprovision releases one request.
+/// A shorter provision thawing period is raised to `MIN_THAWING_PERIOD` for
+/// the test, so B always expires well after A.
+#[tokio::test]
+#[serial]
+async fn interleaved_thaws_fulfil_in_order() -> Result<()> {
+    let net = net()?;
+    eprintln!("=== Interleaved Thaw Requests ===");
+
+    // Start from an empty list so the new requests are the only ones
+    if let Some(last) = net.provision_thaw_requests()?.last() {
+        net.advance_time_to(last.thawing_until).await?;
+        net.provision_deprovision_ready()?;
+    }
+    anyhow::ensure!(
+        net.provision_thaw_requests()?.is_empty(),
+        "pending thaw requests could not be cleared"
+    );
+
+    net.stake_tokens(THAW_AMOUNT + THAW_AMOUNT)?;
+    net.provision_add(THAW_AMOUNT + THAW_AMOUNT)?;
+    let original = net.provision()?;
+    let thawing_period = original.thawing_period.max(MIN_THAWING_PERIOD);
+    if thawing_period != original.thawing_period {
+        eprintln!(
+            "  Thawing period {}s → {thawing_period}s",
+            original.thawing_period
+        );
+        net.set_provision_parameters(original.max_verifier_cut, thawing_period)?;
+        net.accept_provision_parameters()?;
+    }
+    assert_eq!(net.provision()?.thawing_period, thawing_period);
+
+    net.provision_thaw(THAW_AMOUNT)?;
+    net.advance_time(thawing_period / 2).await?;
+    net.provision_thaw(THAW_AMOUNT)?;
+
+    let requests = net.provision_thaw_requests()?;
+    for r in &requests {
+        eprintln!("  {}: {} GRT until {}", r.id, r.tokens, r.thawing_until);
+    }
+    assert_eq!(requests.len(), 2);
+    let (a, b) = (requests[0], requests[1]);
+    assert_eq!(a.tokens, THAW_AMOUNT);
+    assert_eq!(b.tokens, THAW_AMOUNT);
+    assert!(a.thawing_until < b.thawing_until);
+
+    let now = net.get_block_timestamp()?;
+    assert_eq!(ready_thaw_requests(&requests, now), 0);
+    assert_eq!(
+        net.thawed_tokens(ThawRequestType::Provision, net.indexer_address)?,
+        Grt::ZERO
+    );
+
+    // --- A expires ---
+    let now = net.advance_time_to(a.thawing_until).await?;
+    let thawed = net.thawed_tokens(ThawRequestType::Provision, net.indexer_address)?;
+    eprintln!("  At {now}: {thawed} GRT thawed");
+    let idle_before = net.idle_stake()?;
+    let fulfilled = net.provision_deprovision_ready()?;
+    let released = net.idle_stake()? - idle_before;
+    eprintln!("  Deprovisioned {fulfilled} request(s), {released} GRT back to idle stake");
+    let remaining = net.provision_thaw_requests()?;
+    assert!(
+        now < b.thawing_until,
+        "advanced to {now}, past B's expiry {}",
+        b.thawing_until
+    );
+    assert_eq!(fulfilled, 1);
+    assert_eq!(released, THAW_AMOUNT);
+    assert_eq!(remaining.first().map(|r| r.id), Some(b.id));
+
+    // --- B expires ---
+    net.advance_time_to(b.thawing_until).await?;
+    net.provision_deprovision_ready()?;
+    let remaining = net.provision_thaw_requests()?;
+    assert!(
+        remaining.is_empty(),
+        "all thaw requests should be fulfilled: {remaining:?}"
+    );
+
+    eprintln!("  Idle stake: {}", net.idle_stake()?);
+
+    if thawing_period != original.thawing_period {
+        net.set_provision_parameters(original.max_verifier_cut, original.thawing_period)?;
+        net.accept_provision_parameters()?;
+    }
+
+    Ok(())
+}